@@ -1,7 +1,11 @@
-use crate::service::storage::storage_get_str;
+use crate::network_profile::{network_profile, NodeConfig};
+use crate::service::storage::{
+    storage_get, storage_get_str, storage_remove, storage_set, storage_set_str,
+};
+use algonaut::{algod::v2::Algod, indexer::v2::Indexer};
 use anyhow::{anyhow, Error, Result};
 use mbase::{
-    dependencies::{network, DataType, Network},
+    dependencies::DataType,
     models::{
         capi_deps::{CapiAddress, CapiAssetDaoDeps},
         funds::FundsAssetId,
@@ -9,27 +13,49 @@ use mbase::{
 };
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::{convert::TryInto, str::FromStr};
+use std::{collections::HashMap, convert::TryInto, str::FromStr};
 
-/// URL determined by environment variable
-pub fn explorer_base_url<'a>() -> &'a str {
-    explorer_base_url_for_net(&network())
+/// Algod client used by the providers.
+/// In tests, this points to the in-process fake node if one is running - see [crate::fake_node].
+pub fn algod() -> Algod {
+    #[cfg(test)]
+    if let Some(algod) = crate::fake_node::algod_override() {
+        return algod;
+    }
+    if let Some(node) = selected_profile_node() {
+        match Algod::new(node.algod_url, "") {
+            Ok(algod) => return algod,
+            Err(e) => log::error!("Couldn't create algod for network profile: {e:?}"),
+        }
+    }
+    mbase::dependencies::algod()
 }
 
-pub fn explorer_base_url_for_net<'a>(net: &Network) -> &'a str {
-    match net {
-        // No explorer for private network - we just test that it opens and searches
-        Network::Private | Network::SandboxPrivate | Network::Test => {
-            "https://testnet.algoexplorer.io/"
+/// Indexer client used by the providers. Same overrides as [algod].
+pub fn indexer() -> Indexer {
+    #[cfg(test)]
+    if let Some(indexer) = crate::fake_node::indexer_override() {
+        return indexer;
+    }
+    if let Some(node) = selected_profile_node() {
+        match Indexer::new(node.indexer_url) {
+            Ok(indexer) => return indexer,
+            Err(e) => log::error!("Couldn't create indexer for network profile: {e:?}"),
         }
     }
+    mbase::dependencies::indexer()
 }
 
-pub fn funds_asset_specs() -> Result<FundsAssetSpecs> {
-    Ok(FundsAssetSpecs {
-        id: funds_asset_id()?,
-        decimals: 6,
-    })
+/// The node of the network profile selected at runtime, if any (otherwise the build's network config is used)
+fn selected_profile_node() -> Option<NodeConfig> {
+    match network_profile() {
+        Ok(profile) if profile.selected => profile.node(),
+        Ok(_) => None,
+        Err(e) => {
+            log::error!("Couldn't read network profile, using build's network: {e:?}");
+            None
+        }
+    }
 }
 
 pub fn capi_deps() -> Result<CapiAssetDaoDeps> {
@@ -40,16 +66,30 @@ pub fn capi_deps() -> Result<CapiAssetDaoDeps> {
 }
 
 /// This is WASM-only as the decimals are needed only for formatting - we don't need this in core.
+/// Retrieved from the asset's params, see [crate::service::funds_asset_specs].
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FundsAssetSpecs {
     pub id: FundsAssetId,
-    // Technically (algonaut) decimals is u64, but Decimal wants u32 and realistically u32 is enough. + we currently hardcode this.
+    // Technically (algonaut) decimals is u64, but Decimal wants u32 and realistically u32 is enough.
     pub decimals: u32,
+    pub unit_name: String,
+    pub name: String,
 }
 
 pub fn funds_asset_id() -> Result<FundsAssetId> {
     // init_log().unwrap(); // in case it's needed to debug first access - currently logs are initialized about at the same time as first access
 
+    let profile = network_profile()?;
+    if let Some(id) = profile.funds_asset_id {
+        return Ok(id);
+    }
+    if !profile.uses_build_env() {
+        return Err(anyhow!(
+            "No funds asset id for network profile: {:?}",
+            profile.id
+        ));
+    }
+
     let str = if is_runtime_env()? {
         storage_get_str("FUNDS_ASSET_ID")?
     } else {
@@ -65,6 +105,17 @@ pub fn funds_asset_id() -> Result<FundsAssetId> {
 pub fn capi_address() -> Result<CapiAddress> {
     // init_log().unwrap(); // in case it's needed to debug first access - currently logs are initialized about at the same time as first access
 
+    let profile = network_profile()?;
+    if let Some(address) = profile.capi_address {
+        return Ok(address);
+    }
+    if !profile.uses_build_env() {
+        return Err(anyhow!(
+            "No capi address for network profile: {:?}",
+            profile.id
+        ));
+    }
+
     let str = if is_runtime_env()? {
         storage_get_str("CAPI_ADDRESS")?
     } else {
@@ -77,11 +128,70 @@ pub fn capi_address() -> Result<CapiAddress> {
     Ok(CapiAddress(str.parse().map_err(Error::msg)?))
 }
 
+/// Local storage key for the data type set at runtime (for all providers)
+const DATA_TYPE_KEY: &str = "DATA_TYPE";
+/// Local storage key for per provider data types (provider name -> data type), these take precedence over [DATA_TYPE_KEY]
+const PROVIDER_DATA_TYPES_KEY: &str = "PROVIDER_DATA_TYPES";
+
+/// The data type set at runtime (local storage) has priority over the build-time DATA_TYPE,
+/// this way a single build can be switched between real and mock data (e.g. by QA).
 pub fn data_type() -> Result<DataType> {
-    let str = option_env!("DATA_TYPE").ok_or_else(|| anyhow!("Please pass DATA_TYPE"))?;
+    let str = match storage_get_str(DATA_TYPE_KEY)? {
+        Some(str) => str,
+        None => option_env!("DATA_TYPE")
+            .map(|s| s.to_owned()) // key needs to be a literal here
+            .ok_or_else(|| anyhow!("Please pass DATA_TYPE"))?,
+    };
 
     log::debug!("Data type: {:?}", str);
 
+    parse_data_type(&str)
+}
+
+/// Providers for which a data type was set individually. Invalid data types are skipped.
+pub fn provider_data_types() -> Result<HashMap<String, DataType>> {
+    let strs: HashMap<String, String> = storage_get(PROVIDER_DATA_TYPES_KEY)?.unwrap_or_default();
+    Ok(strs
+        .into_iter()
+        .filter_map(|(provider, str)| match parse_data_type(&str) {
+            Ok(data_type) => Some((provider, data_type)),
+            Err(e) => {
+                log::warn!("Skipping data type of provider: {provider}: {e:?}");
+                None
+            }
+        })
+        .collect())
+}
+
+/// None: use the build-time data type
+pub fn set_data_type(data_type: Option<&str>) -> Result<()> {
+    match data_type {
+        Some(str) => {
+            // validate before saving
+            parse_data_type(str)?;
+            storage_set_str(DATA_TYPE_KEY, str)
+        }
+        None => storage_remove(DATA_TYPE_KEY),
+    }
+}
+
+/// None: the provider uses the global data type again
+pub fn set_provider_data_type(provider: &str, data_type: Option<&str>) -> Result<()> {
+    let mut strs: HashMap<String, String> =
+        storage_get(PROVIDER_DATA_TYPES_KEY)?.unwrap_or_default();
+    match data_type {
+        Some(str) => {
+            parse_data_type(str)?;
+            strs.insert(provider.to_owned(), str.to_owned());
+        }
+        None => {
+            strs.remove(provider);
+        }
+    }
+    storage_set(PROVIDER_DATA_TYPES_KEY, &strs)
+}
+
+fn parse_data_type(str: &str) -> Result<DataType> {
     match str {
         "real" => Ok(DataType::Real),
         "mock" => Ok(DataType::Mock),
@@ -137,3 +247,49 @@ fn is_runtime_env() -> Result<bool> {
         Err(anyhow!("Invalid RUNTIME_ENV value: {str}"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fake_node::fixtures::CAPI_ADDRESS,
+        network_profile::{set_network_profile, NetworkProfileId, NetworkProfileSettings},
+    };
+
+    fn select_network(network: NetworkProfileId, capi_address: Option<&str>) {
+        set_network_profile(&NetworkProfileSettings {
+            network,
+            explorer: None,
+            funds_asset_id: None,
+            capi_address: capi_address.map(|s| s.to_owned()),
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn selected_profile_node_is_used_instead_of_the_build_network() {
+        // nothing selected: the build's network config
+        assert!(selected_profile_node().is_none());
+
+        select_network(NetworkProfileId::MainNet, Some(CAPI_ADDRESS));
+
+        let node = selected_profile_node().unwrap();
+        assert_eq!("https://mainnet-api.algonode.cloud", node.algod_url);
+        assert_eq!("https://mainnet-idx.algonode.cloud", node.indexer_url);
+    }
+
+    #[test]
+    fn selected_local_profile_uses_the_build_network() {
+        select_network(NetworkProfileId::Local, None);
+
+        assert!(selected_profile_node().is_none());
+    }
+
+    #[test]
+    fn selected_profile_funds_asset_and_capi_address_are_used_instead_of_the_build_env() {
+        select_network(NetworkProfileId::TestNet, Some(CAPI_ADDRESS));
+
+        assert_eq!(FundsAssetId(10458941), funds_asset_id().unwrap());
+        assert_eq!(CAPI_ADDRESS, capi_address().unwrap().0.to_string());
+    }
+}
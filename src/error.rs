@@ -11,6 +11,10 @@ use crate::{
             },
         },
     },
+    service::{
+        expected_txs::UnexpectedSignedTxError, node_error::service_error_to_fr_error,
+        passthrough::InvalidPassthroughError, tx_costs::NotEnoughAlgosError,
+    },
 };
 use algonaut::error::ServiceError;
 use mbase::state::app_state::ApplicationLocalStateError;
@@ -20,17 +24,55 @@ use wasm_bindgen::JsValue;
 
 /// "Fr": frontend
 /// All errors that can be returned to JS
+/// The (camelCase) variant names are the error codes JS maps to messages: don't rename them.
 #[derive(Tsify, Debug, Clone, Serialize)]
 #[tsify(into_wasm_abi)]
 #[serde(rename_all(serialize = "camelCase"))]
 pub enum FrError {
     NotEnoughAlgos,
-    NotEnoughFundsAsset { to_buy: String },
+    NotEnoughFundsAsset {
+        to_buy: String,
+    },
     Validation(ValidationError),
     CreateDaoValidations(CreateAssetsInputErrors),
     UpdateDaoDataValidations(ValidateUpateDataInputErrors),
     AddTeamMemberValidations(AddTeamMemberInputErrors),
     Validations(HashMap<String, ValidationError>),
+    /// The frontend submitted a tx different to the ones handed out for signing
+    UnexpectedSignedTx(UnexpectedSignedTxError),
+    /// The passthrough data returned by the frontend was modified (or is from another session)
+    InvalidPassthrough,
+    /// The txs would leave an account below its min balance
+    BelowMinBalance {
+        address: String,
+        balance: String,
+        min_balance: String,
+    },
+    /// An asset transfer exceeds the sender's holdings (base units)
+    NotEnoughAsset {
+        amount: String,
+        holdings: String,
+    },
+    /// An asset transfer's sender or receiver isn't opted in to the asset
+    AssetNotOptedIn {
+        asset_id: String,
+        address: String,
+    },
+    NotOptedInToApp,
+    /// The app logic (TEAL) rejected the tx. pc: program counter where it failed, if known.
+    AppRejected {
+        reason: String,
+        pc: Option<String>,
+    },
+    /// The node returned an error that isn't mapped to a specific case
+    Node {
+        status: Option<u16>,
+        message: String,
+    },
+    /// The node couldn't be reached
+    Network {
+        message: String,
+    },
     Internal(String), // Things we can't explain to users. Text is for developers (can be forwarded with error reporting).
     Msg(String), // this is temporary / last resort: we expect to map all the errors to localized error messages in js
 }
@@ -96,13 +138,32 @@ impl From<FrError> for JsValue {
 
 impl From<anyhow::Error> for FrError {
     fn from(e: anyhow::Error) -> Self {
+        // the errors may have been wrapped with context (e.g. by core), so we search the whole chain
+        if e.chain().any(|cause| cause.is::<InvalidPassthroughError>()) {
+            return FrError::InvalidPassthrough;
+        }
+        if e.chain().any(|cause| cause.is::<NotEnoughAlgosError>()) {
+            return FrError::NotEnoughAlgos;
+        }
+        if let Some(e) = e
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<ServiceError>())
+        {
+            return service_error_to_fr_error(e);
+        }
+        if let Some(e) = e
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<UnexpectedSignedTxError>())
+        {
+            return FrError::UnexpectedSignedTx(e.clone());
+        }
         FrError::Msg(e.to_string())
     }
 }
 
 impl From<ServiceError> for FrError {
     fn from(e: ServiceError) -> Self {
-        FrError::Msg(e.to_string())
+        service_error_to_fr_error(&e)
     }
 }
 
@@ -138,6 +199,55 @@ impl From<Box<dyn Error + 'static>> for FrError {
 
 impl From<ApplicationLocalStateError<'static>> for FrError {
     fn from(e: ApplicationLocalStateError) -> Self {
-        FrError::Msg(format!("{e:?}"))
+        match e {
+            ApplicationLocalStateError::NotOptedIn => FrError::NotOptedInToApp,
+            _ => FrError::Internal(format!("{e:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_node::fixtures::CREATOR;
+    use algonaut::core::MicroAlgos;
+    use anyhow::Context;
+
+    fn wrapped<E: std::error::Error + Send + Sync + 'static>(e: E) -> anyhow::Error {
+        Err::<(), _>(e)
+            .context("Error submitting the txs")
+            .context("Error in core")
+            .unwrap_err()
+    }
+
+    #[test]
+    fn from_anyhow_finds_the_errors_wrapped_with_context() {
+        assert!(matches!(
+            FrError::from(wrapped(InvalidPassthroughError)),
+            FrError::InvalidPassthrough
+        ));
+        assert!(matches!(
+            FrError::from(wrapped(NotEnoughAlgosError {
+                address: CREATOR.parse().unwrap(),
+                available: MicroAlgos(1),
+                cost: MicroAlgos(2),
+            })),
+            FrError::NotEnoughAlgos
+        ));
+        assert!(matches!(
+            FrError::from(wrapped(UnexpectedSignedTxError::Count {
+                expected: 2,
+                received: 1,
+            })),
+            FrError::UnexpectedSignedTx(UnexpectedSignedTxError::Count { .. })
+        ));
+    }
+
+    #[test]
+    fn from_anyhow_falls_back_to_msg() {
+        assert!(matches!(
+            FrError::from(anyhow::anyhow!("Something")),
+            FrError::Msg(msg) if msg == "Something"
+        ));
     }
 }
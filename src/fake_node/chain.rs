@@ -0,0 +1,545 @@
+use algonaut::transaction::SignedTransaction;
+use data_encoding::BASE64;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Sandbox genesis - the value isn't validated by the providers, it just has to be well formed
+const GENESIS_HASH: &str = "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=";
+const GENESIS_ID: &str = "sandnet-v1";
+const CONSENSUS_VERSION: &str =
+    "https://github.com/algorandfoundation/specs/tree/d5ac876d7ede07367dbaa26e149aa42589aac1f7";
+const ZERO_ADDRESS: &str = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAY5HFKQ";
+const MIN_FEE: u64 = 1_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TealValueFake {
+    Bytes(Vec<u8>),
+    Uint(u64),
+}
+
+#[derive(Debug, Clone, Default)]
+struct FakeAccount {
+    micro_algos: u64,
+    /// asset id -> amount
+    assets: HashMap<u64, u64>,
+    /// app id -> local state
+    local_state: HashMap<u64, Vec<(Vec<u8>, TealValueFake)>>,
+}
+
+#[derive(Debug, Clone)]
+struct FakeApp {
+    creator: String,
+    global_state: Vec<(Vec<u8>, TealValueFake)>,
+}
+
+#[derive(Debug, Clone)]
+struct FakeAsset {
+    creator: String,
+    decimals: u64,
+    total: u64,
+    name: String,
+    unit_name: String,
+    metadata_hash: Option<Vec<u8>>,
+}
+
+/// Indexer transaction, in the indexer's JSON format
+#[derive(Debug, Clone)]
+pub struct FakeTx(pub Value);
+
+impl FakeTx {
+    pub fn payment(id: &str, sender: &str, receiver: &str, amount: u64, round_time: i64) -> FakeTx {
+        FakeTx::base(id, sender, "pay", round_time).with(
+            "payment-transaction",
+            json!({ "amount": amount, "receiver": receiver, "close-amount": 0 }),
+        )
+    }
+
+    pub fn asset_transfer(
+        id: &str,
+        sender: &str,
+        receiver: &str,
+        asset_id: u64,
+        amount: u64,
+        round_time: i64,
+    ) -> FakeTx {
+        FakeTx::base(id, sender, "axfer", round_time).with(
+            "asset-transfer-transaction",
+            json!({ "amount": amount, "asset-id": asset_id, "receiver": receiver, "close-amount": 0 }),
+        )
+    }
+
+    pub fn app_call(
+        id: &str,
+        sender: &str,
+        app_id: u64,
+        args: &[&[u8]],
+        round_time: i64,
+    ) -> FakeTx {
+        let args: Vec<String> = args.iter().map(|a| BASE64.encode(a)).collect();
+        FakeTx::base(id, sender, "appl", round_time).with(
+            "application-transaction",
+            json!({
+                "application-id": app_id,
+                "application-args": args,
+                "on-completion": "noop",
+                "accounts": [],
+                "foreign-apps": [],
+                "foreign-assets": [],
+                "global-state-schema": { "num-byte-slice": 0, "num-uint": 0 },
+                "local-state-schema": { "num-byte-slice": 0, "num-uint": 0 },
+            }),
+        )
+    }
+
+    /// Sets (or overrides) a field, e.g. "note", "group" or "inner-txns"
+    pub fn with(mut self, key: &str, value: Value) -> FakeTx {
+        if let Value::Object(map) = &mut self.0 {
+            map.insert(key.to_owned(), value);
+        }
+        self
+    }
+
+    fn base(id: &str, sender: &str, tx_type: &str, round_time: i64) -> FakeTx {
+        FakeTx(json!({
+            "id": id,
+            "sender": sender,
+            "tx-type": tx_type,
+            "fee": MIN_FEE,
+            "round-time": round_time,
+            "confirmed-round": 1,
+            "first-valid": 1,
+            "last-valid": 1001,
+            "intra-round-offset": 0,
+            "genesis-hash": GENESIS_HASH,
+            "genesis-id": GENESIS_ID,
+            "sender-rewards": 0,
+            "receiver-rewards": 0,
+            "close-rewards": 0,
+            "signature": {},
+        }))
+    }
+
+    fn str_field(&self, pointer: &str) -> Option<&str> {
+        self.0.pointer(pointer).and_then(|v| v.as_str())
+    }
+
+    fn u64_field(&self, pointer: &str) -> Option<u64> {
+        self.0.pointer(pointer).and_then(|v| v.as_u64())
+    }
+
+    fn round_time(&self) -> i64 {
+        self.0
+            .get("round-time")
+            .and_then(|v| v.as_i64())
+            .unwrap_or_default()
+    }
+
+    /// Like the indexer, a tx involves an address if the tx or one of its inner txs does
+    fn involves(&self, address: &str) -> bool {
+        self.any_tx(&|tx| {
+            let field = |pointer| tx.pointer(pointer).and_then(|v| v.as_str());
+            field("/sender") == Some(address)
+                || field("/payment-transaction/receiver") == Some(address)
+                || field("/asset-transfer-transaction/receiver") == Some(address)
+        })
+    }
+
+    /// Like the indexer, a tx transfers an asset if the tx or one of its inner txs does
+    fn transfers_asset(&self, asset_id: u64) -> bool {
+        self.any_tx(&|tx| {
+            tx.pointer("/asset-transfer-transaction/asset-id")
+                .and_then(|v| v.as_u64())
+                == Some(asset_id)
+        })
+    }
+
+    fn any_tx(&self, f: &dyn Fn(&Value) -> bool) -> bool {
+        fn any(tx: &Value, f: &dyn Fn(&Value) -> bool) -> bool {
+            f(tx)
+                || tx
+                    .get("inner-txns")
+                    .and_then(|inner| inner.as_array())
+                    .map_or(false, |inner| inner.iter().any(|tx| any(tx, f)))
+        }
+        any(&self.0, f)
+    }
+}
+
+/// Filters supported by the fake indexer's transaction search
+#[derive(Debug, Clone, Default)]
+pub(super) struct TxQuery {
+    pub address: Option<String>,
+    pub asset_id: Option<u64>,
+    pub application_id: Option<u64>,
+    pub tx_type: Option<String>,
+    pub note_prefix: Option<String>,
+    pub after_time: Option<i64>,
+    pub before_time: Option<i64>,
+    pub limit: Option<usize>,
+    pub next: Option<usize>,
+}
+
+/// In-memory chain state served by [super::FakeNode]
+#[derive(Debug, Clone, Default)]
+pub struct FakeChain {
+    round: u64,
+    accounts: HashMap<String, FakeAccount>,
+    apps: HashMap<u64, FakeApp>,
+    assets: HashMap<u64, FakeAsset>,
+    txs: Vec<FakeTx>,
+    submitted: Vec<SignedTransaction>,
+    /// (asset id, currency) -> price of 1 display unit
+    prices: HashMap<(u64, String), String>,
+}
+
+impl FakeChain {
+    pub fn new() -> FakeChain {
+        FakeChain {
+            round: 1,
+            ..FakeChain::default()
+        }
+    }
+
+    pub fn set_round(&mut self, round: u64) {
+        self.round = round;
+    }
+
+    pub fn set_algos(&mut self, address: &str, micro_algos: u64) {
+        self.account_mut(address).micro_algos = micro_algos;
+    }
+
+    pub fn set_asset_holding(&mut self, address: &str, asset_id: u64, amount: u64) {
+        self.account_mut(address).assets.insert(asset_id, amount);
+    }
+
+    pub fn add_asset(
+        &mut self,
+        asset_id: u64,
+        creator: &str,
+        unit_name: &str,
+        decimals: u64,
+        total: u64,
+    ) {
+        self.assets.insert(
+            asset_id,
+            FakeAsset {
+                creator: creator.to_owned(),
+                decimals,
+                total,
+                name: unit_name.to_owned(),
+                unit_name: unit_name.to_owned(),
+                metadata_hash: None,
+            },
+        );
+    }
+
+    /// The asset has to be added first
+    pub fn set_asset_metadata_hash(&mut self, asset_id: u64, hash: &[u8]) {
+        if let Some(asset) = self.assets.get_mut(&asset_id) {
+            asset.metadata_hash = Some(hash.to_vec());
+        }
+    }
+
+    pub fn add_app(&mut self, app_id: u64, creator: &str) {
+        self.apps.insert(
+            app_id,
+            FakeApp {
+                creator: creator.to_owned(),
+                global_state: vec![],
+            },
+        );
+    }
+
+    /// Raw TEAL key-values - the keys have to match the ones the DAO app uses
+    pub fn set_app_global_state(&mut self, app_id: u64, state: Vec<(Vec<u8>, TealValueFake)>) {
+        self.apps
+            .entry(app_id)
+            .or_insert_with(|| FakeApp {
+                creator: ZERO_ADDRESS.to_owned(),
+                global_state: vec![],
+            })
+            .global_state = state;
+    }
+
+    /// Opts the account in to the app and sets its local state
+    pub fn set_app_local_state(
+        &mut self,
+        address: &str,
+        app_id: u64,
+        state: Vec<(Vec<u8>, TealValueFake)>,
+    ) {
+        self.account_mut(address).local_state.insert(app_id, state);
+    }
+
+    pub fn add_tx(&mut self, tx: FakeTx) {
+        self.txs.push(tx);
+    }
+
+    /// Price served by the fake price API. `price` is a decimal string, e.g. "0.93"
+    pub fn set_price(&mut self, asset_id: u64, currency: &str, price: &str) {
+        self.prices
+            .insert((asset_id, currency.to_owned()), price.to_owned());
+    }
+
+    /// Transactions submitted to the fake algod, in submission order
+    pub fn submitted(&self) -> &[SignedTransaction] {
+        &self.submitted
+    }
+
+    pub(super) fn submit(&mut self, txs: Vec<SignedTransaction>) {
+        self.submitted.extend(txs);
+        self.round += 1;
+    }
+
+    pub(super) fn price(&self, asset_id: u64, currency: &str) -> Option<&String> {
+        self.prices.get(&(asset_id, currency.to_owned()))
+    }
+
+    fn account_mut(&mut self, address: &str) -> &mut FakeAccount {
+        self.accounts.entry(address.to_owned()).or_default()
+    }
+
+    pub(super) fn status_json(&self) -> Value {
+        json!({
+            "catchup-time": 0,
+            "last-round": self.round,
+            "last-version": CONSENSUS_VERSION,
+            "next-version": CONSENSUS_VERSION,
+            "next-version-round": self.round + 1,
+            "next-version-supported": true,
+            "stopped-at-unsupported-round": false,
+            "time-since-last-round": 0,
+        })
+    }
+
+    pub(super) fn suggested_params_json(&self) -> Value {
+        json!({
+            "consensus-version": CONSENSUS_VERSION,
+            "fee": 0,
+            "genesis-hash": GENESIS_HASH,
+            "genesis-id": GENESIS_ID,
+            "last-round": self.round,
+            "min-fee": MIN_FEE,
+        })
+    }
+
+    pub(super) fn account_json(&self, address: &str) -> Value {
+        let default = FakeAccount::default();
+        let account = self.accounts.get(address).unwrap_or(&default);
+
+        let assets: Vec<Value> = account
+            .assets
+            .iter()
+            .map(|(id, amount)| {
+                json!({
+                    "amount": amount,
+                    "asset-id": id,
+                    "creator": self.assets.get(id).map(|a| a.creator.as_str()).unwrap_or(ZERO_ADDRESS),
+                    "is-frozen": false,
+                })
+            })
+            .collect();
+
+        let local_states: Vec<Value> = account
+            .local_state
+            .iter()
+            .map(|(id, state)| local_state_json(*id, state))
+            .collect();
+
+        let created_apps: Vec<Value> = self
+            .apps
+            .iter()
+            .filter(|(_, app)| app.creator == address)
+            .map(|(id, app)| app_json(*id, app))
+            .collect();
+
+        let created_assets: Vec<Value> = self
+            .assets
+            .iter()
+            .filter(|(_, asset)| asset.creator == address)
+            .map(|(id, asset)| asset_json(*id, asset))
+            .collect();
+
+        let min_balance = 100_000
+            + 100_000 * (account.assets.len() as u64)
+            + 100_000 * (account.local_state.len() as u64);
+
+        json!({
+            "address": address,
+            "amount": account.micro_algos,
+            "amount-without-pending-rewards": account.micro_algos,
+            "apps-local-state": local_states,
+            "apps-total-schema": { "num-byte-slice": 0, "num-uint": 0 },
+            "assets": assets,
+            "created-apps": created_apps,
+            "created-assets": created_assets,
+            "min-balance": min_balance,
+            "pending-rewards": 0,
+            "reward-base": 0,
+            "rewards": 0,
+            "round": self.round,
+            "status": "Offline",
+            "total-apps-opted-in": account.local_state.len(),
+            "total-assets-opted-in": account.assets.len(),
+            "total-created-apps": created_apps.len(),
+            "total-created-assets": created_assets.len(),
+        })
+    }
+
+    pub(super) fn account_application_json(&self, address: &str, app_id: u64) -> Option<Value> {
+        let state = self.accounts.get(address)?.local_state.get(&app_id)?;
+        Some(json!({
+            "app-local-state": local_state_json(app_id, state),
+            "round": self.round,
+        }))
+    }
+
+    pub(super) fn account_asset_json(&self, address: &str, asset_id: u64) -> Option<Value> {
+        let amount = self.accounts.get(address)?.assets.get(&asset_id)?;
+        Some(json!({
+            "asset-holding": { "amount": amount, "asset-id": asset_id, "is-frozen": false },
+            "round": self.round,
+        }))
+    }
+
+    pub(super) fn app_json(&self, app_id: u64) -> Option<Value> {
+        self.apps.get(&app_id).map(|app| app_json(app_id, app))
+    }
+
+    pub(super) fn asset_json(&self, asset_id: u64) -> Option<Value> {
+        self.assets
+            .get(&asset_id)
+            .map(|asset| asset_json(asset_id, asset))
+    }
+
+    pub(super) fn round(&self) -> u64 {
+        self.round
+    }
+
+    pub(super) fn asset_balances_json(&self, asset_id: u64) -> Value {
+        let balances: Vec<Value> = self
+            .accounts
+            .iter()
+            .filter_map(|(address, account)| {
+                account.assets.get(&asset_id).map(|amount| {
+                    json!({
+                        "address": address,
+                        "amount": amount,
+                        "is-frozen": false,
+                        "deleted": false,
+                        "opted-in-at-round": 1,
+                    })
+                })
+            })
+            .collect();
+        json!({ "balances": balances, "current-round": self.round })
+    }
+
+    pub(super) fn accounts_with_app_json(&self, app_id: Option<u64>) -> Value {
+        let accounts: Vec<Value> = self
+            .accounts
+            .iter()
+            .filter(|(_, account)| match app_id {
+                Some(id) => account.local_state.contains_key(&id),
+                None => true,
+            })
+            .map(|(address, _)| self.account_json(address))
+            .collect();
+        json!({ "accounts": accounts, "current-round": self.round })
+    }
+
+    pub(super) fn txs_json(&self, query: &TxQuery) -> Value {
+        let mut matching: Vec<&FakeTx> = self
+            .txs
+            .iter()
+            .filter(|tx| {
+                query.address.as_ref().map_or(true, |a| tx.involves(a))
+                    && query.asset_id.map_or(true, |id| tx.transfers_asset(id))
+                    && query.application_id.map_or(true, |id| {
+                        tx.u64_field("/application-transaction/application-id") == Some(id)
+                    })
+                    && query
+                        .tx_type
+                        .as_ref()
+                        .map_or(true, |t| tx.str_field("/tx-type") == Some(t))
+                    && query.note_prefix.as_ref().map_or(true, |p| {
+                        tx.str_field("/note")
+                            .map_or(false, |n| n.starts_with(p.as_str()))
+                    })
+                    && query.after_time.map_or(true, |t| tx.round_time() >= t)
+                    && query.before_time.map_or(true, |t| tx.round_time() < t)
+            })
+            .collect();
+        // most recent first, like the indexer (stable: txs of the same round keep the order they were added in)
+        matching.sort_by_key(|tx| std::cmp::Reverse(tx.round_time()));
+
+        let start = query.next.unwrap_or(0).min(matching.len());
+        let end = query
+            .limit
+            .map_or(matching.len(), |l| (start + l).min(matching.len()));
+
+        let page: Vec<Value> = matching[start..end].iter().map(|tx| tx.0.clone()).collect();
+
+        let mut res = json!({ "transactions": page, "current-round": self.round });
+        if end < matching.len() {
+            res["next-token"] = json!(end.to_string());
+        }
+        res
+    }
+}
+
+fn teal_kv_json(state: &[(Vec<u8>, TealValueFake)]) -> Vec<Value> {
+    state
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                TealValueFake::Bytes(bytes) => {
+                    json!({ "type": 1, "bytes": BASE64.encode(bytes), "uint": 0 })
+                }
+                TealValueFake::Uint(uint) => json!({ "type": 2, "bytes": "", "uint": uint }),
+            };
+            json!({ "key": BASE64.encode(key), "value": value })
+        })
+        .collect()
+}
+
+fn local_state_json(app_id: u64, state: &[(Vec<u8>, TealValueFake)]) -> Value {
+    json!({
+        "id": app_id,
+        "key-value": teal_kv_json(state),
+        "schema": { "num-byte-slice": 16, "num-uint": 16 },
+    })
+}
+
+fn app_json(app_id: u64, app: &FakeApp) -> Value {
+    json!({
+        "id": app_id,
+        "params": {
+            "approval-program": "",
+            "clear-state-program": "",
+            "creator": app.creator,
+            "extra-program-pages": 0,
+            "global-state": teal_kv_json(&app.global_state),
+            "global-state-schema": { "num-byte-slice": 64, "num-uint": 64 },
+            "local-state-schema": { "num-byte-slice": 16, "num-uint": 16 },
+        }
+    })
+}
+
+fn asset_json(asset_id: u64, asset: &FakeAsset) -> Value {
+    let mut json = json!({
+        "index": asset_id,
+        "params": {
+            "creator": asset.creator,
+            "decimals": asset.decimals,
+            "default-frozen": false,
+            "total": asset.total,
+            "name": asset.name,
+            "unit-name": asset.unit_name,
+        }
+    });
+    if let Some(hash) = &asset.metadata_hash {
+        json["params"]["metadata-hash"] = json!(BASE64.encode(hash));
+    }
+    json
+}
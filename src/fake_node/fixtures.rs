@@ -0,0 +1,207 @@
+//! A set up DAO on a [FakeChain], shared by the def providers' tests.
+//!
+//! The global / local state keys mirror the ones the DAO app uses (see mbase's `dao_app_state`):
+//! if they change there, they have to be changed here too.
+
+use super::{FakeChain, TealValueFake};
+use crate::{
+    js::{common::SignedTxFromJs, to_sign_js::ToSignJs},
+    network_profile::{set_network_profile, NetworkProfileId, NetworkProfileSettings},
+};
+use algonaut::{
+    algod::v2::Algod,
+    core::{to_app_address, Address, MicroAlgos},
+    crypto::Signature,
+    transaction::{
+        transaction::TransactionSignature, tx_group::TxGroup, Pay, SignedTransaction, Transaction,
+        TxnBuilder,
+    },
+};
+use data_encoding::BASE64;
+
+pub const CREATOR: &str = "XRV73BEOXV4BTSNIFPYSJVS6P5ZZ2CHAAJQB4I53SBVKZVAKHWAS3ZYL7Y";
+pub const INVESTOR: &str = "34RAC265MPZYSWMTGHZWJPCQMSYYOS243VWBTOSS6T5LOZRHJ4XPJ6Z3BI";
+pub const CAPI_ADDRESS: &str = "7RAP3CY37XAUYAKLYNZB4AKDW5UHZOPFURWETWVXRMOMUD43WSPTPWIAAI";
+
+pub const FUNDS_ASSET_ID: u64 = 10;
+pub const SHARES_ASSET_ID: u64 = 11;
+pub const DAO_APP_ID: u64 = 12;
+
+pub const FUNDS_ASSET_DECIMALS: u64 = 6;
+pub const SHARE_SUPPLY: u64 = 1_000;
+/// In funds asset base units (1 display unit)
+pub const SHARE_PRICE: u64 = 1_000_000;
+pub const MIN_INVEST_SHARES: u64 = 1;
+pub const MAX_INVEST_SHARES: u64 = 100;
+
+/// Enough to pay fees and min balance increases of any operation
+const ALGOS: u64 = 10_000_000;
+
+pub fn app_address() -> String {
+    to_app_address(DAO_APP_ID).to_string()
+}
+
+/// Selects a network profile with the fixture's funds asset and capi address (otherwise they're read from the build's env)
+pub fn use_fixture_network_profile() {
+    set_network_profile(&NetworkProfileSettings {
+        network: NetworkProfileId::TestNet,
+        explorer: None,
+        funds_asset_id: Some(FUNDS_ASSET_ID.to_string()),
+        capi_address: Some(CAPI_ADDRESS.to_owned()),
+    })
+    .expect("Couldn't set the fixture network profile");
+}
+
+/// The DAO, with all its shares in the app and no funds received yet.
+/// The creator and investor have algos and funds asset, and aren't opted in to the app.
+pub fn dao_chain() -> FakeChain {
+    let app_address = app_address();
+
+    let mut chain = FakeChain::new();
+    chain.add_asset(
+        FUNDS_ASSET_ID,
+        CREATOR,
+        "USDC",
+        FUNDS_ASSET_DECIMALS,
+        u64::MAX,
+    );
+    chain.add_asset(SHARES_ASSET_ID, CREATOR, "SHARE", 0, SHARE_SUPPLY);
+
+    chain.add_app(DAO_APP_ID, CREATOR);
+    chain.set_app_global_state(DAO_APP_ID, dao_global_state());
+
+    chain.set_algos(&app_address, ALGOS);
+    chain.set_asset_holding(&app_address, FUNDS_ASSET_ID, 0);
+    chain.set_asset_holding(&app_address, SHARES_ASSET_ID, SHARE_SUPPLY);
+
+    for account in [CREATOR, INVESTOR, CAPI_ADDRESS] {
+        chain.set_algos(account, ALGOS);
+        chain.set_asset_holding(account, FUNDS_ASSET_ID, 1_000 * SHARE_PRICE);
+    }
+
+    chain
+}
+
+/// Opts the investor in to the DAO, with `shares` locked and nothing claimed
+pub fn set_investor_shares(chain: &mut FakeChain, shares: u64) {
+    chain.set_app_local_state(
+        INVESTOR,
+        DAO_APP_ID,
+        vec![
+            (b"Shares".to_vec(), TealValueFake::Uint(shares)),
+            (b"ClaimedTotal".to_vec(), TealValueFake::Uint(0)),
+            (b"ClaimedInit".to_vec(), TealValueFake::Uint(0)),
+            (
+                b"Dao".to_vec(),
+                TealValueFake::Bytes(DAO_APP_ID.to_be_bytes().to_vec()),
+            ),
+        ],
+    );
+}
+
+/// The txs handed out for signing, decoded from the WalletConnect payloads (filled, as no wallet is set in the tests)
+pub fn to_sign_txs(to_sign: &ToSignJs) -> Vec<Transaction> {
+    to_sign
+        .wc
+        .as_ref()
+        .expect("No WalletConnect txs")
+        .iter()
+        .map(|tx| {
+            let json = serde_json::to_value(tx).expect("Couldn't serialize WalletConnect tx");
+            let txn = json["txn"].as_str().expect("No txn in WalletConnect tx");
+            let msg_pack = BASE64.decode(txn.as_bytes()).expect("Invalid txn base64");
+            rmp_serde::from_slice(&msg_pack).expect("Invalid txn msg pack")
+        })
+        .collect()
+}
+
+/// Payments (sender, receiver, micro algos), built with the node's params, grouped if there's more than one
+pub async fn payment_txs(algod: &Algod, payments: &[(&str, &str, u64)]) -> Vec<Transaction> {
+    let params = algod
+        .suggested_transaction_params()
+        .await
+        .expect("Couldn't fetch params");
+    let mut txs: Vec<Transaction> = payments
+        .iter()
+        .map(|(sender, receiver, amount)| {
+            let pay = Pay::new(
+                sender.parse().expect("Invalid sender"),
+                receiver.parse().expect("Invalid receiver"),
+                MicroAlgos(*amount),
+            );
+            TxnBuilder::with(&params, pay.build())
+                .build()
+                .expect("Couldn't build tx")
+        })
+        .collect();
+    if txs.len() > 1 {
+        let mut refs: Vec<&mut Transaction> = txs.iter_mut().collect();
+        TxGroup::assign_group_id(&mut refs).expect("Couldn't group txs");
+    }
+    txs
+}
+
+/// The tx as returned by the wallet, but without a valid signature (the fake node doesn't verify them)
+pub fn signed(tx: &Transaction) -> SignedTransaction {
+    SignedTransaction {
+        transaction: tx.clone(),
+        transaction_id: tx.id().expect("Couldn't calculate tx id"),
+        sig: TransactionSignature::Single(Signature([0; 64])),
+        auth_address: None,
+    }
+}
+
+/// The txs as JS passes them back to submit(), see [signed]
+pub fn signed_js_txs(txs: &[Transaction]) -> Vec<SignedTxFromJs> {
+    txs.iter()
+        .map(|tx| SignedTxFromJs {
+            blob: rmp_serde::to_vec_named(&signed(tx)).expect("Couldn't serialize signed tx"),
+        })
+        .collect()
+}
+
+fn dao_global_state() -> Vec<(Vec<u8>, TealValueFake)> {
+    let creator: Address = CREATOR.parse().expect("Invalid fixture address");
+    let bytes = |str: &str| TealValueFake::Bytes(str.as_bytes().to_vec());
+
+    vec![
+        (b"CentralReceivedTotal".to_vec(), TealValueFake::Uint(0)),
+        (
+            b"FundsAssetId".to_vec(),
+            TealValueFake::Uint(FUNDS_ASSET_ID),
+        ),
+        (
+            b"SharesAssetId".to_vec(),
+            TealValueFake::Uint(SHARES_ASSET_ID),
+        ),
+        (b"DaoName".to_vec(), bytes("Fixture DAO")),
+        (b"SharePrice".to_vec(), TealValueFake::Uint(SHARE_PRICE)),
+        (b"InvestorsPart".to_vec(), TealValueFake::Uint(40)),
+        (
+            b"SocialMediaUrl".to_vec(),
+            bytes("https://twitter.com/capi"),
+        ),
+        (b"Owner".to_vec(), TealValueFake::Bytes(creator.0.to_vec())),
+        (
+            b"Versions".to_vec(),
+            TealValueFake::Bytes([1u16.to_be_bytes(), 1u16.to_be_bytes()].concat()),
+        ),
+        (b"LockedShares".to_vec(), TealValueFake::Uint(0)),
+        (
+            b"SharesForInvestors".to_vec(),
+            TealValueFake::Uint(SHARE_SUPPLY / 2),
+        ),
+        (b"MinRaiseTarget".to_vec(), TealValueFake::Uint(0)),
+        (b"MinRaiseDeadline".to_vec(), TealValueFake::Uint(0)),
+        (b"Raised".to_vec(), TealValueFake::Uint(0)),
+        (
+            b"MinInvestAmount".to_vec(),
+            TealValueFake::Uint(MIN_INVEST_SHARES),
+        ),
+        (
+            b"MaxInvestAmount".to_vec(),
+            TealValueFake::Uint(MAX_INVEST_SHARES),
+        ),
+        (b"SetupDate".to_vec(), TealValueFake::Uint(1_640_995_200)),
+    ]
+}
@@ -0,0 +1,144 @@
+//! In-process stand-in for algod, indexer and the price API, to run the def providers natively (cargo test) without a sandbox.
+//!
+//! [FakeNode::start] serves the subset of the algod / indexer REST APIs used by core and the providers
+//! from an in-memory [FakeChain], and makes [crate::dependencies::algod] / [crate::dependencies::indexer]
+//! point to it on the current thread until it's dropped. The same applies to [crate::fiat::price_source],
+//! with the prices set with [FakeChain::set_price].
+//!
+//! Usage (in a test running on a tokio runtime, see `fixtures` for a chain with a set up DAO):
+//! ```ignore
+//! let mut chain = FakeChain::new();
+//! chain.set_app_global_state(dao_id.0, vec![(b"Shares".to_vec(), TealValueFake::Uint(100))]);
+//! let _node = FakeNode::start(chain)?;
+//! let res = ViewDaoProviderDef {}.get(pars).await?;
+//! ```
+
+mod chain;
+pub mod fixtures;
+mod server;
+
+pub use chain::{FakeChain, FakeTx, TealValueFake};
+
+use self::server::Api;
+use algonaut::{algod::v2::Algod, indexer::v2::Indexer};
+use anyhow::Result;
+use std::{
+    cell::RefCell,
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+};
+
+thread_local! {
+    /// (algod, indexer, prices) addresses of the running fake node
+    static FAKE_NODE_ADDRESSES: RefCell<Option<(SocketAddr, SocketAddr, SocketAddr)>> = RefCell::new(None);
+}
+
+/// Token expected by algonaut - the fake node doesn't check it
+const FAKE_TOKEN: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+pub struct FakeNode {
+    algod_address: SocketAddr,
+    indexer_address: SocketAddr,
+    prices_address: SocketAddr,
+    chain: Arc<Mutex<FakeChain>>,
+    stop: Arc<AtomicBool>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl FakeNode {
+    /// Starts serving `chain` on free local ports and routes the providers' clients to it
+    pub fn start(chain: FakeChain) -> Result<FakeNode> {
+        let algod_listener = TcpListener::bind("127.0.0.1:0")?;
+        let indexer_listener = TcpListener::bind("127.0.0.1:0")?;
+        let prices_listener = TcpListener::bind("127.0.0.1:0")?;
+        let algod_address = algod_listener.local_addr()?;
+        let indexer_address = indexer_listener.local_addr()?;
+        let prices_address = prices_listener.local_addr()?;
+
+        let chain = Arc::new(Mutex::new(chain));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handles = vec![
+            server::spawn(algod_listener, Api::Algod, chain.clone(), stop.clone()),
+            server::spawn(indexer_listener, Api::Indexer, chain.clone(), stop.clone()),
+            server::spawn(prices_listener, Api::Prices, chain.clone(), stop.clone()),
+        ];
+
+        FAKE_NODE_ADDRESSES.with(|a| {
+            *a.borrow_mut() = Some((algod_address, indexer_address, prices_address))
+        });
+
+        log::debug!(
+            "Started fake node. algod: {algod_address}, indexer: {indexer_address}, prices: {prices_address}"
+        );
+
+        Ok(FakeNode {
+            algod_address,
+            indexer_address,
+            prices_address,
+            chain,
+            stop,
+            handles,
+        })
+    }
+
+    /// Access to the chain state, e.g. to modify it between provider calls or inspect submitted transactions
+    pub fn chain(&self) -> Arc<Mutex<FakeChain>> {
+        self.chain.clone()
+    }
+}
+
+impl Drop for FakeNode {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // unblock accept()
+        let _ = TcpStream::connect(self.algod_address);
+        let _ = TcpStream::connect(self.indexer_address);
+        let _ = TcpStream::connect(self.prices_address);
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+        FAKE_NODE_ADDRESSES.with(|a| *a.borrow_mut() = None);
+    }
+}
+
+pub fn algod_override() -> Option<Algod> {
+    fake_node_urls().and_then(|(url, _, _)| match Algod::new(&url, FAKE_TOKEN) {
+        Ok(algod) => Some(algod),
+        Err(e) => {
+            log::error!("Couldn't create fake algod: {e:?}");
+            None
+        }
+    })
+}
+
+pub fn indexer_override() -> Option<Indexer> {
+    fake_node_urls().and_then(|(_, url, _)| match Indexer::new(&url) {
+        Ok(indexer) => Some(indexer),
+        Err(e) => {
+            log::error!("Couldn't create fake indexer: {e:?}");
+            None
+        }
+    })
+}
+
+pub fn price_source_url_override() -> Option<String> {
+    fake_node_urls().map(|(_, _, url)| url)
+}
+
+/// (algod, indexer, prices) urls
+fn fake_node_urls() -> Option<(String, String, String)> {
+    FAKE_NODE_ADDRESSES.with(|a| {
+        a.borrow().map(|(algod, indexer, prices)| {
+            (
+                format!("http://{algod}"),
+                format!("http://{indexer}"),
+                format!("http://{prices}"),
+            )
+        })
+    })
+}
@@ -0,0 +1,342 @@
+use super::chain::{FakeChain, TxQuery};
+use algonaut::transaction::SignedTransaction;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Cursor, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+/// algod and indexer have overlapping paths (e.g. /v2/accounts/{address}), so each gets its own listener
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Api {
+    Algod,
+    Indexer,
+    /// See [crate::service::price_source::HttpPriceSource]
+    Prices,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Response {
+        Response { status: 200, body }
+    }
+
+    fn not_found(path: &str) -> Response {
+        Response {
+            status: 404,
+            body: json!({ "message": format!("fake node: no data for {path}") }),
+        }
+    }
+
+    fn bad_request(message: String) -> Response {
+        Response {
+            status: 400,
+            body: json!({ "message": message }),
+        }
+    }
+}
+
+pub(super) fn spawn(
+    listener: TcpListener,
+    api: Api,
+    chain: Arc<Mutex<FakeChain>>,
+    stop: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if stop.load(Ordering::SeqCst) {
+                break;
+            }
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = handle(stream, api, &chain) {
+                        log::error!("Fake node error handling request: {e:?}");
+                    }
+                }
+                Err(e) => log::error!("Fake node connection error: {e:?}"),
+            }
+        }
+    })
+}
+
+fn handle(stream: TcpStream, api: Api, chain: &Mutex<FakeChain>) -> anyhow::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let request = read_request(&mut reader)?;
+
+    log::trace!(
+        "Fake node ({api:?}) request: {} {}",
+        request.method,
+        request.path
+    );
+
+    let response = {
+        // a panic in a previous request shouldn't take the node down
+        let mut chain = chain.lock().unwrap_or_else(|e| e.into_inner());
+        match api {
+            Api::Algod => route_algod(&request, &mut chain),
+            Api::Indexer => route_indexer(&request, &chain),
+            Api::Prices => route_prices(&request, &chain),
+        }
+    };
+
+    write_response(stream, response)
+}
+
+fn route_algod(req: &Request, chain: &mut FakeChain) -> Response {
+    let segments: Vec<&str> = req.path.trim_matches('/').split('/').collect();
+    match (req.method.as_str(), segments.as_slice()) {
+        ("GET", ["health"]) => Response::ok(json!({})),
+        ("GET", ["v2", "status"]) => Response::ok(chain.status_json()),
+        ("GET", ["v2", "status", "wait-for-block-after", _]) => Response::ok(chain.status_json()),
+        ("GET", ["v2", "transactions", "params"]) => Response::ok(chain.suggested_params_json()),
+        ("GET", ["v2", "accounts", address]) => Response::ok(chain.account_json(address)),
+        ("GET", ["v2", "accounts", address, "applications", id]) => with_id(
+            id,
+            |id| chain.account_application_json(address, id),
+            &req.path,
+        ),
+        ("GET", ["v2", "accounts", address, "assets", id]) => {
+            with_id(id, |id| chain.account_asset_json(address, id), &req.path)
+        }
+        ("GET", ["v2", "applications", id]) => with_id(id, |id| chain.app_json(id), &req.path),
+        ("GET", ["v2", "assets", id]) => with_id(id, |id| chain.asset_json(id), &req.path),
+        ("POST", ["v2", "transactions"]) => match decode_signed_txs(&req.body) {
+            Ok(txs) => {
+                let tx_id = txs
+                    .first()
+                    .and_then(|tx| tx.transaction.id().ok())
+                    .unwrap_or_default();
+                chain.submit(txs);
+                Response::ok(json!({ "txId": tx_id }))
+            }
+            Err(e) => Response::bad_request(format!("Couldn't decode transactions: {e:?}")),
+        },
+        // the fake node doesn't evaluate TEAL: dryruns always pass
+        ("POST", ["v2", "teal", "dryrun"]) => Response::ok(json!({
+            "error": "",
+            "protocol-version": "",
+            "txns": [],
+        })),
+        ("GET", ["v2", "transactions", "pending", tx_id]) => {
+            let submitted = chain
+                .submitted()
+                .iter()
+                .find(|tx| tx.transaction.id().map(|id| id == *tx_id).unwrap_or(false));
+            match submitted {
+                // everything submitted is considered confirmed in the round it caused
+                Some(tx) => Response::ok(json!({
+                    "confirmed-round": chain.round(),
+                    "pool-error": "",
+                    "txn": serde_json::to_value(tx).unwrap_or(Value::Null),
+                })),
+                None => Response::not_found(&req.path),
+            }
+        }
+        _ => Response::not_found(&req.path),
+    }
+}
+
+fn route_indexer(req: &Request, chain: &FakeChain) -> Response {
+    let segments: Vec<&str> = req.path.trim_matches('/').split('/').collect();
+    match (req.method.as_str(), segments.as_slice()) {
+        ("GET", ["health"]) => Response::ok(json!({ "round": chain.round() })),
+        ("GET", ["v2", "transactions"]) => Response::ok(chain.txs_json(&tx_query(req, None))),
+        ("GET", ["v2", "accounts", address, "transactions"]) => {
+            Response::ok(chain.txs_json(&tx_query(req, Some(address))))
+        }
+        ("GET", ["v2", "accounts"]) => {
+            Response::ok(chain.accounts_with_app_json(
+                req.query.get("application-id").and_then(|s| s.parse().ok()),
+            ))
+        }
+        ("GET", ["v2", "accounts", address]) => Response::ok(json!({
+            "account": chain.account_json(address),
+            "current-round": chain.round(),
+        })),
+        ("GET", ["v2", "assets", id, "balances"]) => match id.parse() {
+            Ok(id) => Response::ok(chain.asset_balances_json(id)),
+            Err(_) => Response::bad_request(format!("Invalid id: {id}")),
+        },
+        ("GET", ["v2", "applications", id]) => with_id(
+            id,
+            |id| {
+                chain
+                    .app_json(id)
+                    .map(|app| json!({ "application": app, "current-round": chain.round() }))
+            },
+            &req.path,
+        ),
+        ("GET", ["v2", "assets", id]) => with_id(
+            id,
+            |id| {
+                chain
+                    .asset_json(id)
+                    .map(|asset| json!({ "asset": asset, "current-round": chain.round() }))
+            },
+            &req.path,
+        ),
+        _ => Response::not_found(&req.path),
+    }
+}
+
+fn route_prices(req: &Request, chain: &FakeChain) -> Response {
+    let segments: Vec<&str> = req.path.trim_matches('/').split('/').collect();
+    match (req.method.as_str(), segments.as_slice()) {
+        ("GET", ["v1", "price"]) => {
+            let asset_id = req.query.get("asset_id").and_then(|s| s.parse().ok());
+            let currency = req.query.get("currency");
+            match (asset_id, currency) {
+                (Some(asset_id), Some(currency)) => match chain.price(asset_id, currency) {
+                    Some(price) => Response::ok(json!({ "price": price })),
+                    None => Response::not_found(&req.path),
+                },
+                _ => Response::bad_request("Expected asset_id and currency".to_owned()),
+            }
+        }
+        _ => Response::not_found(&req.path),
+    }
+}
+
+fn with_id(id: &str, f: impl FnOnce(u64) -> Option<Value>, path: &str) -> Response {
+    match id.parse() {
+        Ok(id) => match f(id) {
+            Some(value) => Response::ok(value),
+            None => Response::not_found(path),
+        },
+        Err(_) => Response::bad_request(format!("Invalid id: {id}")),
+    }
+}
+
+fn tx_query(req: &Request, address: Option<&str>) -> TxQuery {
+    let q = &req.query;
+    TxQuery {
+        address: address
+            .map(|a| a.to_owned())
+            .or_else(|| q.get("address").cloned()),
+        asset_id: q.get("asset-id").and_then(|s| s.parse().ok()),
+        application_id: q.get("application-id").and_then(|s| s.parse().ok()),
+        tx_type: q.get("tx-type").cloned(),
+        note_prefix: q.get("note-prefix").cloned(),
+        after_time: q.get("after-time").and_then(|s| parse_time(s)),
+        before_time: q.get("before-time").and_then(|s| parse_time(s)),
+        limit: q.get("limit").and_then(|s| s.parse().ok()),
+        next: q.get("next").and_then(|s| s.parse().ok()),
+    }
+}
+
+fn parse_time(str: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(str)
+        .ok()
+        .map(|d| d.timestamp())
+}
+
+/// Groups are posted as concatenated msgpack-encoded signed transactions
+fn decode_signed_txs(bytes: &[u8]) -> anyhow::Result<Vec<SignedTransaction>> {
+    let mut deserializer = rmp_serde::Deserializer::new(Cursor::new(bytes));
+    let mut txs = vec![];
+    while (deserializer.get_ref().position() as usize) < bytes.len() {
+        txs.push(serde::Deserialize::deserialize(&mut deserializer)?);
+    }
+    Ok(txs)
+}
+
+fn read_request(reader: &mut impl BufRead) -> anyhow::Result<Request> {
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_owned();
+    let target = parts.next().unwrap_or_default().to_owned();
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse()?;
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let (path, query_str) = target.split_once('?').unwrap_or((&target, ""));
+    let query = query_str
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_owned(), percent_decode(v)))
+        .collect();
+
+    Ok(Request {
+        method,
+        path: path.to_owned(),
+        query,
+        body,
+    })
+}
+
+fn percent_decode(str: &str) -> String {
+    let bytes = str.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+                match u8::from_str_radix(hex, 16) {
+                    Ok(b) => {
+                        decoded.push(b);
+                        i += 3;
+                        continue;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            b'+' => decoded.push(b' '),
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn write_response(mut stream: TcpStream, response: Response) -> anyhow::Result<()> {
+    let body = serde_json::to_vec(&response.body)?;
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        _ => "Not Found",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason,
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()?;
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::{
+    dependencies::data_type,
+    service::{
+        price_source::{HttpPriceSource, MockPriceSource, PriceSource},
+        storage::{storage_get, storage_set},
+    },
+};
+use anyhow::{anyhow, Result};
+use mbase::dependencies::DataType;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+/// Local storage key for the fiat display settings
+const FIAT_KEY: &str = "FIAT";
+
+#[derive(Tsify, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PriceSourceSettings {
+    /// Fixed rates, for development / QA. Only available in dev builds or with mock data.
+    /// Only USD stablecoins (e.g. USDC) are priced - with other funds assets, no fiat amounts are shown.
+    Mock,
+    /// Price API, see [crate::service::price_source::HttpPriceSource] for the expected endpoint
+    Http { url: String },
+}
+
+/// Set by JS (e.g. in the settings screen), persisted in local storage
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(from_wasm_abi, into_wasm_abi)]
+pub struct FiatSettings {
+    /// ISO 4217 code, e.g. "USD", "EUR". None: amounts are shown only in the funds asset.
+    pub currency: Option<String>,
+    pub price_source: PriceSourceSettings,
+}
+
+pub fn fiat_settings() -> Result<FiatSettings> {
+    Ok(storage_get(FIAT_KEY)?.unwrap_or_else(|| FiatSettings {
+        currency: None,
+        price_source: PriceSourceSettings::Mock,
+    }))
+}
+
+pub fn set_fiat_settings(settings: &FiatSettings) -> Result<()> {
+    // validate before saving
+    if let Some(currency) = &settings.currency {
+        validate_currency(currency)?;
+    }
+    match &settings.price_source {
+        PriceSourceSettings::Mock => validate_mock_price_source()?,
+        PriceSourceSettings::Http { url } => {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(anyhow!("Invalid price source url: {url}"));
+            }
+        }
+    }
+    storage_set(
+        FIAT_KEY,
+        &FiatSettings {
+            currency: settings.currency.as_ref().map(|c| c.to_uppercase()),
+            ..settings.clone()
+        },
+    )
+}
+
+/// The price source selected in the settings.
+/// In tests, this points to the in-process fake node if one is running - see [crate::fake_node].
+pub fn price_source(settings: &FiatSettings) -> Result<Box<dyn PriceSource>> {
+    #[cfg(test)]
+    if let Some(url) = crate::fake_node::price_source_url_override() {
+        return Ok(Box::new(HttpPriceSource::new(&url)));
+    }
+    Ok(match &settings.price_source {
+        PriceSourceSettings::Mock => {
+            // the settings may have been saved with mock data, which was switched off later
+            validate_mock_price_source()?;
+            Box::new(MockPriceSource {})
+        }
+        PriceSourceSettings::Http { url } => Box::new(HttpPriceSource::new(url)),
+    })
+}
+
+/// The mock prices are fixed, showing them with real data would be misleading
+fn mock_price_source_allowed() -> Result<bool> {
+    Ok(cfg!(debug_assertions) || matches!(data_type()?, DataType::Mock))
+}
+
+fn validate_mock_price_source() -> Result<()> {
+    if mock_price_source_allowed()? {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "The mock price source is only available in dev builds or with mock data"
+        ))
+    }
+}
+
+fn validate_currency(currency: &str) -> Result<()> {
+    if currency.len() == 3 && currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(())
+    } else {
+        Err(anyhow!("Invalid currency code: {currency}"))
+    }
+}
@@ -1,4 +1,20 @@
+use crate::{
+    dependencies::{set_data_type, set_provider_data_type},
+    error::FrError,
+    fiat::{fiat_settings, set_fiat_settings, FiatSettings},
+    js::bridge::{log_wrap_new, log_wrap_new_sync_no_pars},
+    locale::{locale_settings, set_locale, LocaleSettings},
+    network_profile::{
+        network_profile, network_profile_settings, set_network_profile, ExplorerTemplates,
+        NetworkProfileSettings,
+    },
+    provider::swappable_provider_names,
+    service::simulate::{set_simulate_txs, simulate_txs_enabled},
+    wallet::{set_wallet_settings, wallet_settings, WalletSettings},
+};
 use log::info;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(js_name=initLog)]
@@ -7,3 +23,160 @@ pub fn init_log() -> Result<(), JsValue> {
     info!("Initialized wasm logs");
     Ok(())
 }
+
+/// Switches between real and mock data at runtime (persisted in local storage).
+/// Applies to the providers retrieved after this call, i.e. to the next bridge calls.
+#[wasm_bindgen(js_name=setDataType)]
+pub async fn set_data_type_js(pars: SetDataTypeParJs) -> Result<SetDataTypeResJs, FrError> {
+    log_wrap_new("set_data_type", pars, async move |pars| {
+        match &pars.provider {
+            Some(provider) => {
+                if !swappable_provider_names().contains(&provider.as_str()) {
+                    return Err(FrError::Internal(format!("Unknown provider: {provider}")));
+                }
+                set_provider_data_type(provider, pars.data_type.as_deref())?
+            }
+            None => set_data_type(pars.data_type.as_deref())?,
+        }
+        Ok(SetDataTypeResJs {
+            providers: swappable_provider_names()
+                .into_iter()
+                .map(|s| s.to_owned())
+                .collect(),
+        })
+    })
+    .await
+}
+
+#[derive(Tsify, Debug, Clone, Deserialize)]
+#[tsify(from_wasm_abi)]
+pub struct SetDataTypeParJs {
+    /// "real" or "mock". None resets to the default (build-time data type, or global data type, if provider is set)
+    pub data_type: Option<String>,
+    /// Set only for this provider, e.g. "funds_activity". None: set for all providers.
+    pub provider: Option<String>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct SetDataTypeResJs {
+    /// The provider names that can be passed to setDataType
+    pub providers: Vec<String>,
+}
+
+/// Selects the network (node, explorer, funds asset, capi address) at runtime (persisted in local storage)
+#[wasm_bindgen(js_name=setNetworkProfile)]
+pub async fn set_network_profile_js(
+    pars: NetworkProfileSettings,
+) -> Result<NetworkProfileResJs, FrError> {
+    log_wrap_new("set_network_profile", pars, async move |pars| {
+        set_network_profile(&pars)?;
+        network_profile_res()
+    })
+    .await
+}
+
+#[wasm_bindgen(js_name=getNetworkProfile)]
+pub async fn get_network_profile_js() -> Result<NetworkProfileResJs, FrError> {
+    log_wrap_new_sync_no_pars("get_network_profile", network_profile_res).await
+}
+
+fn network_profile_res() -> Result<NetworkProfileResJs, FrError> {
+    Ok(NetworkProfileResJs {
+        settings: network_profile_settings()?,
+        explorer: network_profile()?.explorer,
+    })
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct NetworkProfileResJs {
+    pub settings: NetworkProfileSettings,
+    /// The resolved explorer templates
+    pub explorer: ExplorerTemplates,
+}
+
+/// Sets the locale used to format numbers, dates and percentages (persisted in local storage).
+/// To be called once at startup (and when the user changes the language).
+#[wasm_bindgen(js_name=setLocale)]
+pub async fn set_locale_js(pars: LocaleSettings) -> Result<LocaleSettings, FrError> {
+    log_wrap_new("set_locale", pars, async move |pars| {
+        set_locale(&pars)?;
+        Ok(locale_settings()?)
+    })
+    .await
+}
+
+#[wasm_bindgen(js_name=getLocale)]
+pub async fn get_locale_js() -> Result<LocaleSettings, FrError> {
+    log_wrap_new_sync_no_pars("get_locale", || Ok(locale_settings()?)).await
+}
+
+/// Sets the fiat currency in which amounts are shown alongside the funds asset, and the price source (persisted in local storage).
+#[wasm_bindgen(js_name=setFiat)]
+pub async fn set_fiat_js(pars: FiatSettings) -> Result<FiatSettings, FrError> {
+    log_wrap_new("set_fiat", pars, async move |pars| {
+        set_fiat_settings(&pars)?;
+        Ok(fiat_settings()?)
+    })
+    .await
+}
+
+#[wasm_bindgen(js_name=getFiat)]
+pub async fn get_fiat_js() -> Result<FiatSettings, FrError> {
+    log_wrap_new_sync_no_pars("get_fiat", || Ok(fiat_settings()?)).await
+}
+
+/// Sets the connected wallet (persisted in local storage), so txs to sign are returned only in its format.
+/// To be called when connecting, and with an empty wallet when disconnecting.
+#[wasm_bindgen(js_name=setWallet)]
+pub async fn set_wallet_js(pars: SetWalletParJs) -> Result<WalletResJs, FrError> {
+    log_wrap_new("set_wallet", pars, async move |pars| {
+        set_wallet_settings(pars.wallet.as_ref())?;
+        wallet_res()
+    })
+    .await
+}
+
+#[wasm_bindgen(js_name=getWallet)]
+pub async fn get_wallet_js() -> Result<WalletResJs, FrError> {
+    log_wrap_new_sync_no_pars("get_wallet", wallet_res).await
+}
+
+fn wallet_res() -> Result<WalletResJs, FrError> {
+    Ok(WalletResJs {
+        wallet: wallet_settings()?,
+    })
+}
+
+#[derive(Tsify, Debug, Clone, Deserialize)]
+#[tsify(from_wasm_abi)]
+pub struct SetWalletParJs {
+    /// None: disconnected
+    pub wallet: Option<WalletSettings>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct WalletResJs {
+    pub wallet: Option<WalletSettings>,
+}
+
+/// Enables / disables simulating the txs before returning them for signing (persisted in local storage).
+/// When enabled, the txs to sign include the predicted result, so the UI can prevent signing txs that will fail.
+#[wasm_bindgen(js_name=setSimulateTxs)]
+pub async fn set_simulate_txs_js(pars: SimulateTxsJs) -> Result<SimulateTxsJs, FrError> {
+    log_wrap_new("set_simulate_txs", pars, async move |pars| {
+        set_simulate_txs(pars.enabled)?;
+        Ok(SimulateTxsJs {
+            enabled: simulate_txs_enabled()?,
+        })
+    })
+    .await
+}
+
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(from_wasm_abi, into_wasm_abi)]
+pub struct SimulateTxsJs {
+    pub enabled: bool,
+}
@@ -1,3 +1,4 @@
+use crate::service::expected_txs::consume_expected_txs;
 use algonaut::transaction::{SignedTransaction, Transaction};
 use anyhow::Result;
 use serde::Deserialize;
@@ -42,14 +43,25 @@ pub fn to_my_algo_txs1(txs: &[Transaction]) -> Result<Vec<Value>> {
 pub fn signed_js_tx_to_signed_tx(
     signed_js_tx: &SignedTxFromJs,
 ) -> Result<SignedTransaction, JsValue> {
-    rmp_serde::from_slice(&signed_js_tx.blob).map_err(to_js_value)
+    signed_js_tx_to_signed_tx1(signed_js_tx).map_err(to_js_value)
 }
 
 // TODO remove the other one, use this (js "decorator" refactoring)
+/// Note: doesn't check that the tx was handed out for signing, submit() does it with [verify_signed_js_txs]
 pub fn signed_js_tx_to_signed_tx1(signed_js_tx: &SignedTxFromJs) -> Result<SignedTransaction> {
     Ok(rmp_serde::from_slice(&signed_js_tx.blob)?)
 }
 
+/// Fails if the txs aren't exactly the txs handed out together for signing (see [crate::js::to_sign_js::ToSignJs::new]).
+/// To be called by submit() with all the signed txs of the operation, before submitting any of them.
+pub fn verify_signed_js_txs(txs: &[SignedTxFromJs]) -> Result<()> {
+    let mut signed_txs = vec![];
+    for tx in txs {
+        signed_txs.push(signed_js_tx_to_signed_tx1(tx)?);
+    }
+    consume_expected_txs(&signed_txs)
+}
+
 pub fn signed_js_txs_to_signed_tx(
     txs: &[SignedTxFromJs],
 ) -> Result<Vec<SignedTransaction>, JsValue> {
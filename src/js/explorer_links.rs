@@ -1,22 +1,40 @@
 use algonaut::core::Address;
 use mbase::models::tx_id::TxId;
 
-use crate::dependencies::explorer_base_url;
+use crate::network_profile::{explorer, ExplorerTemplates};
 
-/// Base URL determined by environment variable
+/// Explorer determined by the network profile
 pub fn explorer_tx_id_link_env(tx_id: &TxId) -> String {
-    explorer_tx_id_link(explorer_base_url(), tx_id)
+    explorer_tx_id_link(&explorer(), tx_id)
 }
 
-pub fn explorer_tx_id_link(base_url: &str, tx_id: &TxId) -> String {
-    format!("{}tx/{}", base_url, tx_id.to_string())
+pub fn explorer_tx_id_link(explorer: &ExplorerTemplates, tx_id: &TxId) -> String {
+    explorer.tx.replace("{}", &tx_id.to_string())
 }
 
-/// Base URL determined by environment variable
+/// Explorer determined by the network profile
 pub fn explorer_address_link_env(address: &Address) -> String {
-    explorer_address_link(explorer_base_url(), address)
+    explorer_address_link(&explorer(), address)
 }
 
-pub fn explorer_address_link(base_url: &str, address: &Address) -> String {
-    format!("{}address/{}", base_url, address)
+pub fn explorer_address_link(explorer: &ExplorerTemplates, address: &Address) -> String {
+    explorer.address.replace("{}", &address.to_string())
+}
+
+/// Explorer determined by the network profile
+pub fn explorer_asset_link_env(asset_id: u64) -> String {
+    explorer_asset_link(&explorer(), asset_id)
+}
+
+pub fn explorer_asset_link(explorer: &ExplorerTemplates, asset_id: u64) -> String {
+    explorer.asset.replace("{}", &asset_id.to_string())
+}
+
+/// Explorer determined by the network profile
+pub fn explorer_app_link_env(app_id: u64) -> String {
+    explorer_app_link(&explorer(), app_id)
+}
+
+pub fn explorer_app_link(explorer: &ExplorerTemplates, app_id: u64) -> String {
+    explorer.application.replace("{}", &app_id.to_string())
 }
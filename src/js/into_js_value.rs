@@ -2,6 +2,9 @@
 // tsify does it for us for the parameters, so not sure why we've to do it for results
 
 use crate::{
+    fiat::FiatSettings,
+    js::bridge_general::{NetworkProfileResJs, SetDataTypeResJs, SimulateTxsJs, WalletResJs},
+    locale::LocaleSettings,
     model::dao_js::DaoJs,
     provider::{
         add_roadmap_item_provider::{AddRoadmapItemResJs, SubmitAddRoadmapItemResJs},
@@ -12,13 +15,17 @@ use crate::{
         claim_provider::{ClaimResJs, SubmitClaimResJs},
         create_assets_provider::CreateDaoAssetsResJs,
         create_dao_provider::{CreateDaoRes, CreateDaoResJs},
+        dao_content_provider::VerifyDaoContentResJs,
+        dao_page::DaoPageResJs,
         def::dev_provider_def::{DevSettingsResJs, SubmitDevSettingsResJs},
+        description_provider::DescriptionResJs,
         drain_provider::{DrainResJs, SubmitDrainResJs},
         funds_activity_provider::LoadFundsActivityResJs,
         funds_raising_provider::FundsRaisingResJs,
-        holders_count_provider::{HoldersChangeResJs, HoldersCountResJs},
+        holders_count_provider::{HoldersChangeResJs, HoldersCountResJs, HoldersHistoryResJs},
         income_vs_spending_provider::IncomeVsSpendingResJs,
         investment_provider::{AvailableSharesResJs, LoadInvestorResJs},
+        investor_statement_provider::InvestorStatementResJs,
         lock_provider::{LockResJs, SubmitLockResJs},
         my_daos_provider::MyDaosResJs,
         my_shares_provider::MySharesResJs,
@@ -28,7 +35,10 @@ use crate::{
         rekey_provider::{RekeyResJs, SubmitRekeyResJs},
         roadmap_provider::GetRoadmapResJs,
         shares_distribution_provider::SharedDistributionResJs,
-        team_provider::{AddTeamMemberResJs, EditTeamMemberResJs, GetTeamResJs, SetTeamResJs},
+        team_provider::{
+            AddTeamMemberResJs, EditTeamMemberResJs, GetTeamResJs, RemoveTeamMemberResJs,
+            ReorderTeamMembersResJs, SetTeamResJs,
+        },
         unlock_provider::{SubmitUnlockResJs, UnlockResJs},
         update_app_provider::{SubmitUpdateAppResJs, UpdateDaoAppResJs},
         update_data_provider::{UpdatableDataResJs, UpdateDataResJs},
@@ -37,7 +47,7 @@ use crate::{
         withdrawal_history_provider::LoadWithdrawalResJs,
         wyre_provider::WyreReserveResJs,
     },
-    service::wallet_connect_tx::WalletConnectTx,
+    service::{export::ExportResJs, wallet_connect_tx::WalletConnectTx},
 };
 use serde::Serialize;
 // use serde_wasm_bindgen::to_value;
@@ -135,6 +145,12 @@ impl From<LoadInvestorResJs> for JsValue {
     }
 }
 
+impl From<InvestorStatementResJs> for JsValue {
+    fn from(res: InvestorStatementResJs) -> Self {
+        to_js(res)
+    }
+}
+
 impl From<SubmitClaimResJs> for JsValue {
     fn from(res: SubmitClaimResJs) -> Self {
         to_js(res)
@@ -177,6 +193,36 @@ impl From<HoldersChangeResJs> for JsValue {
     }
 }
 
+impl From<HoldersHistoryResJs> for JsValue {
+    fn from(res: HoldersHistoryResJs) -> Self {
+        to_js(res)
+    }
+}
+
+impl From<DaoPageResJs> for JsValue {
+    fn from(res: DaoPageResJs) -> Self {
+        to_js(res)
+    }
+}
+
+impl From<DescriptionResJs> for JsValue {
+    fn from(res: DescriptionResJs) -> Self {
+        to_js(res)
+    }
+}
+
+impl From<VerifyDaoContentResJs> for JsValue {
+    fn from(res: VerifyDaoContentResJs) -> Self {
+        to_js(res)
+    }
+}
+
+impl From<ExportResJs> for JsValue {
+    fn from(res: ExportResJs) -> Self {
+        to_js(res)
+    }
+}
+
 impl From<IncomeVsSpendingResJs> for JsValue {
     fn from(res: IncomeVsSpendingResJs) -> Self {
         to_js(res)
@@ -375,12 +421,60 @@ impl From<EditTeamMemberResJs> for JsValue {
     }
 }
 
+impl From<RemoveTeamMemberResJs> for JsValue {
+    fn from(res: RemoveTeamMemberResJs) -> Self {
+        to_js(res)
+    }
+}
+
+impl From<ReorderTeamMembersResJs> for JsValue {
+    fn from(res: ReorderTeamMembersResJs) -> Self {
+        to_js(res)
+    }
+}
+
 impl From<SetTeamResJs> for JsValue {
     fn from(res: SetTeamResJs) -> Self {
         to_js(res)
     }
 }
 
+impl From<SetDataTypeResJs> for JsValue {
+    fn from(res: SetDataTypeResJs) -> Self {
+        to_js(res)
+    }
+}
+
+impl From<NetworkProfileResJs> for JsValue {
+    fn from(res: NetworkProfileResJs) -> Self {
+        to_js(res)
+    }
+}
+
+impl From<WalletResJs> for JsValue {
+    fn from(res: WalletResJs) -> Self {
+        to_js(res)
+    }
+}
+
+impl From<SimulateTxsJs> for JsValue {
+    fn from(res: SimulateTxsJs) -> Self {
+        to_js(res)
+    }
+}
+
+impl From<LocaleSettings> for JsValue {
+    fn from(res: LocaleSettings) -> Self {
+        to_js(res)
+    }
+}
+
+impl From<FiatSettings> for JsValue {
+    fn from(res: FiatSettings) -> Self {
+        to_js(res)
+    }
+}
+
 fn to_js<T: Serialize + Debug>(obj: T) -> JsValue {
     // THIS DOESN'T WORK for my algo at least it's returning the txs map with indices as keys and entries as values
     // let res = to_value(&obj);
@@ -1,34 +1,104 @@
 use super::common::to_my_algo_tx1;
-use crate::service::wallet_connect_tx::WalletConnectTx;
+use crate::dependencies::algod;
+use crate::service::{
+    arc1_tx::Arc1Tx,
+    expected_txs::expect_txs,
+    simulate::{simulate, simulate_txs_enabled, SimulationJs, SimulationStatus},
+    tx_costs::{cost_breakdown, preflight_check, txs_sender_costs, CostBreakdownJs},
+    tx_message::default_tx_message,
+    wallet_connect_tx::WalletConnectTx,
+};
+use crate::wallet::{wallet_settings, SignFormat};
 use algonaut::transaction::Transaction;
 use anyhow::{Error, Result};
 use serde::Serialize;
 use serde_json::Value;
 use tsify::Tsify;
 
-// We always return transactions serialized to both my algo and wallet connect formats
-// this could be optimized by passing the connected wallet type as parameter - so we return just the format for it
-// but that seems overkill, for now at least
+/// The txs in the format of the connected wallet (see [crate::wallet::set_wallet_settings]).
+/// If no wallet was set, in all the formats.
 #[derive(Tsify, Debug, Clone, Serialize)]
 #[tsify(into_wasm_abi)]
 pub struct ToSignJs {
-    pub my_algo: Vec<Value>,
-    pub wc: Vec<WalletConnectTx>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub my_algo: Option<Vec<Value>>,
+    /// WalletConnect (Pera, Defly, etc.)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wc: Option<Vec<WalletConnectTx>>,
+    /// ARC-1 `signTxns`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arc1: Option<Vec<Arc1Tx>>,
+    /// What signing costs, in algos
+    pub costs: CostBreakdownJs,
+    /// Set if simulation is enabled (see [crate::service::simulate])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub simulation: Option<SimulationJs>,
 }
 
 impl ToSignJs {
-    pub fn new(txs: Vec<Transaction>) -> Result<ToSignJs> {
+    /// Note that this registers the txs as an operation: submit() accepts exactly these txs, in this order, once
+    pub async fn new(txs: Vec<Transaction>) -> Result<ToSignJs> {
+        Self::with_messages(
+            txs.into_iter()
+                .map(|tx| {
+                    let message = default_tx_message(&tx);
+                    (tx, message)
+                })
+                .collect(),
+        )
+        .await
+    }
+
+    /// Txs with a message describing what they do, shown by the wallet (if supported)
+    pub async fn with_messages(txs: Vec<(Transaction, String)>) -> Result<ToSignJs> {
+        let plain_txs: Vec<Transaction> = txs.iter().map(|(tx, _)| tx.clone()).collect();
+
+        let algod = algod();
+
+        let sender_costs = txs_sender_costs(&algod, &plain_txs).await?;
+        let costs = cost_breakdown(&plain_txs, &sender_costs);
+
+        // the simulation reports an overspend with details (tx, amounts), so it runs before the preflight check
+        let simulation = if simulate_txs_enabled()? {
+            Some(simulate(&algod, &plain_txs).await)
+        } else {
+            None
+        };
+
+        // fail early, instead of after the user signed
+        // (unless the simulation already failed: the UI shows its failure)
+        if simulation.as_ref().map(|s| s.status) != Some(SimulationStatus::Failure) {
+            preflight_check(&algod, &sender_costs).await?;
+        }
+
+        expect_txs(&plain_txs)?;
+
+        let wallet = wallet_settings()?;
+        let format = wallet.as_ref().map(|w| w.wallet_type.sign_format());
+        let wants = |f: SignFormat| format.is_none() || format == Some(f);
+
         let mut my_algo_txs = vec![];
         let mut wc_txs = vec![];
+        let mut arc1_txs = vec![];
 
-        for tx in txs {
-            my_algo_txs.push(to_my_algo_tx1(&tx).map_err(Error::msg)?);
-            wc_txs.push(WalletConnectTx::new(&tx, "")?);
+        for (tx, message) in &txs {
+            if wants(SignFormat::MyAlgo) {
+                my_algo_txs.push(to_my_algo_tx1(tx).map_err(Error::msg)?);
+            }
+            if wants(SignFormat::WalletConnect) {
+                wc_txs.push(WalletConnectTx::new(tx, message)?);
+            }
+            if wants(SignFormat::Arc1) {
+                arc1_txs.push(Arc1Tx::new(tx, message, wallet.as_ref())?);
+            }
         }
 
         Ok(ToSignJs {
-            my_algo: my_algo_txs,
-            wc: wc_txs,
+            my_algo: Some(my_algo_txs).filter(|_| wants(SignFormat::MyAlgo)),
+            wc: Some(wc_txs).filter(|_| wants(SignFormat::WalletConnect)),
+            arc1: Some(arc1_txs).filter(|_| wants(SignFormat::Arc1)),
+            costs,
+            simulation,
         })
     }
 }
@@ -2,11 +2,17 @@
 
 mod dependencies;
 pub mod error;
+#[cfg(test)]
+pub mod fake_node;
+pub mod fiat;
 mod inputs_validation;
 pub mod js;
+pub mod locale;
 mod model;
+pub mod network_profile;
 pub mod provider;
 mod service;
+pub mod wallet;
 
 use anyhow::{anyhow, Result};
 use mbase::{
@@ -0,0 +1,194 @@
+use crate::service::storage::{storage_get, storage_set};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use num_format::Locale;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+/// Local storage key for the user's locale
+const LOCALE_KEY: &str = "LOCALE";
+
+const DEFAULT_LOCALE: &str = "en";
+
+/// Set once per session by JS (e.g. from the browser's language), persisted in local storage
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(from_wasm_abi, into_wasm_abi)]
+pub struct LocaleSettings {
+    /// Language tag, e.g. "en", "de", "fr-CH", "en-IN"
+    pub locale: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PercentageStyle {
+    /// 40%
+    Attached,
+    /// 40 % (non-breaking space)
+    Spaced,
+}
+
+/// What the formatters need to know about the user's locale
+#[derive(Debug, Clone)]
+pub struct UserLocale {
+    /// Decimal and grouping separators, grouping sizes
+    pub number: Locale,
+    pub date_pattern: &'static str,
+    pub date_time_pattern: &'static str,
+    pub percentage_style: PercentageStyle,
+}
+
+impl UserLocale {
+    pub fn decimal_separator(&self) -> &str {
+        self.number.decimal()
+    }
+
+    pub fn format_date(&self, date: &DateTime<Utc>) -> String {
+        date.format(self.date_pattern).to_string()
+    }
+
+    pub fn format_date_time(&self, date: &DateTime<Utc>) -> String {
+        date.format(self.date_time_pattern).to_string()
+    }
+}
+
+/// The user's locale. Formatting is informative, so this falls back to the default locale instead of failing.
+pub fn locale() -> UserLocale {
+    let tag = match storage_get::<LocaleSettings>(LOCALE_KEY) {
+        Ok(settings) => settings.map(|s| s.locale),
+        Err(e) => {
+            log::error!("Couldn't read locale, using default: {e:?}");
+            None
+        }
+    };
+    tag.and_then(|tag| match to_user_locale(&tag) {
+        Ok(locale) => Some(locale),
+        Err(e) => {
+            log::error!("Invalid stored locale, using default: {e:?}");
+            None
+        }
+    })
+    .unwrap_or_else(default_locale)
+}
+
+pub fn locale_settings() -> Result<LocaleSettings> {
+    Ok(storage_get(LOCALE_KEY)?.unwrap_or_else(|| LocaleSettings {
+        locale: DEFAULT_LOCALE.to_owned(),
+    }))
+}
+
+pub fn set_locale(settings: &LocaleSettings) -> Result<()> {
+    // validate before saving
+    to_user_locale(&settings.locale)?;
+    storage_set(LOCALE_KEY, settings)
+}
+
+fn default_locale() -> UserLocale {
+    // the default locale is known to be valid
+    to_user_locale(DEFAULT_LOCALE).expect("Invalid default locale")
+}
+
+pub(crate) fn to_user_locale(tag: &str) -> Result<UserLocale> {
+    let tag = tag.replace('_', "-");
+    let language = tag.split('-').next().unwrap_or_default().to_lowercase();
+
+    // numbers: try the full tag (regional formats differ, e.g. de-CH), then the language
+    let number = Locale::from_name(&tag)
+        .or_else(|_| Locale::from_name(&language))
+        .map_err(|e| anyhow!("Not supported locale: {tag}: {e}"))?;
+
+    // chrono (without unstable locales) only has English day / month names, so other languages use numeric dates
+    let (date_pattern, date_time_pattern) = match language.as_str() {
+        "en" => ("%a %b %e %Y", "%a %b %e %Y %H:%M"),
+        "de" | "ru" | "pl" | "cs" | "fi" | "nb" | "da" | "tr" | "uk" => {
+            ("%d.%m.%Y", "%d.%m.%Y %H:%M")
+        }
+        "ja" | "zh" | "ko" | "sv" | "lt" | "hu" => ("%Y-%m-%d", "%Y-%m-%d %H:%M"),
+        _ => ("%d/%m/%Y", "%d/%m/%Y %H:%M"),
+    };
+
+    let percentage_style = match language.as_str() {
+        "de" | "fr" | "es" | "sv" | "nb" | "fi" | "cs" | "da" | "ru" | "pl" => {
+            PercentageStyle::Spaced
+        }
+        _ => PercentageStyle::Attached,
+    };
+
+    Ok(UserLocale {
+        number,
+        date_pattern,
+        date_time_pattern,
+        percentage_style,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn to_user_locale_en() {
+        let locale = to_user_locale("en").unwrap();
+
+        assert_eq!(".", locale.decimal_separator());
+        assert_eq!(",", locale.number.separator());
+        assert_eq!(PercentageStyle::Attached, locale.percentage_style);
+        assert_eq!(
+            "Sat Dec 31 2022",
+            locale.format_date(&Utc.ymd(2022, 12, 31).and_hms(0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn to_user_locale_uses_the_regional_number_format() {
+        let locale = to_user_locale("de-CH").unwrap();
+
+        // Swiss German groups with apostrophes and uses "." as decimal separator, unlike German
+        assert_eq!(".", locale.decimal_separator());
+        assert!(["'", "’"].contains(&locale.number.separator()));
+        assert_eq!(",", to_user_locale("de").unwrap().decimal_separator());
+        // the rest is by language
+        assert_eq!(PercentageStyle::Spaced, locale.percentage_style);
+        assert_eq!(
+            "31.12.2022",
+            locale.format_date(&Utc.ymd(2022, 12, 31).and_hms(0, 0, 0))
+        );
+
+        // also with underscore
+        assert_eq!(
+            locale.number.separator(),
+            to_user_locale("de_CH").unwrap().number.separator()
+        );
+    }
+
+    #[test]
+    fn to_user_locale_fr() {
+        let locale = to_user_locale("fr").unwrap();
+
+        assert_eq!(",", locale.decimal_separator());
+        assert_eq!(PercentageStyle::Spaced, locale.percentage_style);
+    }
+
+    #[test]
+    fn to_user_locale_falls_back_to_the_language_for_unknown_regions() {
+        let locale = to_user_locale("en-XX").unwrap();
+
+        assert_eq!(".", locale.decimal_separator());
+        assert_eq!(",", locale.number.separator());
+    }
+
+    #[test]
+    fn to_user_locale_fails_with_an_invalid_tag() {
+        assert!(to_user_locale("xx").is_err());
+        assert!(to_user_locale("").is_err());
+    }
+
+    #[test]
+    fn set_locale_rejects_invalid_tags() {
+        assert!(set_locale(&LocaleSettings {
+            locale: "xx".to_owned()
+        })
+        .is_err());
+        // the default is used
+        assert_eq!(".", locale().decimal_separator());
+    }
+}
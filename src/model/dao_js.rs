@@ -1,7 +1,10 @@
 use crate::{
     dependencies::FundsAssetSpecs,
+    js::explorer_links::{explorer_app_link_env, explorer_asset_link_env},
+    locale::locale,
     service::number_formats::{
-        base_units_to_display_units_readable, base_units_to_display_units_str, format_u64_readable,
+        base_units_to_display_units_number_str, base_units_to_display_units_readable,
+        format_u64_readable,
     },
 };
 use anyhow::{anyhow, Result};
@@ -25,11 +28,16 @@ pub struct DaoJs {
     pub share_price: String,
     pub share_price_number_algo: String,
     pub shares_asset_id: String,
+    pub shares_asset_explorer_link: String,
+    pub funds_asset_id: String,
+    /// Unit name of the funds asset (e.g. "USDC"), to display next to the amounts
+    pub funds_asset_unit: String,
     pub image_url: Option<String>,
     // TODO this is optional too, make it optional everywhere
     pub social_media_url: String,
     pub app_id: String,
     pub app_address: String,
+    pub app_explorer_link: String,
     pub invest_link: String,
     pub my_investment_link: String,
     pub my_investment_link_rel: String,
@@ -52,6 +60,16 @@ pub trait ToDaoJs {
 
 impl ToDaoJs for Dao {
     fn to_js(&self, funds_asset_specs: &FundsAssetSpecs) -> Result<DaoJs> {
+        // the amounts would be formatted with the wrong decimals
+        if funds_asset_specs.id != self.funds_asset_id {
+            return Err(anyhow!(
+                "Funds asset specs ({:?}) don't belong to the dao's funds asset ({:?})",
+                funds_asset_specs.id,
+                self.funds_asset_id
+            ));
+        }
+
+        let locale = locale();
         let dao_id_str = self.id().to_string();
         let total_raisable = FundsAmount::new(
             self.token_supply
@@ -82,9 +100,13 @@ impl ToDaoJs for Dao {
         Ok(DaoJs {
             name: self.name.clone(),
             descr_url: self.descr_url.clone(),
-            share_price: base_units_to_display_units_readable(self.share_price, funds_asset_specs)?,
+            share_price: base_units_to_display_units_readable(
+                self.share_price,
+                funds_asset_specs,
+                &locale,
+            )?,
             share_asset_name: self.token_name.clone(),
-            share_supply: format_u64_readable(self.token_supply.val())?,
+            share_supply: format_u64_readable(self.token_supply.val(), &locale)?,
             share_supply_number: self.token_supply.val().to_string(),
             investors_share: self.investors_share.value().to_string(),
             // TODO remove and use the nft url (uncomment line below)
@@ -92,8 +114,12 @@ impl ToDaoJs for Dao {
             image_url: self.image_nft.clone().map(|nft| nft.url),
             social_media_url: self.social_media_url.clone(),
             shares_asset_id: self.shares_asset_id.to_string(),
+            shares_asset_explorer_link: explorer_asset_link_env(self.shares_asset_id),
+            funds_asset_id: self.funds_asset_id.0.to_string(),
+            funds_asset_unit: funds_asset_specs.unit_name.clone(),
             app_id: self.app_id.to_string(),
             app_address: self.app_id.address().to_string(),
+            app_explorer_link: explorer_app_link_env(self.app_id.0),
             // invest_link: format!("/{}/invest", dao_id_str),
             // for now just the dao, because we don't have a dedicated investing view anymore and the embedded view is not linked
             invest_link: format!("/{}", dao_id_str),
@@ -101,7 +127,7 @@ impl ToDaoJs for Dao {
             my_investment_link_rel: format!("investment/{}", dao_id_str),
             dao_link: format!("/{}", dao_id_str),
             creator_address: self.owner.to_string(),
-            share_price_number_algo: base_units_to_display_units_str(
+            share_price_number_algo: base_units_to_display_units_number_str(
                 self.share_price,
                 funds_asset_specs,
             ),
@@ -110,10 +136,12 @@ impl ToDaoJs for Dao {
             raise_min_target: base_units_to_display_units_readable(
                 self.raise_min_target,
                 funds_asset_specs,
+                &locale,
             )?,
             total_raisable: base_units_to_display_units_readable(
                 total_raisable,
                 funds_asset_specs,
+                &locale,
             )?,
             total_raisable_number: total_raisable.val().to_string(),
             funds_raised: funds_raised.to_string(),
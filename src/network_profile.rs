@@ -0,0 +1,327 @@
+use crate::service::storage::{storage_get, storage_set};
+use anyhow::{anyhow, Error, Result};
+use mbase::{
+    dependencies::{network, Network},
+    models::{capi_deps::CapiAddress, funds::FundsAssetId},
+};
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+/// Local storage key for the network profile selected at runtime
+const NETWORK_PROFILE_KEY: &str = "NETWORK_PROFILE";
+
+#[derive(Tsify, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkProfileId {
+    MainNet,
+    TestNet,
+    BetaNet,
+    /// Private / sandbox network
+    Local,
+}
+
+#[derive(Tsify, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExplorerProvider {
+    AlgoExplorer,
+    Pera,
+    Custom(ExplorerTemplates),
+}
+
+/// Link templates, "{}" is replaced with the tx id / address / asset id / application id
+#[derive(Tsify, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExplorerTemplates {
+    pub tx: String,
+    pub address: String,
+    pub asset: String,
+    pub application: String,
+}
+
+impl ExplorerTemplates {
+    fn with_base_url(base_url: &str) -> ExplorerTemplates {
+        ExplorerTemplates {
+            tx: format!("{base_url}tx/{{}}"),
+            address: format!("{base_url}address/{{}}"),
+            asset: format!("{base_url}asset/{{}}"),
+            application: format!("{base_url}application/{{}}"),
+        }
+    }
+}
+
+/// Settings saved in local storage. Everything except the network is optional and falls back to the network's defaults.
+/// Networks other than local have no default capi address, and betanet no default funds asset: these have to be set.
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(from_wasm_abi, into_wasm_abi)]
+pub struct NetworkProfileSettings {
+    pub network: NetworkProfileId,
+    pub explorer: Option<ExplorerProvider>,
+    pub funds_asset_id: Option<String>,
+    pub capi_address: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NetworkProfile {
+    pub id: NetworkProfileId,
+    pub explorer: ExplorerTemplates,
+    /// The set one, or the network's default.
+    /// None: use the environment's funds asset id (only valid if not selected at runtime, or local)
+    pub funds_asset_id: Option<FundsAssetId>,
+    /// None: use the environment's capi address (only valid if not selected at runtime, or local)
+    pub capi_address: Option<CapiAddress>,
+    /// Whether the profile was selected at runtime - if not, everything is determined by the build's environment
+    pub selected: bool,
+}
+
+/// Node used by a runtime selected profile. None for local: the build's network config is used.
+pub struct NodeConfig {
+    pub algod_url: &'static str,
+    pub indexer_url: &'static str,
+}
+
+impl NetworkProfile {
+    pub fn node(&self) -> Option<NodeConfig> {
+        match self.id {
+            NetworkProfileId::MainNet => Some(NodeConfig {
+                algod_url: "https://mainnet-api.algonode.cloud",
+                indexer_url: "https://mainnet-idx.algonode.cloud",
+            }),
+            NetworkProfileId::TestNet => Some(NodeConfig {
+                algod_url: "https://testnet-api.algonode.cloud",
+                indexer_url: "https://testnet-idx.algonode.cloud",
+            }),
+            NetworkProfileId::BetaNet => Some(NodeConfig {
+                algod_url: "https://betanet-api.algonode.cloud",
+                indexer_url: "https://betanet-idx.algonode.cloud",
+            }),
+            NetworkProfileId::Local => None,
+        }
+    }
+
+    /// Whether the build's environment (FUNDS_ASSET_ID, CAPI_ADDRESS) applies to this profile:
+    /// it's configured for the build's network, which a profile selected at runtime may not be
+    pub fn uses_build_env(&self) -> bool {
+        !self.selected || self.id == NetworkProfileId::Local
+    }
+}
+
+/// The profile selected at runtime, or if none was selected, the one corresponding to the build's network
+pub fn network_profile() -> Result<NetworkProfile> {
+    let settings: Option<NetworkProfileSettings> = storage_get(NETWORK_PROFILE_KEY)?;
+    match settings {
+        Some(settings) => to_profile(&settings, true),
+        None => to_profile(&default_settings(), false),
+    }
+}
+
+/// Explorer of the current profile. Explorer links are informative, so this falls back to the build network's explorer instead of failing.
+pub fn explorer() -> ExplorerTemplates {
+    match network_profile() {
+        Ok(profile) => profile.explorer,
+        Err(e) => {
+            log::error!("Couldn't read network profile, using default explorer: {e:?}");
+            let settings = default_settings();
+            explorer_templates(settings.network, &ExplorerProvider::AlgoExplorer)
+        }
+    }
+}
+
+pub fn network_profile_settings() -> Result<NetworkProfileSettings> {
+    Ok(storage_get(NETWORK_PROFILE_KEY)?.unwrap_or_else(default_settings))
+}
+
+pub fn set_network_profile(settings: &NetworkProfileSettings) -> Result<()> {
+    // validate before saving
+    let profile = to_profile(settings, true)?;
+    if !profile.uses_build_env() {
+        if profile.funds_asset_id.is_none() {
+            return Err(anyhow!(
+                "Please set the funds asset id for network: {:?}",
+                profile.id
+            ));
+        }
+        if profile.capi_address.is_none() {
+            return Err(anyhow!(
+                "Please set the capi address for network: {:?}",
+                profile.id
+            ));
+        }
+    }
+    storage_set(NETWORK_PROFILE_KEY, settings)
+}
+
+fn default_settings() -> NetworkProfileSettings {
+    let network = match network() {
+        Network::Private | Network::SandboxPrivate => NetworkProfileId::Local,
+        Network::Test => NetworkProfileId::TestNet,
+    };
+    NetworkProfileSettings {
+        network,
+        explorer: None,
+        funds_asset_id: None,
+        capi_address: None,
+    }
+}
+
+fn to_profile(settings: &NetworkProfileSettings, selected: bool) -> Result<NetworkProfile> {
+    let explorer_provider = settings
+        .explorer
+        .clone()
+        .unwrap_or(ExplorerProvider::AlgoExplorer);
+
+    Ok(NetworkProfile {
+        id: settings.network,
+        explorer: explorer_templates(settings.network, &explorer_provider),
+        funds_asset_id: match &settings.funds_asset_id {
+            Some(id) => Some(FundsAssetId(id.parse().map_err(Error::msg)?)),
+            None if selected => default_funds_asset_id(settings.network),
+            None => None,
+        },
+        capi_address: match &settings.capi_address {
+            Some(address) => Some(CapiAddress(address.parse().map_err(Error::msg)?)),
+            None => None,
+        },
+        selected,
+    })
+}
+
+/// USDC, for the networks where it exists
+fn default_funds_asset_id(network: NetworkProfileId) -> Option<FundsAssetId> {
+    match network {
+        NetworkProfileId::MainNet => Some(FundsAssetId(31566704)),
+        NetworkProfileId::TestNet => Some(FundsAssetId(10458941)),
+        NetworkProfileId::BetaNet | NetworkProfileId::Local => None,
+    }
+}
+
+fn explorer_templates(network: NetworkProfileId, provider: &ExplorerProvider) -> ExplorerTemplates {
+    let base_url = match (provider, network) {
+        (ExplorerProvider::Custom(templates), _) => return templates.clone(),
+        (ExplorerProvider::AlgoExplorer, NetworkProfileId::MainNet) => "https://algoexplorer.io/",
+        (ExplorerProvider::Pera, NetworkProfileId::MainNet) => "https://explorer.perawallet.app/",
+        (ExplorerProvider::Pera, NetworkProfileId::TestNet) => {
+            "https://testnet.explorer.perawallet.app/"
+        }
+        // Pera doesn't support betanet
+        (_, NetworkProfileId::BetaNet) => "https://betanet.algoexplorer.io/",
+        // No explorer for local network - we just test that it opens and searches
+        (_, NetworkProfileId::TestNet) | (_, NetworkProfileId::Local) => {
+            "https://testnet.algoexplorer.io/"
+        }
+    };
+    ExplorerTemplates::with_base_url(base_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_node::fixtures::CAPI_ADDRESS;
+
+    fn settings(
+        network: NetworkProfileId,
+        funds_asset_id: Option<&str>,
+        capi_address: Option<&str>,
+    ) -> NetworkProfileSettings {
+        NetworkProfileSettings {
+            network,
+            explorer: None,
+            funds_asset_id: funds_asset_id.map(|s| s.to_owned()),
+            capi_address: capi_address.map(|s| s.to_owned()),
+        }
+    }
+
+    #[test]
+    fn set_network_profile_requires_capi_address_for_non_local_networks() {
+        for network in [
+            NetworkProfileId::MainNet,
+            NetworkProfileId::TestNet,
+            NetworkProfileId::BetaNet,
+        ] {
+            let res = set_network_profile(&settings(network, Some("123"), None));
+            assert!(res.is_err(), "{network:?}");
+        }
+        assert!(storage_get::<NetworkProfileSettings>(NETWORK_PROFILE_KEY)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn set_network_profile_requires_funds_asset_without_default() {
+        let res = set_network_profile(&settings(
+            NetworkProfileId::BetaNet,
+            None,
+            Some(CAPI_ADDRESS),
+        ));
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn set_network_profile_uses_usdc_as_default_funds_asset() {
+        set_network_profile(&settings(
+            NetworkProfileId::MainNet,
+            None,
+            Some(CAPI_ADDRESS),
+        ))
+        .unwrap();
+
+        let profile = network_profile().unwrap();
+        assert_eq!(NetworkProfileId::MainNet, profile.id);
+        assert_eq!(Some(FundsAssetId(31566704)), profile.funds_asset_id);
+        assert_eq!(
+            Some(CAPI_ADDRESS.to_owned()),
+            profile.capi_address.map(|a| a.0.to_string())
+        );
+        assert!(profile.selected);
+        assert!(!profile.uses_build_env());
+    }
+
+    #[test]
+    fn set_network_profile_accepts_local_without_funds_asset_and_capi_address() {
+        set_network_profile(&settings(NetworkProfileId::Local, None, None)).unwrap();
+
+        let profile = network_profile().unwrap();
+        assert_eq!(None, profile.funds_asset_id);
+        assert!(profile.uses_build_env());
+        assert!(profile.node().is_none());
+    }
+
+    #[test]
+    fn set_network_profile_rejects_invalid_capi_address() {
+        let res = set_network_profile(&settings(
+            NetworkProfileId::TestNet,
+            None,
+            Some("not an address"),
+        ));
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn explorer_uses_custom_templates_as_they_are() {
+        let templates = ExplorerTemplates {
+            tx: "https://my.explorer/t/{}".to_owned(),
+            address: "https://my.explorer/a/{}".to_owned(),
+            asset: "https://my.explorer/as/{}".to_owned(),
+            application: "https://my.explorer/ap/{}".to_owned(),
+        };
+        set_network_profile(&NetworkProfileSettings {
+            explorer: Some(ExplorerProvider::Custom(templates.clone())),
+            ..settings(NetworkProfileId::TestNet, None, Some(CAPI_ADDRESS))
+        })
+        .unwrap();
+
+        assert_eq!(templates, explorer());
+    }
+
+    #[test]
+    fn explorer_templates_of_providers() {
+        assert_eq!(
+            "https://explorer.perawallet.app/tx/{}",
+            explorer_templates(NetworkProfileId::MainNet, &ExplorerProvider::Pera).tx
+        );
+        // Pera doesn't support betanet
+        assert_eq!(
+            "https://betanet.algoexplorer.io/address/{}",
+            explorer_templates(NetworkProfileId::BetaNet, &ExplorerProvider::Pera).address
+        );
+    }
+}
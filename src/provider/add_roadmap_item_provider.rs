@@ -15,6 +15,11 @@ use super::providers;
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 pub trait AddRoadmapItemProvider {
     async fn txs(&self, pars: AddRoadmapItemParJs) -> Result<AddRoadmapItemResJs, FrError>;
+    /// Edit, complete, reopen or remove an item. Submitted like additions, with [submit_add_roadmap_item].
+    async fn change_txs(
+        &self,
+        pars: ChangeRoadmapItemParJs,
+    ) -> Result<AddRoadmapItemResJs, FrError>;
     async fn submit(
         &self,
         pars: SubmitAddRoadmapItemParJs,
@@ -28,7 +33,39 @@ pub struct AddRoadmapItemParJs {
     pub dao_id: String,
     pub title: String,
     pub date: String,
+    /// Tx id of the parent item (e.g. an epic), if it's a sub-item
     pub parent: Option<String>,
+    pub description: Option<String>,
+    /// http(s) url
+    pub link: Option<String>,
+}
+
+#[derive(Tsify, Debug, Clone, Deserialize)]
+#[tsify(from_wasm_abi)]
+pub struct ChangeRoadmapItemParJs {
+    pub creator_address: String,
+    pub dao_id: String,
+    /// Tx id of the item (as in the roadmap, i.e. of its addition)
+    pub item_tx_id: String,
+    pub change: RoadmapItemChangeJs,
+}
+
+#[derive(Tsify, Debug, Clone, Deserialize)]
+pub enum RoadmapItemChangeJs {
+    Edit {
+        title: String,
+        /// Timestamp (seconds), like the addition's date
+        date: String,
+        /// Replaces the current description / link (None removes them)
+        description: Option<String>,
+        link: Option<String>,
+    },
+    Complete {
+        /// Timestamp (seconds). Default: now.
+        date: Option<String>,
+    },
+    Reopen,
+    Remove,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
@@ -57,6 +94,16 @@ pub async fn add_roadmap_item(pars: AddRoadmapItemParJs) -> Result<AddRoadmapIte
     .await
 }
 
+#[wasm_bindgen(js_name=changeRoadmapItem)]
+pub async fn change_roadmap_item(
+    pars: ChangeRoadmapItemParJs,
+) -> Result<AddRoadmapItemResJs, FrError> {
+    log_wrap_new("change_roadmap_item", pars, async move |pars| {
+        providers()?.add_roadmap_item.change_txs(pars).await
+    })
+    .await
+}
+
 #[wasm_bindgen(js_name=submitAddRoadmapItem)]
 pub async fn submit_add_roadmap_item(
     pars: SubmitAddRoadmapItemParJs,
@@ -20,6 +20,8 @@ pub trait BalanceProvider {
 #[tsify(from_wasm_abi)]
 pub struct BalanceParJs {
     pub address: String,
+    /// The funds asset to show the balance of (e.g. the one of the DAO being viewed). None: default funds asset.
+    pub funds_asset_id: Option<String>,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
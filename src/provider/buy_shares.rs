@@ -7,6 +7,7 @@ use crate::{
         inputs_validation_js::{to_validation_error_js, ValidationErrorJs},
         to_sign_js::ToSignJs,
     },
+    service::passthrough::Passthrough,
 };
 use anyhow::Result;
 use async_trait::async_trait;
@@ -65,6 +66,16 @@ pub struct SubmitBuySharesParJs {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmitBuySharesPassthroughParJs {
     pub dao_msg_pack: Vec<u8>,
+    /// See [crate::service::passthrough]
+    pub mac: String,
+}
+
+impl Passthrough for SubmitBuySharesPassthroughParJs {
+    const OPERATION: &'static str = "buy_shares";
+
+    fn mac_mut(&mut self) -> &mut String {
+        &mut self.mac
+    }
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
@@ -30,6 +30,8 @@ pub struct CalculateTotalPriceParJs {
     pub locked_shares: Option<String>,
     pub investors_share: String,
     pub share_price: String,
+    /// The DAO's funds asset. None: default funds asset.
+    pub funds_asset_id: Option<String>,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
@@ -45,6 +47,8 @@ pub struct CalculateTotalPriceResJs {
 pub struct CalculateMaxFundsParJs {
     pub shares_amount: String,
     pub share_price: String,
+    /// Funds asset selected in the create dao form. None: default funds asset.
+    pub funds_asset_id: Option<String>,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
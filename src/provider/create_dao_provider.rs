@@ -6,7 +6,9 @@ use crate::js::common::SignedTxFromJs;
 use crate::js::to_sign_js::ToSignJs;
 use crate::model::dao_js::DaoJs;
 use crate::service::number_formats::validate_funds_amount_input;
+use crate::service::passthrough::Passthrough;
 use algonaut::core::Address;
+use algonaut::crypto::HashDigest;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use chrono::Utc;
@@ -45,6 +47,8 @@ pub struct ValidatedDaoInputs {
     pub share_price: FundsAmount,
     pub investors_share: SharesPercentage,
     pub image_url: Option<String>,
+    /// Saved as the image NFT's metadata hash. Set after the validation, when the image is fetched.
+    pub image_hash: Option<HashDigest>,
     pub social_media_url: String,
     pub min_raise_target: FundsAmount,
     pub min_raise_target_end_date: Timestamp,
@@ -84,6 +88,8 @@ pub struct CreateDaoFormInputsJs {
     pub prospectus_bytes: Option<Vec<u8>>,
     pub min_invest_amount: String,
     pub max_invest_amount: String,
+    /// The asset the DAO raises and receives funds in. None: default funds asset.
+    pub funds_asset_id: Option<String>,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
@@ -118,6 +124,7 @@ pub fn validated_inputs_to_dao_specs(inputs: &ValidatedDaoInputs) -> Result<Setu
         inputs.investors_share,
         inputs.share_price,
         inputs.image_url.clone(),
+        inputs.image_hash.clone(),
         inputs.social_media_url.clone(),
         inputs.shares_for_investors,
         inputs.min_raise_target,
@@ -241,6 +248,7 @@ pub fn validate_dao_inputs(
                 min_raise_target: *min_raise_target,
                 min_raise_target_end_date: *min_raise_target_end_date,
                 image_url: image_url.clone(),
+                image_hash: None,
                 prospectus_url: prospectus_url.clone(),
                 prospectus_bytes: prospectus_bytes.clone(),
                 min_invest_amount: *min_invest_amount,
@@ -282,6 +290,16 @@ pub struct CreateDaoParJs {
 #[tsify(from_wasm_abi, into_wasm_abi)]
 pub struct CreateDaoPassthroughParJs {
     pub inputs: CreateDaoFormInputsJs,
+    /// See [crate::service::passthrough]
+    pub mac: String,
+}
+
+impl Passthrough for CreateDaoPassthroughParJs {
+    const OPERATION: &'static str = "create_assets";
+
+    fn mac_mut(&mut self) -> &mut String {
+        &mut self.mac
+    }
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
@@ -379,8 +397,12 @@ pub fn validate_url_opt(url: &Option<String>) -> Result<Option<String>, Validati
     }
 }
 
+/// The image url is saved as the url of the image NFT, which is limited to 96 bytes
 pub fn validate_image_url(url: &Option<String>) -> Result<Option<String>, ValidationError> {
-    validate_url_opt(url)
+    match url {
+        Some(url) => Ok(Some(validate_text_min_max_length(url, 0, 96)?)),
+        None => Ok(None),
+    }
 }
 
 pub fn validate_prospectus_url(url: &Option<String>) -> Result<Option<String>, ValidationError> {
@@ -531,8 +553,19 @@ pub struct SubmitSetupDaoPassthroughParJs {
     pub creator: String,
     pub shares_asset_id: u64,
     pub app_id: u64,
+    pub funds_asset_id: u64,
     pub description_url: Option<String>,
     pub setup_date: String,
+    /// See [crate::service::passthrough]
+    pub mac: String,
+}
+
+impl Passthrough for SubmitSetupDaoPassthroughParJs {
+    const OPERATION: &'static str = "create_dao";
+
+    fn mac_mut(&mut self) -> &mut String {
+        &mut self.mac
+    }
 }
 
 #[wasm_bindgen(js_name=createDao)]
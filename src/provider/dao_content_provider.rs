@@ -0,0 +1,42 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{error::FrError, js::bridge::log_wrap_new, service::verified_content::ContentStatusJs};
+
+use super::providers;
+
+/// Checks the DAO's off-chain content against the on-chain hashes, see [crate::service::verified_content]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait DaoContentProvider {
+    async fn verify(&self, pars: VerifyDaoContentParJs) -> Result<VerifyDaoContentResJs, FrError>;
+}
+
+#[derive(Tsify, Debug, Clone, Deserialize)]
+#[tsify(from_wasm_abi)]
+pub struct VerifyDaoContentParJs {
+    pub dao_id: String,
+}
+
+/// None: the DAO doesn't have the content
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct VerifyDaoContentResJs {
+    pub description: Option<ContentStatusJs>,
+    pub image: Option<ContentStatusJs>,
+    pub team: Option<ContentStatusJs>,
+    pub prospectus: Option<ContentStatusJs>,
+}
+
+#[wasm_bindgen(js_name=verifyDaoContent)]
+pub async fn verify_dao_content(
+    pars: VerifyDaoContentParJs,
+) -> Result<VerifyDaoContentResJs, FrError> {
+    log_wrap_new("verify_dao_content", pars, async move |pars| {
+        providers()?.dao_content.verify(pars).await
+    })
+    .await
+}
@@ -0,0 +1,152 @@
+use super::{
+    balance_provider::{BalanceChangeParJs, BalanceChangeResJs},
+    funds_raising_provider::{FundsRaisingParsJs, FundsRaisingResJs},
+    holders_count_provider::{HoldersCountParJs, HoldersCountResJs},
+    investment_provider::{
+        AvailableSharesParJs, AvailableSharesResJs, LoadInvestorParJs, LoadInvestorResJs,
+    },
+    providers,
+    view_dao_provider::{ViewDaoParJs, ViewDaoResJs},
+};
+use crate::{error::FrError, js::bridge::log_wrap_new};
+use anyhow::Result;
+use futures::try_join;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[derive(Tsify, Debug, Clone, Deserialize)]
+#[tsify(from_wasm_abi)]
+pub struct DaoPageParJs {
+    pub dao_id: String,
+    /// Set if a wallet is connected: the investment of this address is included
+    pub investor_address: Option<String>,
+}
+
+/// Everything the DAO page shows, fetched concurrently
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct DaoPageResJs {
+    pub view: ViewDaoResJs,
+    pub available_shares: AvailableSharesResJs,
+    /// Set if an investor address was passed
+    pub investment: Option<LoadInvestorResJs>,
+    pub funds_raising: FundsRaisingResJs,
+    pub balance_change: BalanceChangeResJs,
+    pub holders_count: HoldersCountResJs,
+}
+
+/// All or nothing: if a section fails, the page fails with the section's error
+/// (without waiting for the other sections). JS can fall back to the individual providers to show partial pages.
+#[wasm_bindgen(js_name=loadDaoPage)]
+pub async fn load_dao_page(pars: DaoPageParJs) -> Result<DaoPageResJs, FrError> {
+    log_wrap_new("load_dao_page", pars, async move |pars| {
+        let providers = providers()?;
+        let dao_id = &pars.dao_id;
+
+        // the holders count needs the shares asset id, which we get with the view
+        let view_and_holders_count = async {
+            let view = providers
+                .view_dao
+                .get(ViewDaoParJs {
+                    dao_id: dao_id.clone(),
+                })
+                .await?;
+            let holders_count = providers
+                .holders_count
+                .get(HoldersCountParJs {
+                    asset_id: view.dao.shares_asset_id.clone(),
+                    app_id: view.dao.app_id.clone(),
+                })
+                .await?;
+            Ok((view, holders_count))
+        };
+
+        let investment = async {
+            match &pars.investor_address {
+                Some(investor_address) => providers
+                    .investment
+                    .get_investor_data(LoadInvestorParJs {
+                        dao_id: dao_id.clone(),
+                        investor_address: investor_address.clone(),
+                    })
+                    .await
+                    .map(Some),
+                None => Ok(None),
+            }
+        };
+
+        let ((view, holders_count), available_shares, investment, funds_raising, balance_change) =
+            try_join!(
+                view_and_holders_count,
+                providers.investment.available_shares(AvailableSharesParJs {
+                    dao_id: dao_id.clone(),
+                }),
+                investment,
+                providers.raised.data(FundsRaisingParsJs {
+                    dao_id: dao_id.clone(),
+                }),
+                providers.balance.get_balance_change(BalanceChangeParJs {
+                    dao_id: dao_id.clone(),
+                }),
+            )?;
+
+        Ok(DaoPageResJs {
+            view,
+            available_shares,
+            investment,
+            funds_raising,
+            balance_change,
+            holders_count,
+        })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dependencies::set_data_type,
+        fake_node::{
+            fixtures::{
+                dao_chain, set_investor_shares, use_fixture_network_profile, DAO_APP_ID, INVESTOR,
+                SHARES_ASSET_ID,
+            },
+            FakeNode,
+        },
+    };
+
+    fn pars(investor_address: Option<&str>) -> DaoPageParJs {
+        DaoPageParJs {
+            dao_id: DAO_APP_ID.to_string(),
+            investor_address: investor_address.map(|a| a.to_owned()),
+        }
+    }
+
+    #[tokio::test]
+    async fn load_dao_page_returns_all_the_sections() {
+        let mut chain = dao_chain();
+        set_investor_shares(&mut chain, 10);
+        chain.set_asset_holding(INVESTOR, SHARES_ASSET_ID, 0);
+        let _node = FakeNode::start(chain).unwrap();
+        use_fixture_network_profile();
+        set_data_type(Some("real")).unwrap();
+
+        let page = load_dao_page(pars(Some(INVESTOR))).await.unwrap();
+
+        assert_eq!(page.view.dao.app_id, DAO_APP_ID.to_string());
+        assert!(page.investment.is_some());
+    }
+
+    #[tokio::test]
+    async fn load_dao_page_fails_if_a_section_fails() {
+        let _node = FakeNode::start(dao_chain()).unwrap();
+        use_fixture_network_profile();
+        set_data_type(Some("real")).unwrap();
+
+        // only the investment section fails
+        assert!(load_dao_page(pars(Some("not an address"))).await.is_err());
+        assert!(load_dao_page(pars(None)).await.is_ok());
+    }
+}
@@ -1,22 +1,21 @@
+use crate::dependencies::algod;
 use crate::error::FrError;
-use crate::js::common::signed_js_tx_to_signed_tx1;
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs};
 use crate::js::to_sign_js::ToSignJs;
 use crate::provider::add_roadmap_item_provider::{
     AddRoadmapItemParJs, AddRoadmapItemResJs, SubmitAddRoadmapItemParJs,
 };
 use crate::provider::add_roadmap_item_provider::{
-    AddRoadmapItemProvider, SubmitAddRoadmapItemResJs,
+    AddRoadmapItemProvider, ChangeRoadmapItemParJs, RoadmapItemChangeJs, SubmitAddRoadmapItemResJs,
 };
-use algonaut::crypto::HashDigest;
-use anyhow::{anyhow, Error, Result};
+use crate::service::roadmap::{tx_id_to_hash, RoadmapItemPayload, RoadmapOp};
+use anyhow::{Error, Result};
 use async_trait::async_trait;
 use base::roadmap::add_roadmap_item::{
     add_roadmap_item, submit_add_roadmap_item, AddRoadmapItemToSigned, RoadmapItemInputs,
 };
-use data_encoding::BASE64;
-use mbase::date_util::timestamp_seconds_to_date;
-use mbase::dependencies::algod;
-use std::convert::TryInto;
+use chrono::Utc;
+use mbase::{date_util::timestamp_seconds_to_date, models::tx_id::TxId};
 
 pub struct AddRoadmapItemProviderDef {}
 
@@ -29,16 +28,23 @@ impl AddRoadmapItemProvider for AddRoadmapItemProviderDef {
         let dao_creator = pars.creator_address.parse().map_err(Error::msg)?;
         let dao_id = pars.dao_id.parse()?;
 
-        let parent_hash = hash_str_option_to_hash_option(pars.parent)?;
+        let parent_hash = match &pars.parent {
+            Some(parent) => Some(tx_id_to_hash(&parent.parse()?)?),
+            None => None,
+        };
 
         let date = timestamp_seconds_to_date(pars.date.parse()?)?;
 
+        let message = format!("Add roadmap item: {}", pars.title);
+
         let to_sign = add_roadmap_item(
             &algod,
             &dao_creator,
             &RoadmapItemInputs {
                 dao_id,
-                title: pars.title,
+                title: RoadmapItemPayload::new(RoadmapOp::Add, pars.title)
+                    .with_details(pars.description, pars.link)
+                    .to_title()?,
                 parent: Box::new(parent_hash),
                 date,
             },
@@ -46,7 +52,67 @@ impl AddRoadmapItemProvider for AddRoadmapItemProviderDef {
         .await?;
 
         Ok(AddRoadmapItemResJs {
-            to_sign: ToSignJs::new(vec![to_sign.tx])?,
+            to_sign: ToSignJs::with_messages(vec![(to_sign.tx, message)]).await?,
+        })
+    }
+
+    async fn change_txs(
+        &self,
+        pars: ChangeRoadmapItemParJs,
+    ) -> Result<AddRoadmapItemResJs, FrError> {
+        let algod = algod();
+
+        let dao_creator = pars.creator_address.parse().map_err(Error::msg)?;
+        let dao_id = pars.dao_id.parse()?;
+        let item_tx_id: TxId = pars.item_tx_id.parse()?;
+
+        // the date isn't used for reopen / remove, but base requires one
+        let (payload, date, message) = match pars.change {
+            RoadmapItemChangeJs::Edit {
+                title,
+                date,
+                description,
+                link,
+            } => (
+                RoadmapItemPayload::new(RoadmapOp::Edit, title.clone())
+                    .with_details(description, link),
+                timestamp_seconds_to_date(date.parse()?)?,
+                format!("Edit roadmap item: {title}"),
+            ),
+            RoadmapItemChangeJs::Complete { date } => (
+                RoadmapItemPayload::new(RoadmapOp::Complete, "".to_owned()),
+                match date {
+                    Some(date) => timestamp_seconds_to_date(date.parse()?)?,
+                    None => Utc::now(),
+                },
+                "Mark roadmap item as completed".to_owned(),
+            ),
+            RoadmapItemChangeJs::Reopen => (
+                RoadmapItemPayload::new(RoadmapOp::Reopen, "".to_owned()),
+                Utc::now(),
+                "Reopen roadmap item".to_owned(),
+            ),
+            RoadmapItemChangeJs::Remove => (
+                RoadmapItemPayload::new(RoadmapOp::Remove, "".to_owned()),
+                Utc::now(),
+                "Remove roadmap item".to_owned(),
+            ),
+        };
+
+        let to_sign = add_roadmap_item(
+            &algod,
+            &dao_creator,
+            &RoadmapItemInputs {
+                dao_id,
+                title: payload.to_title()?,
+                parent: Box::new(Some(tx_id_to_hash(&item_tx_id)?)),
+                date,
+            },
+        )
+        .await?;
+
+        Ok(AddRoadmapItemResJs {
+            to_sign: ToSignJs::with_messages(vec![(to_sign.tx, message)]).await?,
         })
     }
 
@@ -62,6 +128,8 @@ impl AddRoadmapItemProvider for AddRoadmapItemProviderDef {
                 pars.txs.len()
             )));
         }
+
+        verify_signed_js_txs(&pars.txs)?;
         let tx = &pars.txs[0];
 
         let add_roadmap_item_signed_tx = signed_js_tx_to_signed_tx1(tx)?;
@@ -77,20 +145,3 @@ impl AddRoadmapItemProvider for AddRoadmapItemProviderDef {
         Ok(SubmitAddRoadmapItemResJs { tx_id })
     }
 }
-
-fn hash_str_option_to_hash_option(hash_str: Option<String>) -> Result<Option<HashDigest>> {
-    Ok(match &hash_str {
-        Some(hash_str) => {
-            let bytes = BASE64.decode(hash_str.as_bytes())?;
-            Some(HashDigest(bytes.clone().try_into().map_err(|e| {
-                anyhow!(
-                    "Couldn't convert bytes(len: {:?}): {:?} into hash. e: {:?}",
-                    bytes.len(),
-                    bytes,
-                    e
-                )
-            })?))
-        }
-        None => None,
-    })
-}
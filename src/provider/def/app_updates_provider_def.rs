@@ -1,4 +1,6 @@
+use crate::service::dao_cache::dao_global_state;
 use crate::{
+    dependencies::algod,
     error::FrError,
     provider::app_updates_provider::{
         AppUpdatesProvider, CheckForUpdatesParJs, CheckForUpdatesResJs, UpdateDataJs,
@@ -7,10 +9,7 @@ use crate::{
 use anyhow::{Error, Result};
 use async_trait::async_trait;
 use base::{dependencies::teal_api, teal::TealApi};
-use mbase::{
-    api::version::Version, dependencies::algod, models::dao_id::DaoId,
-    state::dao_app_state::dao_global_state,
-};
+use mbase::{api::version::Version, models::dao_id::DaoId};
 
 pub struct AppUpdatesProviderDef {}
 
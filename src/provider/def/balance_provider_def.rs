@@ -1,21 +1,22 @@
+use crate::locale::locale;
 use std::cmp::Ordering;
 
-use crate::dependencies::capi_deps;
+use crate::dependencies::{algod, capi_deps, indexer};
 use crate::error::FrError;
 use crate::model::QuantityChangeJs;
 use crate::provider::balance_provider::{
     BalanceChangeParJs, BalanceChangeResJs, BalanceParJs, BalanceProvider, BalanceResJs,
 };
+use crate::service::dao_cache::dao_global_state;
 use crate::service::number_formats::microalgos_to_algos_str;
-use crate::{
-    dependencies::funds_asset_specs, service::number_formats::base_units_to_display_units_str,
+use crate::service::{
+    funds_asset_specs::funds_asset_specs_for_input, number_formats::base_units_to_display_units_str,
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
 use base::queries::historic_balance::historic_dao_funds_balance;
 use base::state::account_state::{funds_holdings, funds_holdings_from_account};
 use chrono::{Duration, Utc};
-use mbase::dependencies::{algod, indexer};
 use mbase::models::dao_id::DaoId;
 
 pub struct BalanceProviderDef {}
@@ -24,8 +25,10 @@ pub struct BalanceProviderDef {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl BalanceProvider for BalanceProviderDef {
     async fn get(&self, pars: BalanceParJs) -> Result<BalanceResJs, FrError> {
+        let locale = locale();
         let algod = algod();
-        let funds_asset_specs = funds_asset_specs()?;
+        let funds_asset_specs =
+            funds_asset_specs_for_input(&algod, pars.funds_asset_id.as_deref()).await?;
 
         let account = algod
             .account_information(&pars.address.parse().map_err(Error::msg)?)
@@ -36,10 +39,11 @@ impl BalanceProvider for BalanceProviderDef {
         let funds_asset_holdings = funds_holdings_from_account(&account, funds_asset_specs.id)?;
 
         Ok(BalanceResJs {
-            balance_algos: microalgos_to_algos_str(balance),
+            balance_algos: microalgos_to_algos_str(balance, &locale),
             balance_funds_asset: base_units_to_display_units_str(
                 funds_asset_holdings,
                 &funds_asset_specs,
+                &locale,
             ),
         })
     }
@@ -52,26 +56,21 @@ impl BalanceProvider for BalanceProviderDef {
         let algod = algod();
         let indexer = indexer();
         let capi_deps = capi_deps()?;
-        let funds_asset_specs = funds_asset_specs()?;
 
         // let address = pars.address.parse().map_err(Error::msg)?;
         let dao_id: DaoId = pars.dao_id.parse()?;
 
+        let funds_asset_id = dao_global_state(&algod, dao_id.0).await?.funds_asset_id;
+
         let dao_address = dao_id.0.address();
 
         let date = Utc::now() - Duration::weeks(1); // account's balance a week ago
                                                     // let date = Utc::now(); // debugging: use this to get current balance
 
-        let past_balance = historic_dao_funds_balance(
-            &algod,
-            &indexer,
-            funds_asset_specs.id,
-            dao_id,
-            &capi_deps,
-            date,
-        )
-        .await?;
-        let current_balance = funds_holdings(&algod, &dao_address, funds_asset_specs.id).await?;
+        let past_balance =
+            historic_dao_funds_balance(&algod, &indexer, funds_asset_id, dao_id, &capi_deps, date)
+                .await?;
+        let current_balance = funds_holdings(&algod, &dao_address, funds_asset_id).await?;
         log::debug!("past balance: {past_balance:?}");
         log::debug!("current balance: {current_balance:?}");
 
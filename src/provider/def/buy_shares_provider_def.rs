@@ -1,22 +1,28 @@
 use crate::{
-    dependencies::funds_asset_specs,
+    dependencies::algod,
     error::FrError,
     inputs_validation::ValidationError,
-    js::{common::signed_js_tx_to_signed_tx1, to_sign_js::ToSignJs},
+    js::{
+        common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs},
+        to_sign_js::ToSignJs,
+    },
     provider::buy_shares::{
         BuySharesProvider, InvestParJs, InvestResJs, SubmitBuySharesParJs,
         SubmitBuySharesPassthroughParJs, SubmitBuySharesResJs,
     },
     service::{
-        invest_or_lock::submit_apps_optins_from_js, number_formats::validate_share_amount_positive,
+        dao_cache::{dao_investor_state, invalidate_after_submit, load_dao},
+        invest_or_lock::submit_apps_optins_from_js,
+        number_formats::validate_share_amount_positive,
+        passthrough::Passthrough,
     },
 };
-use algonaut::{algod::v2::Algod, core::Address};
+use algonaut::{algod::v2::Algod, core::Address, transaction::Transaction};
 use anyhow::{anyhow, Error, Result};
 use async_trait::async_trait;
 use base::{
     flows::{
-        create_dao::storage::load_dao::load_dao,
+        create_dao::model::Dao,
         invest::{
             invest::{invest_txs, submit_invest},
             model::InvestSigned,
@@ -25,14 +31,10 @@ use base::{
     state::account_state::asset_holdings,
 };
 use mbase::{
-    dependencies::algod,
     models::{
         asset_amount::AssetAmount, dao_id::DaoId, share_amount::ShareAmount, timestamp::Timestamp,
     },
-    state::{
-        app_state::ApplicationLocalStateError,
-        dao_app_state::{dao_investor_state, SignedProspectus},
-    },
+    state::{app_state::ApplicationLocalStateError, dao_app_state::SignedProspectus},
     util::network_util::wait_for_pending_transaction,
 };
 
@@ -43,7 +45,6 @@ pub struct BuySharesProviderDef {}
 impl BuySharesProvider for BuySharesProviderDef {
     async fn txs(&self, pars: InvestParJs) -> Result<InvestResJs, FrError> {
         let algod = algod();
-        let funds_asset_specs = funds_asset_specs()?;
 
         let investor_address = pars.investor_address.parse().map_err(Error::msg)?;
 
@@ -99,29 +100,39 @@ impl BuySharesProvider for BuySharesProviderDef {
             dao.app_id,
             dao.shares_asset_id,
             validated_share_amount,
-            funds_asset_specs.id,
+            dao.funds_asset_id,
             dao.share_price,
             signed_prospectus,
         )
         .await?;
 
+        let message = format!("Buy {validated_share_amount} shares of {}", dao.name);
         let to_sign_txs = vec![
-            to_sign.app_call,
-            to_sign.payment_tx,
-            to_sign.shares_asset_optin_tx,
+            (to_sign.app_call, message.clone()),
+            (to_sign.payment_tx, message),
+            (
+                to_sign.shares_asset_optin_tx,
+                format!("Opt in to the shares of {}", dao.name),
+            ),
         ];
 
+        let txs: Vec<Transaction> = to_sign_txs.iter().map(|(tx, _)| tx.clone()).collect();
+
         Ok(InvestResJs {
-            to_sign: ToSignJs::new(to_sign_txs)?,
+            to_sign: ToSignJs::with_messages(to_sign_txs).await?,
             pt: SubmitBuySharesPassthroughParJs {
                 dao_msg_pack: rmp_serde::to_vec_named(&dao).map_err(Error::msg)?,
-            },
+                mac: String::new(),
+            }
+            .signed(&txs)?,
         })
     }
 
     async fn submit(&self, pars: SubmitBuySharesParJs) -> Result<SubmitBuySharesResJs, FrError> {
+        // the dao is deserialized from the passthrough and trusted from here on
+        pars.pt.verify(&pars.txs)?;
+
         let algod = algod();
-        let funds_asset_specs = funds_asset_specs()?;
 
         if pars.txs.len() != 3 {
             return Err(FrError::Msg(format!(
@@ -130,6 +141,8 @@ impl BuySharesProvider for BuySharesProviderDef {
             )));
         }
 
+        verify_signed_js_txs(&pars.txs)?;
+
         let investor_address = pars.investor_address.parse().map_err(Error::msg)?;
         let buy_total_cost: u64 = pars.buy_total_cost.parse().map_err(Error::msg)?;
 
@@ -137,7 +150,8 @@ impl BuySharesProvider for BuySharesProviderDef {
         let payment_tx = signed_js_tx_to_signed_tx1(&pars.txs[1])?;
         let shares_asset_optin_tx = signed_js_tx_to_signed_tx1(&pars.txs[2])?;
 
-        let dao = rmp_serde::from_slice(&pars.pt.dao_msg_pack).map_err(Error::msg)?;
+        let dao: Dao = rmp_serde::from_slice(&pars.pt.dao_msg_pack).map_err(Error::msg)?;
+        let funds_asset_id = dao.funds_asset_id;
 
         let submit_res = submit_invest(
             &algod,
@@ -148,27 +162,27 @@ impl BuySharesProvider for BuySharesProviderDef {
                 payment_tx,
             },
         )
-        .await;
-
-        if let Some(err) = submit_res.as_ref().err() {
-            if err.to_string().contains("underflow on subtracting") {
-                // what the user has to buy (on-ramp) to do the transaction: the amount they tried to buy - what they have
-                let holdings =
-                    asset_holdings(&algod, &investor_address, funds_asset_specs.id.0).await?;
-                let to_buy = AssetAmount(
-                    buy_total_cost
-                        .checked_sub(holdings.0)
-                        .ok_or(anyhow!("Error subtracting: {buy_total_cost} - {holdings}"))?,
-                );
-                return Err(FrError::NotEnoughFundsAsset {
-                    to_buy: to_buy.to_string(),
-                });
-            }
+        .await
+        .map_err(FrError::from);
+
+        if let Err(FrError::NotEnoughAsset { .. }) = submit_res {
+            // what the user has to buy (on-ramp) to do the transaction: the amount they tried to buy - what they have
+            let holdings = asset_holdings(&algod, &investor_address, funds_asset_id.0).await?;
+            let to_buy = AssetAmount(
+                buy_total_cost
+                    .checked_sub(holdings.0)
+                    .ok_or(anyhow!("Error subtracting: {buy_total_cost} - {holdings}"))?,
+            );
+            return Err(FrError::NotEnoughFundsAsset {
+                to_buy: to_buy.to_string(),
+            });
         }
         let submit_res = submit_res?;
 
         let _ = wait_for_pending_transaction(&algod, &submit_res.tx_id).await?;
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         log::debug!("Submit invest res: {:?}", submit_res);
 
         Ok(SubmitBuySharesResJs {
@@ -212,3 +226,106 @@ struct IfBuySharesCalculation {
     currently_owned: ShareAmount,
     total_if_buy: ShareAmount,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fake_node::{
+            fixtures::{
+                app_address, dao_chain, to_sign_txs, use_fixture_network_profile, DAO_APP_ID,
+                FUNDS_ASSET_ID, INVESTOR, SHARES_ASSET_ID, SHARE_PRICE,
+            },
+            FakeNode,
+        },
+        provider::buy_shares::SignedProspectusJs,
+    };
+    use algonaut::transaction::transaction::TransactionType;
+
+    fn invest_pars(share_count: &str) -> InvestParJs {
+        InvestParJs {
+            dao_id: DAO_APP_ID.to_string(),
+            share_count: share_count.to_owned(),
+            investor_address: INVESTOR.to_owned(),
+            app_opt_ins: None,
+            available_shares: "500".to_owned(),
+            signed_prospectus: SignedProspectusJs {
+                url: "https://prospectus.com".to_owned(),
+                hash: "hash".to_owned(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn txs_returns_app_call_payment_and_shares_optin() {
+        let _node = FakeNode::start(dao_chain()).unwrap();
+        use_fixture_network_profile();
+
+        let res = BuySharesProviderDef {}
+            .txs(invest_pars("10"))
+            .await
+            .unwrap();
+
+        let txs = to_sign_txs(&res.to_sign);
+        assert_eq!(3, txs.len());
+        let investor: Address = INVESTOR.parse().unwrap();
+
+        match &txs[0].txn_type {
+            TransactionType::ApplicationCallTransaction(call) => {
+                assert_eq!(investor, call.sender);
+                assert_eq!(Some(DAO_APP_ID), call.app_id);
+            }
+            other => panic!("Expected app call, got: {other:?}"),
+        }
+        match &txs[1].txn_type {
+            TransactionType::AssetTransferTransaction(xfer) => {
+                assert_eq!(investor, xfer.sender);
+                assert_eq!(app_address(), xfer.receiver.to_string());
+                assert_eq!(FUNDS_ASSET_ID, xfer.xfer);
+                assert_eq!(10 * SHARE_PRICE, xfer.amount);
+            }
+            other => panic!("Expected funds asset transfer, got: {other:?}"),
+        }
+        match &txs[2].txn_type {
+            TransactionType::AssetAcceptTransaction(accept) => {
+                assert_eq!(investor, accept.sender);
+                assert_eq!(SHARES_ASSET_ID, accept.xfer);
+            }
+            other => panic!("Expected shares opt-in, got: {other:?}"),
+        }
+
+        assert!(txs[0].group.is_some());
+        assert!(txs.iter().all(|tx| tx.group == txs[0].group));
+    }
+
+    #[tokio::test]
+    async fn txs_rejects_more_shares_than_available() {
+        let _node = FakeNode::start(dao_chain()).unwrap();
+        use_fixture_network_profile();
+
+        let res = BuySharesProviderDef {}.txs(invest_pars("501")).await;
+
+        assert!(matches!(
+            res,
+            Err(FrError::Validation(
+                ValidationError::ShareCountLargerThanAvailable
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn txs_rejects_more_shares_than_max_invest_amount() {
+        let _node = FakeNode::start(dao_chain()).unwrap();
+        use_fixture_network_profile();
+
+        // the fixture's max is 100
+        let res = BuySharesProviderDef {}.txs(invest_pars("101")).await;
+
+        assert!(matches!(
+            res,
+            Err(FrError::Validation(
+                ValidationError::BuyingMoreSharesThanMaxTotalAmount { .. }
+            ))
+        ));
+    }
+}
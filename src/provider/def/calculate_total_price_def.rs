@@ -1,22 +1,23 @@
+use crate::locale::locale;
 use std::convert::TryInto;
 use std::str::FromStr;
 
 use crate::calculate_profit_percentage;
-use crate::dependencies::funds_asset_specs;
+use crate::dependencies::algod;
 use crate::error::FrError;
 use crate::provider::calculate_total_price::{
     CalculateMaxFundsParJs, CalculateMaxFundsResJs, CalculateTotalPriceParJs,
     CalculateTotalPriceProvider, CalculateTotalPriceResJs,
 };
+use crate::service::funds_asset_specs::funds_asset_specs_for_input;
 use crate::service::number_formats::{
-    base_units_to_display_units_readable, validate_funds_amount_input,
+    base_units_to_display_units_readable, format_percentage, validate_funds_amount_input,
     validate_share_amount_positive, validate_share_amount_positive_or_0,
 };
 use anyhow::{anyhow, Error, Result};
 use async_trait::async_trait;
 use mbase::models::funds::FundsAmount;
 use mbase::models::share_amount::ShareAmount;
-use mbase::util::decimal_util::DecimalExt;
 use rust_decimal::Decimal;
 
 pub struct CalculateTotalPriceDef {}
@@ -29,7 +30,9 @@ impl CalculateTotalPriceProvider for CalculateTotalPriceDef {
         &self,
         pars: CalculateTotalPriceParJs,
     ) -> Result<CalculateTotalPriceResJs, FrError> {
-        let funds_asset_specs = funds_asset_specs()?;
+        let locale = locale();
+        let funds_asset_specs =
+            funds_asset_specs_for_input(&algod(), pars.funds_asset_id.as_deref()).await?;
 
         let validated_price = validate_funds_amount_input(&pars.share_price, &funds_asset_specs)?;
         let validated_share_amount = validate_share_amount_positive(&pars.shares_amount)?;
@@ -72,12 +75,12 @@ impl CalculateTotalPriceProvider for CalculateTotalPriceDef {
         )?;
 
         let total_price_display =
-            base_units_to_display_units_readable(total_price, &funds_asset_specs)?;
+            base_units_to_display_units_readable(total_price, &funds_asset_specs, &locale)?;
 
         Ok(CalculateTotalPriceResJs {
             total_price: total_price_display,
             total_price_number: total_price.val().to_string(),
-            profit_percentage: profit_percentage.format_percentage(),
+            profit_percentage: format_percentage(profit_percentage, &locale)?,
         })
     }
 
@@ -86,7 +89,9 @@ impl CalculateTotalPriceProvider for CalculateTotalPriceDef {
         &self,
         pars: CalculateMaxFundsParJs,
     ) -> Result<CalculateMaxFundsResJs, FrError> {
-        let funds_asset_specs = funds_asset_specs()?;
+        let locale = locale();
+        let funds_asset_specs =
+            funds_asset_specs_for_input(&algod(), pars.funds_asset_id.as_deref()).await?;
 
         let validated_price = validate_funds_amount_input(&pars.share_price, &funds_asset_specs)?;
         let validated_share_amount = validate_share_amount_positive(&pars.shares_amount)?;
@@ -101,7 +106,7 @@ impl CalculateTotalPriceProvider for CalculateTotalPriceDef {
         );
 
         let total_price_display =
-            base_units_to_display_units_readable(total_price, &funds_asset_specs)?;
+            base_units_to_display_units_readable(total_price, &funds_asset_specs, &locale)?;
 
         Ok(CalculateMaxFundsResJs {
             total_price: total_price_display,
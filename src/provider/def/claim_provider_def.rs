@@ -1,17 +1,18 @@
-use crate::dependencies::{capi_deps, funds_asset_specs};
+use crate::dependencies::{algod, capi_deps};
 use crate::error::FrError;
-use crate::js::common::signed_js_tx_to_signed_tx1;
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs};
 use crate::js::to_sign_js::ToSignJs;
 use crate::provider::claim_provider::{
     ClaimParJs, ClaimProvider, ClaimResJs, SubmitClaimParJs, SubmitClaimResJs,
 };
-use crate::service::drain_if_needed::{drain_if_needed_tx, prepare_pars_and_submit_drain};
+use crate::service::dao_cache::{invalidate_after_submit, load_dao};
+use crate::service::drain_if_needed::{
+    drain_if_needed_tx, drain_message, prepare_pars_and_submit_drain,
+};
 use anyhow::{Error, Result};
 use async_trait::async_trait;
 use base::diagnostics::log_claim_diagnostics;
 use base::flows::claim::claim::{claim, submit_claim, ClaimSigned};
-use base::flows::create_dao::storage::load_dao::load_dao;
-use mbase::dependencies::algod;
 use mbase::util::network_util::wait_for_pending_transaction;
 
 pub struct ClaimProviderDef {}
@@ -21,29 +22,32 @@ pub struct ClaimProviderDef {}
 impl ClaimProvider for ClaimProviderDef {
     async fn txs(&self, pars: ClaimParJs) -> Result<ClaimResJs, FrError> {
         let algod = algod();
-        let funds_asset_id = funds_asset_specs()?.id;
         let capi_deps = capi_deps()?;
 
         let dao_id = pars.dao_id.parse()?;
 
         let dao = load_dao(&algod, dao_id).await?;
+        let funds_asset_id = dao.funds_asset_id;
 
         let investor_address = &pars.investor_address.parse().map_err(Error::msg)?;
 
         let to_sign_for_claim = claim(&algod, investor_address, dao.app_id, funds_asset_id).await?;
 
-        let mut to_sign = vec![to_sign_for_claim.app_call_tx];
+        let mut to_sign = vec![(
+            to_sign_for_claim.app_call_tx,
+            format!("Claim dividend from {}", dao.name),
+        )];
 
         let maybe_to_sign_for_drain =
             drain_if_needed_tx(&algod, &dao, investor_address, funds_asset_id, &capi_deps).await?;
 
         // we append drain at the end since it's optional, so the indices of the non optional txs are fixed
         if let Some(to_sign_for_drain) = maybe_to_sign_for_drain {
-            to_sign.push(to_sign_for_drain.app_call_tx);
+            to_sign.push((to_sign_for_drain.app_call_tx, drain_message(&dao)));
         }
 
         Ok(ClaimResJs {
-            to_sign: ToSignJs::new(to_sign)?,
+            to_sign: ToSignJs::with_messages(to_sign).await?,
         })
     }
 
@@ -58,6 +62,8 @@ impl ClaimProvider for ClaimProviderDef {
             )));
         }
 
+        verify_signed_js_txs(&pars.txs)?;
+
         if pars.txs.len() == 2 {
             prepare_pars_and_submit_drain(&algod, &pars.txs[1]).await?;
         }
@@ -89,6 +95,76 @@ impl ClaimProvider for ClaimProviderDef {
         log::warn!("Submit claim tx id: {:?}", claim_tx_id);
         wait_for_pending_transaction(&algod, &claim_tx_id).await?;
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         Ok(SubmitClaimResJs {})
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_node::{
+        fixtures::{
+            app_address, dao_chain, set_investor_shares, to_sign_txs, use_fixture_network_profile,
+            DAO_APP_ID, FUNDS_ASSET_ID, INVESTOR,
+        },
+        FakeNode,
+    };
+    use algonaut::{
+        core::Address,
+        transaction::{transaction::TransactionType, Transaction},
+    };
+
+    fn claim_pars() -> ClaimParJs {
+        ClaimParJs {
+            dao_id: DAO_APP_ID.to_string(),
+            investor_address: INVESTOR.to_owned(),
+        }
+    }
+
+    /// Asserts that the tx is a call of the DAO app by the investor
+    fn assert_investor_app_call(tx: &Transaction) {
+        let investor: Address = INVESTOR.parse().unwrap();
+        match &tx.txn_type {
+            TransactionType::ApplicationCallTransaction(call) => {
+                assert_eq!(investor, call.sender);
+                assert_eq!(Some(DAO_APP_ID), call.app_id);
+            }
+            other => panic!("Expected app call, got: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn txs_returns_claim_without_drain_if_nothing_to_drain() {
+        let mut chain = dao_chain();
+        set_investor_shares(&mut chain, 10);
+        let _node = FakeNode::start(chain).unwrap();
+        use_fixture_network_profile();
+
+        let res = ClaimProviderDef {}.txs(claim_pars()).await.unwrap();
+
+        let txs = to_sign_txs(&res.to_sign);
+        assert_eq!(1, txs.len());
+        assert_investor_app_call(&txs[0]);
+    }
+
+    #[tokio::test]
+    async fn txs_appends_drain_if_the_app_has_undrained_funds() {
+        let mut chain = dao_chain();
+        set_investor_shares(&mut chain, 10);
+        // received, but not drained yet: the global state's received total is still 0
+        chain.set_asset_holding(&app_address(), FUNDS_ASSET_ID, 1_000_000);
+        let _node = FakeNode::start(chain).unwrap();
+        use_fixture_network_profile();
+
+        let res = ClaimProviderDef {}.txs(claim_pars()).await.unwrap();
+
+        // claim first, drain appended
+        let txs = to_sign_txs(&res.to_sign);
+        assert_eq!(2, txs.len());
+        assert_investor_app_call(&txs[0]);
+        assert_investor_app_call(&txs[1]);
+        assert_ne!(txs[0].id().unwrap(), txs[1].id().unwrap());
+    }
+}
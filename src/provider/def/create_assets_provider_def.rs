@@ -1,4 +1,4 @@
-use crate::dependencies::{capi_deps, funds_asset_specs};
+use crate::dependencies::{algod, capi_deps};
 use crate::error::FrError;
 use crate::js::to_sign_js::ToSignJs;
 use crate::provider::create_assets_provider::{
@@ -7,6 +7,8 @@ use crate::provider::create_assets_provider::{
 use crate::provider::create_dao_provider::validate_dao_inputs;
 use crate::provider::create_dao_provider::{CreateDaoFormInputsJs, CreateDaoPassthroughParJs};
 use crate::service::constants::{MAX_RAISABLE_AMOUNT, PRECISION};
+use crate::service::funds_asset_specs::funds_asset_specs_for_input;
+use crate::service::passthrough::Passthrough;
 use algonaut::core::Address;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -14,7 +16,6 @@ use base::dependencies::teal_api;
 use base::flows::create_dao::setup::create_shares::create_assets;
 use base::teal::TealApi;
 use mbase::api::contract::Contract;
-use mbase::dependencies::algod;
 use mbase::models::funds::FundsAmount;
 use mbase::models::setup_dao_specs::SetupDaoSpecs;
 
@@ -24,7 +25,8 @@ pub struct CreateAssetsProviderDef {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl CreateAssetsProvider for CreateAssetsProviderDef {
     async fn txs(&self, pars: CreateDaoAssetsParJs) -> Result<CreateDaoAssetsResJs, FrError> {
-        let funds_asset_specs = funds_asset_specs()?;
+        let funds_asset_specs =
+            funds_asset_specs_for_input(&algod(), pars.inputs.funds_asset_id.as_deref()).await?;
 
         // Note: partly redundant validation here (to_dao_specs validates everything again)
         let validated_inputs = validate_dao_inputs(&pars.inputs, &funds_asset_specs)?;
@@ -63,13 +65,24 @@ async fn create_dao_assets_txs(
     )
     .await?;
 
+    let txs = vec![
+        create_assets_txs.create_shares_tx,
+        create_assets_txs.create_app_tx,
+    ];
+
+    let messages = vec![
+        format!("Create the shares of {}", dao_specs.name),
+        format!("Create the app of {}", dao_specs.name),
+    ];
+
     Ok(CreateDaoAssetsResJs {
-        to_sign: ToSignJs::new(vec![
-            create_assets_txs.create_shares_tx,
-            create_assets_txs.create_app_tx,
-        ])?,
+        to_sign: ToSignJs::with_messages(txs.iter().cloned().zip(messages).collect()).await?,
         // we forward the inputs to the next step, just for a little convenience (javascript could pass them as separate fields again instead)
         // the next step will validate them again, as this performs type conversion too (+ general safety)
-        pt: CreateDaoPassthroughParJs { inputs },
+        pt: CreateDaoPassthroughParJs {
+            inputs,
+            mac: String::new(),
+        }
+        .signed(&txs)?,
     })
 }
@@ -1,13 +1,19 @@
-use crate::dependencies::funds_asset_specs;
+use crate::dependencies::algod;
 use crate::error::FrError;
-use crate::js::common::signed_js_tx_to_signed_tx1;
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs};
 use crate::js::to_sign_js::ToSignJs;
 use crate::model::dao_js::ToDaoJs;
 use crate::provider::create_dao_provider::{
-    validate_dao_inputs, validated_inputs_to_dao_specs, CreateDaoParJs, CreateDaoProvider,
-    CreateDaoRes, CreateDaoResJs, SubmitCreateDaoParJs, SubmitSetupDaoPassthroughParJs,
+    validate_dao_description_url_opt, validate_dao_inputs, validated_inputs_to_dao_specs,
+    CreateAssetsInputErrors, CreateDaoParJs, CreateDaoProvider, CreateDaoRes, CreateDaoResJs,
+    SubmitCreateDaoParJs, SubmitSetupDaoPassthroughParJs, ValidateDaoInputsError,
 };
 use crate::service::constants::PRECISION;
+use crate::service::funds_asset_specs::{funds_asset_specs_for, funds_asset_specs_for_input};
+use crate::service::passthrough::Passthrough;
+use crate::service::verified_content::{
+    fetched_content_hash_opt, url_with_fetched_content_hash_opt,
+};
 use algonaut::transaction::Transaction;
 use anyhow::{Error, Result};
 use async_trait::async_trait;
@@ -18,8 +24,8 @@ use base::flows::create_dao::setup_dao::Programs;
 use base::flows::create_dao::setup_dao::{setup_dao_txs, submit_setup_dao};
 use base::teal::TealApi;
 use mbase::api::contract::Contract;
-use mbase::dependencies::algod;
 use mbase::models::dao_app_id::DaoAppId;
+use mbase::models::funds::FundsAssetId;
 use mbase::models::timestamp::Timestamp;
 
 pub struct CreateDaoProviderDef {}
@@ -28,9 +34,13 @@ pub struct CreateDaoProviderDef {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl CreateDaoProvider for CreateDaoProviderDef {
     async fn txs(&self, pars: CreateDaoParJs) -> Result<CreateDaoResJs, FrError> {
+        pars.pt.verify(&pars.create_assets_signed_txs)?;
+        verify_signed_js_txs(&pars.create_assets_signed_txs)?;
+
         let algod = algod();
         let api = teal_api();
-        let funds_asset_specs = funds_asset_specs()?;
+        let funds_asset_specs =
+            funds_asset_specs_for_input(&algod, pars.pt.inputs.funds_asset_id.as_deref()).await?;
 
         // we assume order: js has as little logic as possible:
         // we send txs to be signed, as an array, and get the signed txs array back
@@ -39,8 +49,23 @@ impl CreateDaoProvider for CreateDaoProviderDef {
         let create_shares_signed_tx = &pars.create_assets_signed_txs[0];
         let create_app_signed_tx = &pars.create_assets_signed_txs[1];
 
-        let validated_inputs = validate_dao_inputs(&pars.pt.inputs, &funds_asset_specs)?;
-
+        let mut validated_inputs = validate_dao_inputs(&pars.pt.inputs, &funds_asset_specs)?;
+
+        // save the hashes of the content, to verify it when loading it:
+        // the description's with the url, the image's in the NFT's metadata hash
+        validated_inputs.description_url =
+            url_with_fetched_content_hash_opt(validated_inputs.description_url).await?;
+        validated_inputs.image_hash =
+            fetched_content_hash_opt(validated_inputs.image_url.as_deref()).await?;
+        // the hash makes the url longer, so check it again
+        validate_dao_description_url_opt(&validated_inputs.description_url).map_err(|e| {
+            ValidateDaoInputsError::AllFieldsValidation(CreateAssetsInputErrors {
+                description: Some(e),
+                ..CreateAssetsInputErrors::default()
+            })
+        })?;
+
+        // if the creator doesn't have enough algos, this returns FrError::NotEnoughAlgos (see node_error)
         let submit_assets_res = submit_create_assets(
             &algod,
             &CreateDaoAssetsSigned {
@@ -48,15 +73,7 @@ impl CreateDaoProvider for CreateDaoProviderDef {
                 create_app: signed_js_tx_to_signed_tx1(create_app_signed_tx)?,
             },
         )
-        .await;
-
-        if let Some(err) = submit_assets_res.as_ref().err() {
-            if err.to_string().contains("overspend") {
-                return Err(FrError::NotEnoughAlgos);
-            }
-        }
-
-        let submit_assets_res = submit_assets_res?;
+        .await?;
 
         let creator_address = pars.pt.inputs.creator.parse().map_err(Error::msg)?;
         let dao_specs = validated_inputs_to_dao_specs(&validated_inputs)?;
@@ -97,23 +114,36 @@ impl CreateDaoProvider for CreateDaoProviderDef {
         }
 
         Ok(CreateDaoResJs {
-            to_sign: ToSignJs::new(txs_to_sign)?,
+            to_sign: ToSignJs::with_messages(
+                txs_to_sign
+                    .iter()
+                    .cloned()
+                    .zip(txs_to_sign_messages(&dao_specs.name))
+                    .collect(),
+            )
+            .await?,
             pt: SubmitSetupDaoPassthroughParJs {
                 specs: dao_specs,
                 creator: creator_address.to_string(),
                 shares_asset_id: submit_assets_res.shares_asset_id,
                 app_id: submit_assets_res.app_id.0,
+                funds_asset_id: funds_asset_specs.id.0,
                 description_url: validated_inputs.description_url,
                 setup_date: to_sign.setup_date.0.to_string(),
-            },
+                mac: String::new(),
+            }
+            .signed(&txs_to_sign)?,
         })
     }
 
     async fn submit(&self, pars: SubmitCreateDaoParJs) -> Result<CreateDaoRes, FrError> {
         // log::debug!("in bridge_submit_create_dao, pars: {:?}", pars);
 
+        pars.pt.verify(&pars.txs)?;
+
         let algod = algod();
-        let funds_asset_specs = funds_asset_specs()?;
+        let funds_asset_specs =
+            funds_asset_specs_for(&algod, FundsAssetId(pars.pt.funds_asset_id)).await?;
 
         if pars.txs.len() != 3 {
             return Err(FrError::Internal(format!(
@@ -122,6 +152,8 @@ impl CreateDaoProvider for CreateDaoProviderDef {
             )));
         }
 
+        verify_signed_js_txs(&pars.txs)?;
+
         // TODO (low prio) improve this access, it's easy for the indices to get out of sync
         // and assign the txs to incorrect variables, which may cause subtle bugs
         // maybe refactor writing/reading into a helper struct or function
@@ -160,6 +192,15 @@ impl CreateDaoProvider for CreateDaoProviderDef {
     }
 }
 
+/// Same order as [txs_to_sign]
+fn txs_to_sign_messages(dao_name: &str) -> Vec<String> {
+    vec![
+        format!("Set up the app of {dao_name}"),
+        format!("Fund the app of {dao_name}"),
+        format!("Transfer the shares of {dao_name} to its app"),
+    ]
+}
+
 fn txs_to_sign(res: &SetupDaoToSign) -> Vec<Transaction> {
     vec![
         res.setup_app_tx.clone(),
@@ -0,0 +1,48 @@
+use crate::{
+    dependencies::algod,
+    error::FrError,
+    provider::dao_content_provider::{
+        DaoContentProvider, VerifyDaoContentParJs, VerifyDaoContentResJs,
+    },
+    service::{
+        dao_cache::load_dao,
+        verified_content::{verify_image_opt, verify_opt},
+    },
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::join;
+
+pub struct DaoContentProviderDef {}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl DaoContentProvider for DaoContentProviderDef {
+    async fn verify(&self, pars: VerifyDaoContentParJs) -> Result<VerifyDaoContentResJs, FrError> {
+        let algod = algod();
+
+        let dao = load_dao(&algod, pars.dao_id.parse()?).await?;
+
+        let (description, image, team, prospectus) = join!(
+            verify_opt(dao.descr_url.as_deref(), None),
+            verify_image_opt(
+                &algod,
+                dao.image_nft
+                    .as_ref()
+                    .map(|nft| (nft.asset_id, nft.url.as_str()))
+            ),
+            verify_opt(dao.team_url.as_deref(), None),
+            verify_opt(
+                dao.prospectus.as_ref().map(|p| p.url.as_str()),
+                dao.prospectus.as_ref().map(|p| p.hash.as_str())
+            ),
+        );
+
+        Ok(VerifyDaoContentResJs {
+            description,
+            image,
+            team,
+            prospectus,
+        })
+    }
+}
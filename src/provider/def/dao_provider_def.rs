@@ -1,13 +1,13 @@
+use crate::dependencies::algod;
+use crate::service::dao_cache::load_dao;
 use crate::{
-    dependencies::funds_asset_specs,
     error::FrError,
     model::dao_js::{DaoJs, ToDaoJs},
     provider::dao_provider::DaoProvider,
+    service::funds_asset_specs::funds_asset_specs_for,
 };
 use anyhow::Result;
 use async_trait::async_trait;
-use base::flows::create_dao::storage::load_dao::load_dao;
-use mbase::dependencies::algod;
 
 pub struct DaoUserViewProviderDef {}
 
@@ -21,6 +21,8 @@ impl DaoProvider for DaoUserViewProviderDef {
 
         let dao = load_dao(&algod, dao_id).await?;
 
-        Ok(dao.to_js(&funds_asset_specs()?)?)
+        let funds_asset_specs = funds_asset_specs_for(&algod, dao.funds_asset_id).await?;
+
+        Ok(dao.to_js(&funds_asset_specs)?)
     }
 }
@@ -1,13 +1,13 @@
+use crate::dependencies::algod;
 use crate::error::FrError;
 use crate::js::bridge::log_wrap_new;
-use crate::js::common::{signed_js_tx_to_signed_tx1, SignedTxFromJs};
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs, SignedTxFromJs};
 use crate::js::to_sign_js::ToSignJs;
 use crate::provider::create_dao_provider::validate_min_raised_target_end_date;
 use crate::provider::providers;
+use crate::service::dao_cache::{invalidate_after_submit, load_dao};
 use anyhow::{Error, Result};
 use base::dev_settings::{dev_settings, submit_dev_settings, DevSettings, DevSettingsSigned};
-use base::flows::create_dao::storage::load_dao::load_dao;
-use mbase::dependencies::algod;
 use mbase::util::network_util::wait_for_pending_transaction;
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
@@ -36,10 +36,13 @@ impl DevProviderDef {
         )
         .await?;
 
-        let to_sign_txs = vec![to_sign.app_call_tx];
+        let to_sign_txs = vec![(
+            to_sign.app_call_tx,
+            format!("Change the raise end date of {}", dao.name),
+        )];
 
         Ok(DevSettingsResJs {
-            to_sign: ToSignJs::new(to_sign_txs)?,
+            to_sign: ToSignJs::with_messages(to_sign_txs).await?,
         })
     }
 
@@ -55,6 +58,8 @@ impl DevProviderDef {
                 pars.txs.len()
             )));
         }
+
+        verify_signed_js_txs(&pars.txs)?;
         let tx = &pars.txs[0];
 
         let tx_id = submit_dev_settings(
@@ -69,6 +74,8 @@ impl DevProviderDef {
 
         let _ = wait_for_pending_transaction(&algod, &tx_id).await?;
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         Ok(SubmitDevSettingsResJs {})
     }
 }
@@ -1,17 +1,16 @@
 use super::investment_provider_def::{fetch_claimable_dividend, investor_local_state_view_data};
-use crate::dependencies::capi_deps;
+use crate::dependencies::{algod, capi_deps};
 use crate::error::FrError;
+use crate::locale::locale;
 use crate::provider::dividends_provider::{DividendsParJs, DividendsProvider};
 use crate::service::constants::PRECISION;
-use crate::{
-    dependencies::funds_asset_specs, service::number_formats::base_units_to_display_units_str,
+use crate::service::dao_cache::{dao_global_state, load_dao};
+use crate::service::{
+    funds_asset_specs::funds_asset_specs_for, number_formats::base_units_to_display_units_str,
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use base::flows::create_dao::storage::load_dao::load_dao;
-use mbase::dependencies::algod;
 use mbase::models::dao_id::DaoId;
-use mbase::state::dao_app_state::dao_global_state;
 
 pub struct DividendsProviderDef {}
 
@@ -19,8 +18,8 @@ pub struct DividendsProviderDef {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl DividendsProvider for DividendsProviderDef {
     async fn get(&self, pars: DividendsParJs) -> Result<String, FrError> {
+        let locale = locale();
         let algod = algod();
-        let funds_asset_specs = funds_asset_specs()?;
         let capi_deps = capi_deps()?;
 
         let investor_address = &pars.investor_address.parse().map_err(Error::msg)?;
@@ -28,6 +27,7 @@ impl DividendsProvider for DividendsProviderDef {
 
         let dao = load_dao(&algod, dao_id).await?;
         let central_state = dao_global_state(&algod, dao_id.0).await?;
+        let funds_asset_specs = funds_asset_specs_for(&algod, dao.funds_asset_id).await?;
 
         let investor_view_data =
             investor_local_state_view_data(&algod, investor_address, dao.app_id).await?;
@@ -47,6 +47,7 @@ impl DividendsProvider for DividendsProviderDef {
         Ok(base_units_to_display_units_str(
             claimable_dividend,
             &funds_asset_specs,
+            &locale,
         ))
     }
 }
@@ -1,19 +1,20 @@
-use crate::dependencies::{capi_deps, funds_asset_specs};
+use crate::dependencies::{algod, capi_deps};
 use crate::error::FrError;
-use crate::js::common::signed_js_tx_to_signed_tx1;
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs};
 use crate::js::to_sign_js::ToSignJs;
 use crate::provider::drain_provider::{
     DrainParJs, DrainProvider, DrainResJs, SubmitDrainParJs, SubmitDrainPassthroughParJs,
     SubmitDrainResJs,
 };
+use crate::service::dao_cache::{invalidate_after_submit, load_dao};
+use crate::service::drain_if_needed::drain_message;
 use crate::service::number_formats::microalgos_to_algos;
+use crate::service::passthrough::Passthrough;
 use anyhow::Error;
 use anyhow::Result;
 use async_trait::async_trait;
-use base::flows::create_dao::storage::load_dao::load_dao;
 use base::flows::drain::drain::fetch_drain_amount_and_drain;
 use base::flows::drain::drain::{submit_drain, DrainSigned};
-use mbase::dependencies::algod;
 
 pub struct DrainProviderDef {}
 
@@ -32,22 +33,29 @@ impl DrainProvider for DrainProviderDef {
             &algod,
             &pars.drainer_address.parse().map_err(Error::msg)?,
             dao.app_id,
-            funds_asset_specs()?.id,
+            dao.funds_asset_id,
             &capi_deps,
         )
         .await?;
 
+        let txs = vec![to_sign.app_call_tx];
+
         Ok(DrainResJs {
-            to_sign: ToSignJs::new(vec![to_sign.app_call_tx])?,
+            to_sign: ToSignJs::with_messages(vec![(txs[0].clone(), drain_message(&dao))]).await?,
             pt: SubmitDrainPassthroughParJs {
                 dao_id: dao_id.to_string(),
-            },
+                mac: String::new(),
+            }
+            .signed(&txs)?,
         })
     }
 
     async fn submit(&self, pars: SubmitDrainParJs) -> Result<SubmitDrainResJs, FrError> {
+        pars.pt.verify(&pars.txs)?;
+
         let algod = algod();
 
+        verify_signed_js_txs(&pars.txs)?;
         let app_call_tx = &pars.txs[0];
 
         let res = submit_drain(
@@ -60,6 +68,8 @@ impl DrainProvider for DrainProviderDef {
 
         log::debug!("Submit drain res: {:?}", res);
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         // TODO pass the dao from drain request, no need to fetch again here?
 
         let dao = load_dao(&algod, pars.pt.dao_id.parse()?).await?;
@@ -1,26 +1,29 @@
+use crate::dependencies::FundsAssetSpecs;
 use crate::{
-    dependencies::{capi_deps, funds_asset_specs},
+    dependencies::{algod, capi_deps, indexer},
     error::FrError,
     js::explorer_links::explorer_tx_id_link_env,
+    locale::locale,
     provider::funds_activity_provider::{
-        FundsActivityProvider, FundsActivityViewData, LoadFundsActivityParJs,
-        LoadFundsActivityResJs,
+        funds_activity_export_file, funds_activity_page_size, ExportFundsActivityParJs,
+        FundsActivityExportEntry, FundsActivityFilterJs, FundsActivityProvider,
+        FundsActivityTypeJs, FundsActivityViewData, LoadFundsActivityParJs, LoadFundsActivityResJs,
     },
+    service::dao_cache::load_dao,
+    service::export::ExportResJs,
+    service::funds_activity_page::{funds_activity_all, funds_activity_page, FundsActivityFilter},
+    service::funds_asset_specs::funds_asset_specs_for,
     service::number_formats::{
         base_units_to_display_units, base_units_to_display_units_str, format_decimal_readable,
-        format_short,
+        format_short, validate_funds_amount_input,
     },
+    service::price_source::fiat_converter,
 };
-use anyhow::Result;
+use anyhow::{Error, Result};
 use async_trait::async_trait;
-use base::{
-    flows::create_dao::storage::load_dao::load_dao,
-    queries::funds_activity::{funds_activity, FundsActivityEntryType},
-};
-use mbase::{
-    checked::CheckedSub,
-    dependencies::{algod, indexer},
-};
+use base::queries::funds_activity::FundsActivityEntryType;
+use chrono::{DateTime, Utc};
+use mbase::checked::CheckedSub;
 
 use super::shares_distribution_provider_def::shorten_address;
 
@@ -30,37 +33,44 @@ pub struct FundsActivityProviderDef {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl FundsActivityProvider for FundsActivityProviderDef {
     async fn get(&self, pars: LoadFundsActivityParJs) -> Result<LoadFundsActivityResJs, FrError> {
+        let locale = locale();
         let algod = algod();
         let indexer = indexer();
         let capi_deps = capi_deps()?;
 
         let dao_id = pars.dao_id.parse()?;
         let dao = load_dao(&algod, dao_id).await?;
+        let funds_asset_specs = funds_asset_specs_for(&algod, dao.funds_asset_id).await?;
 
-        let mut activity_entries =
-            funds_activity(&algod, &indexer, dao_id, &capi_deps, dao.funds_asset_id).await?;
-        // sort descendingly by date (most recent activity first)
-        activity_entries.sort_by(|p1, p2| p2.date.cmp(&p1.date));
+        let filter = to_filter(&pars.filter.unwrap_or_default(), &funds_asset_specs)?;
+        let page_size = funds_activity_page_size(pars.max_results.as_deref())?;
 
-        // TODO limit results already with the queries?
-        if let Some(max_results) = pars.max_results {
-            let max_results = max_results.parse()?;
-            activity_entries = activity_entries.into_iter().take(max_results).collect();
-        }
+        let page = funds_activity_page(&indexer, &dao, &capi_deps, &filter, page_size, pars.cursor)
+            .await?;
+
+        let fiat = fiat_converter(&funds_asset_specs).await;
+        let to_fiat = |funds| {
+            fiat.as_ref()
+                .and_then(|f| f.convert_or_log(funds, &funds_asset_specs, &locale))
+        };
 
         let mut view_data_entries = vec![];
-        for entry in activity_entries {
+        for entry in page.entries {
+            let amount_without_fee = entry.amount.sub(&entry.fee)?;
             let amount_display_units =
-                base_units_to_display_units(entry.amount, &funds_asset_specs()?);
+                base_units_to_display_units(entry.amount, &funds_asset_specs);
             let amount_without_fee_display_units =
-                base_units_to_display_units(entry.amount.sub(&entry.fee)?, &funds_asset_specs()?);
+                base_units_to_display_units(amount_without_fee, &funds_asset_specs);
 
             view_data_entries.push(FundsActivityViewData {
-                amount: format_decimal_readable(amount_display_units)?,
-                short_amount: format_short(amount_display_units)?,
-                fee: base_units_to_display_units_str(entry.fee, &funds_asset_specs()?),
-                amount_without_fee: format_decimal_readable(amount_without_fee_display_units)?,
-                short_amount_without_fee: format_short(amount_without_fee_display_units)?,
+                amount: format_decimal_readable(amount_display_units, &locale)?,
+                short_amount: format_short(amount_display_units, &locale)?,
+                fee: base_units_to_display_units_str(entry.fee, &funds_asset_specs, &locale),
+                amount_without_fee: format_decimal_readable(
+                    amount_without_fee_display_units,
+                    &locale,
+                )?,
+                short_amount_without_fee: format_short(amount_without_fee_display_units, &locale)?,
                 is_income: match entry.type_ {
                     FundsActivityEntryType::Income => "true",
                     FundsActivityEntryType::Spending => "false",
@@ -73,15 +83,229 @@ impl FundsActivityProvider for FundsActivityProviderDef {
                 }
                 .to_owned(),
                 description: entry.description,
-                date: entry.date.format("%a %b %e %Y").to_string(),
+                date: locale.format_date(&entry.date),
                 tx_id: entry.tx_id.to_string(),
                 tx_link: explorer_tx_id_link_env(&entry.tx_id),
                 address: shorten_address(&entry.address)?,
+                amount_fiat: to_fiat(entry.amount),
+                amount_without_fee_fiat: to_fiat(amount_without_fee),
             });
         }
 
         Ok(LoadFundsActivityResJs {
             entries: view_data_entries,
+            next: page.next,
         })
     }
+
+    async fn export(&self, pars: ExportFundsActivityParJs) -> Result<ExportResJs, FrError> {
+        let algod = algod();
+        let indexer = indexer();
+        let capi_deps = capi_deps()?;
+
+        let dao_id = pars.dao_id.parse()?;
+        let dao = load_dao(&algod, dao_id).await?;
+        let funds_asset_specs = funds_asset_specs_for(&algod, dao.funds_asset_id).await?;
+
+        // same entries as the paged view
+        let mut activity_entries = funds_activity_all(&indexer, &dao, &capi_deps).await?;
+        // chronological, as usual in accounting
+        activity_entries.sort_by(|p1, p2| p1.date.cmp(&p2.date));
+
+        let mut export_entries = vec![];
+        for entry in activity_entries {
+            let net_amount = entry.amount.sub(&entry.fee)?;
+            export_entries.push(FundsActivityExportEntry {
+                type_: match entry.type_ {
+                    FundsActivityEntryType::Income => "income",
+                    FundsActivityEntryType::Spending => "spending",
+                }
+                .to_owned(),
+                date: entry.date.to_rfc3339(),
+                amount_base_units: entry.amount.to_string(),
+                amount: base_units_to_display_units(entry.amount, &funds_asset_specs).to_string(),
+                fee_base_units: entry.fee.to_string(),
+                fee: base_units_to_display_units(entry.fee, &funds_asset_specs).to_string(),
+                net_amount_base_units: net_amount.to_string(),
+                net_amount: base_units_to_display_units(net_amount, &funds_asset_specs).to_string(),
+                funds_asset_id: funds_asset_specs.id.0.to_string(),
+                funds_asset_unit: funds_asset_specs.unit_name.clone(),
+                description: entry.description,
+                address: entry.address.to_string(),
+                tx_id: entry.tx_id.to_string(),
+                tx_link: explorer_tx_id_link_env(&entry.tx_id),
+            });
+        }
+
+        Ok(funds_activity_export_file(
+            pars.format,
+            &pars.dao_id,
+            &export_entries,
+        )?)
+    }
+}
+
+fn to_filter(
+    filter: &FundsActivityFilterJs,
+    funds_asset_specs: &FundsAssetSpecs,
+) -> Result<FundsActivityFilter, FrError> {
+    Ok(FundsActivityFilter {
+        type_: filter.type_.map(|t| match t {
+            FundsActivityTypeJs::Income => FundsActivityEntryType::Income,
+            FundsActivityTypeJs::Spending => FundsActivityEntryType::Spending,
+        }),
+        after: filter.after.as_deref().map(parse_date).transpose()?,
+        before: filter.before.as_deref().map(parse_date).transpose()?,
+        counterparty: match &filter.counterparty {
+            Some(address) => Some(address.parse().map_err(Error::msg)?),
+            None => None,
+        },
+        min_amount: match &filter.min_amount {
+            Some(amount) => Some(validate_funds_amount_input(amount, funds_asset_specs)?),
+            None => None,
+        },
+    })
+}
+
+fn parse_date(str: &str) -> Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(str)?.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_node::{
+        fixtures::{
+            app_address, dao_chain, use_fixture_network_profile, CAPI_ADDRESS, CREATOR, DAO_APP_ID,
+            FUNDS_ASSET_ID, INVESTOR,
+        },
+        FakeChain, FakeNode, FakeTx,
+    };
+    use data_encoding::BASE64;
+    use serde_json::json;
+
+    const INCOME_TX_ID: &str = "ADHRIH4IDVFVK4WU2XHHHW4WK726MCD7T4Y2MUT2T47CCLP2JZYA";
+    const DRAIN_TX_ID: &str = "KEBUKR4O7QPN6DESD6L7LMYU5NI3JIBUP7MF6NUEKEA5HPX7DIOQ";
+    const WITHDRAW_TX_ID: &str = "YJXSFW4D3RI45IPLP7SO7SXIYS2K2XSAYKLLTXTXZ7IWVZUMVKKA";
+
+    /// Income of 5, drain of the Capi fee, withdrawal of 2 (inner tx of the app call), in this order
+    fn chain_with_activity() -> FakeChain {
+        let app_address = app_address();
+        let mut chain = dao_chain();
+
+        chain.add_tx(FakeTx::asset_transfer(
+            INCOME_TX_ID,
+            INVESTOR,
+            &app_address,
+            FUNDS_ASSET_ID,
+            5_000_000,
+            1_650_000_000,
+        ));
+        chain.add_tx(
+            FakeTx::app_call(DRAIN_TX_ID, INVESTOR, DAO_APP_ID, &[], 1_650_000_050).with(
+                "inner-txns",
+                json!([FakeTx::asset_transfer(
+                    "",
+                    &app_address,
+                    CAPI_ADDRESS,
+                    FUNDS_ASSET_ID,
+                    50_000,
+                    1_650_000_050
+                )
+                .0]),
+            ),
+        );
+        chain.add_tx(
+            FakeTx::app_call(WITHDRAW_TX_ID, CREATOR, DAO_APP_ID, &[], 1_650_000_100)
+                .with("note", json!(BASE64.encode(b"Office rent")))
+                .with(
+                    "inner-txns",
+                    json!([FakeTx::asset_transfer(
+                        "",
+                        &app_address,
+                        CREATOR,
+                        FUNDS_ASSET_ID,
+                        2_000_000,
+                        1_650_000_100
+                    )
+                    .0]),
+                ),
+        );
+
+        chain
+    }
+
+    fn load_pars(filter: Option<FundsActivityFilterJs>) -> LoadFundsActivityParJs {
+        LoadFundsActivityParJs {
+            dao_id: DAO_APP_ID.to_string(),
+            max_results: None,
+            cursor: None,
+            filter,
+        }
+    }
+
+    #[tokio::test]
+    async fn get_returns_income_and_withdrawal_without_drain_most_recent_first() {
+        let _node = FakeNode::start(chain_with_activity()).unwrap();
+        use_fixture_network_profile();
+
+        let res = FundsActivityProviderDef {}
+            .get(load_pars(None))
+            .await
+            .unwrap();
+
+        assert_eq!(2, res.entries.len());
+        assert_eq!(None, res.next);
+
+        let withdrawal = &res.entries[0];
+        assert_eq!("false", withdrawal.is_income);
+        assert_eq!("Office rent", withdrawal.description);
+        assert_eq!(WITHDRAW_TX_ID, withdrawal.tx_id);
+
+        let income = &res.entries[1];
+        assert_eq!("true", income.is_income);
+        assert_eq!(INCOME_TX_ID, income.tx_id);
+    }
+
+    fn min_amount_filter(min_amount: &str) -> Option<FundsActivityFilterJs> {
+        Some(FundsActivityFilterJs {
+            min_amount: Some(min_amount.to_owned()),
+            ..FundsActivityFilterJs::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn get_min_amount_includes_withdrawals_in_inner_txs() {
+        let _node = FakeNode::start(chain_with_activity()).unwrap();
+        use_fixture_network_profile();
+
+        let res = FundsActivityProviderDef {}
+            .get(load_pars(min_amount_filter("2")))
+            .await
+            .unwrap();
+
+        let tx_ids: Vec<&str> = res.entries.iter().map(|e| e.tx_id.as_str()).collect();
+        assert_eq!(vec![WITHDRAW_TX_ID, INCOME_TX_ID], tx_ids);
+    }
+
+    #[tokio::test]
+    async fn get_min_amount_excludes_smaller_entries() {
+        let _node = FakeNode::start(chain_with_activity()).unwrap();
+        use_fixture_network_profile();
+
+        let res = FundsActivityProviderDef {}
+            .get(load_pars(min_amount_filter("3")))
+            .await
+            .unwrap();
+
+        let tx_ids: Vec<&str> = res.entries.iter().map(|e| e.tx_id.as_str()).collect();
+        assert_eq!(vec![INCOME_TX_ID], tx_ids);
+    }
+
+    #[test]
+    fn filter_type_is_deserialized_from_type() {
+        let filter: FundsActivityFilterJs =
+            serde_json::from_value(json!({ "type": "income" })).unwrap();
+        assert_eq!(Some(FundsActivityTypeJs::Income), filter.type_);
+    }
 }
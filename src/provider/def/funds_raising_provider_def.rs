@@ -1,17 +1,18 @@
-use crate::dependencies::funds_asset_specs;
+use crate::dependencies::algod;
 use crate::error::FrError;
+use crate::locale::locale;
 use crate::provider::funds_raising_provider::{
     FundsRaisingParsJs, FundsRaisingProvider, FundsRaisingResJs, FundsRaisingState,
     FundsRaisingStateJs,
 };
-use crate::service::number_formats::base_units_to_display_units_readable;
+use crate::service::dao_cache::dao_global_state;
+use crate::service::funds_asset_specs::funds_asset_specs_for;
+use crate::service::number_formats::{base_units_to_display_units_readable, format_percentage};
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::Utc;
-use mbase::dependencies::algod;
 use mbase::models::dao_id::DaoId;
-use mbase::state::dao_app_state::{dao_global_state, CentralAppGlobalState};
-use mbase::util::decimal_util::DecimalExt;
+use mbase::state::dao_app_state::CentralAppGlobalState;
 use rust_decimal::Decimal;
 
 pub struct FundsRaisingProviderDef {}
@@ -20,12 +21,13 @@ pub struct FundsRaisingProviderDef {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl FundsRaisingProvider for FundsRaisingProviderDef {
     async fn data(&self, pars: FundsRaisingParsJs) -> Result<FundsRaisingResJs, FrError> {
+        let locale = locale();
         let algod = algod();
-        let funds_asset_specs = funds_asset_specs()?;
 
         let dao_id: DaoId = pars.dao_id.parse()?;
 
         let dao_state = dao_global_state(&algod, dao_id.0).await?;
+        let funds_asset_specs = funds_asset_specs_for(&algod, dao_state.funds_asset_id).await?;
 
         let state: FundsRaisingState =
             if Utc::now() >= dao_state.min_funds_target_end_date.to_date()? {
@@ -55,9 +57,15 @@ impl FundsRaisingProvider for FundsRaisingProviderDef {
 
         Ok(FundsRaisingResJs {
             raised_number: dao_state.raised.to_string(),
-            raised: base_units_to_display_units_readable(dao_state.raised, &funds_asset_specs)?,
+            raised: base_units_to_display_units_readable(
+                dao_state.raised,
+                &funds_asset_specs,
+                &locale,
+            )?,
             state: state_js,
-            goal_exceeded_percentage: exceeded_percentage.map(|e| e.format_percentage()), // Some("40%".to_owned()),
+            goal_exceeded_percentage: exceeded_percentage
+                .map(|e| format_percentage(e, &locale))
+                .transpose()?, // Some("40%".to_owned()),
         })
     }
 }
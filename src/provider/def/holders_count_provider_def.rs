@@ -1,21 +1,22 @@
-use std::cmp::Ordering;
-
 use crate::{
+    dependencies::{algod, indexer},
     error::FrError,
-    model::QuantityChangeJs,
-    provider::holders_count_provider::{
-        HoldersChangeParJs, HoldersChangeResJs, HoldersCountParJs, HoldersCountProvider,
-        HoldersCountResJs,
+    locale::locale,
+    provider::{
+        holders_count_provider::{
+            format_difference, to_quantity_change, HoldersChangeParJs, HoldersChangeResJs,
+            HoldersCountParJs, HoldersCountPointJs, HoldersCountProvider, HoldersCountResJs,
+            HoldersHistoryParJs, HoldersHistoryResJs,
+        },
+        income_vs_spending_provider::{interval_data, parse_date},
     },
-    service::storage::{storage_get, storage_set},
+    service::{dao_cache::load_dao, holders_history::holders_history},
 };
-use algonaut::core::to_app_address;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use base::queries::shares_distribution::holders_count;
-use chrono::{DateTime, Duration, Utc};
-use mbase::{dependencies::indexer, models::dao_app_id::DaoAppId};
-use serde::{Deserialize, Serialize};
+use base::queries::shares_distribution::shares_holders_distribution;
+use chrono::{Duration, Utc};
+use mbase::models::{dao_app_id::DaoAppId, dao_id::DaoId};
 
 pub struct HoldersCountProviderDef {}
 
@@ -23,13 +24,31 @@ pub struct HoldersCountProviderDef {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl HoldersCountProvider for HoldersCountProviderDef {
     async fn get(&self, pars: HoldersCountParJs) -> Result<HoldersCountResJs, FrError> {
+        let algod = algod();
         let indexer = indexer();
 
-        let asset_id = pars.asset_id.parse()?;
+        let asset_id: u64 = pars.asset_id.parse()?;
         let app_id: DaoAppId = pars.app_id.parse()?;
-        let app_address = to_app_address(app_id.0);
+        let dao = load_dao(&algod, DaoId(app_id)).await?;
+        if dao.shares_asset_id != asset_id {
+            return Err(
+                anyhow!("Asset: {asset_id} isn't the shares asset of app: {app_id}").into(),
+            );
+        }
 
-        let holders_count = holders_count(&indexer, asset_id, &app_address).await?;
+        // from the current holdings (free + locked shares, like the change and history), without replaying the history
+        let app_address = dao.app_address();
+        let holders_count = shares_holders_distribution(
+            &algod,
+            &indexer,
+            dao.shares_asset_id,
+            dao.app_id,
+            dao.token_supply.val(),
+        )
+        .await?
+        .iter()
+        .filter(|h| h.address != app_address && h.amount.val() > 0)
+        .count();
 
         Ok(HoldersCountResJs {
             count: holders_count.to_string(),
@@ -37,125 +56,109 @@ impl HoldersCountProvider for HoldersCountProviderDef {
     }
 
     async fn change(&self, pars: HoldersChangeParJs) -> Result<HoldersChangeResJs, FrError> {
+        let algod = algod();
         let indexer = indexer();
 
-        let asset_id = pars.asset_id.parse()?;
+        let asset_id: u64 = pars.asset_id.parse()?;
         let app_id: DaoAppId = pars.app_id.parse()?;
-        let app_address = to_app_address(app_id.0);
-
-        let current_holders_count = holders_count(&indexer, asset_id, &app_address).await?;
+        let dao = load_dao(&algod, DaoId(app_id)).await?;
+        if dao.shares_asset_id != asset_id {
+            return Err(
+                anyhow!("Asset: {asset_id} isn't the shares asset of app: {app_id}").into(),
+            );
+        }
 
-        let storage_key = app_id.to_string();
+        let now = Utc::now();
+        let since = match &pars.since {
+            Some(since) => parse_date(since)?,
+            None => now - Duration::days(1),
+        };
+        if since > now {
+            return Err(anyhow!("Since: {since} is in the future").into());
+        }
 
-        let last_counts_opt: Option<LastHoldersCounts> = storage_get(&storage_key)?;
+        let history = holders_history(&indexer, &dao, now).await?;
+        let previous_count = history.count_before(since);
+        let count = history.count();
 
-        let now = Utc::now();
+        let change = to_quantity_change(previous_count, count);
+        log::debug!("Holders change: {change:?}");
 
-        let duration = Duration::days(1);
-
-        match last_counts_opt {
-            Some(last_counts) => {
-                // compare with the most recent count >= duration ago
-                let change = if let Some(count) =
-                    count_more_than_duration_ago(&last_counts, duration, now)
-                {
-                    let change_str = match current_holders_count.cmp(&count.count) {
-                        Ordering::Less => QuantityChangeJs::Down,
-                        Ordering::Equal => QuantityChangeJs::Eq,
-                        Ordering::Greater => QuantityChangeJs::Up,
-                    };
-
-                    HoldersChangeResJs {
-                        change: change_str.to_owned(),
-                    }
-                } else {
-                    // no count to compare with (far enough in the past) - so change can't be determined
-                    HoldersChangeResJs {
-                        change: QuantityChangeJs::Unknown,
-                    }
-                };
-
-                save_new_count_and_clamp(&last_counts, now, current_holders_count, app_id)?;
-
-                log::debug!("Holders change: {change:?}");
-                Ok(change)
-            }
-            None => {
-                // nothing saved yet - just save new count
-                storage_set(
-                    &app_id.to_string(),
-                    &LastHoldersCounts {
-                        counts: vec![LastHoldersCount {
-                            date: now,
-                            count: current_holders_count,
-                        }],
-                    },
-                )?;
-                Ok(HoldersChangeResJs {
-                    change: QuantityChangeJs::Unknown,
-                })
-            }
-        }
+        Ok(HoldersChangeResJs {
+            change,
+            count: count.to_string(),
+            previous_count: previous_count.to_string(),
+            difference: format_difference(previous_count, count),
+        })
     }
-}
 
-// saves new count for dao at the end and removes old elements if the vector has more than 2 elements
-// the reason we don't need more than 2 elements is that we don't save unless x duration passed since the last entry
-// so saved entries are spaced by a duration >= x
-// and since we use this to look for "most recent entry with x duration ago", it will be either the last one, or the one before.
-fn save_new_count_and_clamp(
-    last_counts: &LastHoldersCounts,
-    now: DateTime<Utc>,
-    current_holders_count: usize,
-    app_id: DaoAppId,
-) -> Result<()> {
-    if let Some(last_count) = last_counts.counts.last() {
-        // save a new entry if more than duration since the last entry transcurred
-        if now - last_count.date >= Duration::days(1) {
-            let mut last_counts = last_counts.clone();
-            last_counts.counts.push(LastHoldersCount {
-                date: now,
-                count: current_holders_count,
+    async fn history(&self, pars: HoldersHistoryParJs) -> Result<HoldersHistoryResJs, FrError> {
+        let algod = algod();
+        let indexer = indexer();
+        let locale = locale();
+
+        let dao = load_dao(&algod, pars.dao_id.parse()?).await?;
+
+        let interval_data = interval_data(
+            pars.interval.as_deref(),
+            pars.start.as_deref(),
+            pars.end.as_deref(),
+            pars.bucket,
+        )?;
+        let bucket_starts = interval_data.bucket_starts()?;
+        let range_end = match bucket_starts.last() {
+            Some(last) => interval_data.bucket.next(*last)?,
+            None => return Ok(HoldersHistoryResJs { points: vec![] }),
+        };
+
+        let history = holders_history(&indexer, &dao, range_end).await?;
+
+        let mut points = vec![];
+        for start in bucket_starts {
+            let count = history.count_before(interval_data.bucket.next(start)?);
+            points.push(HoldersCountPointJs {
+                date: locale.format_date(&start),
+                timestamp: start.timestamp_millis(),
+                count: count.to_string(),
+                count_number: count as u64,
             });
-
-            // remove leading entry if there are more than 2 elements
-            if last_counts.counts.len() > 2 {
-                last_counts.counts.remove(0);
-            }
-
-            // save updated counts
-            storage_set(&app_id.to_string(), &last_counts)?;
         }
-    } else {
-        // we expect either no counts stored or counts with elements
-        // holders count change icon is unimportant - ignore errors
-        log::error!("Invalid state (ignored): counts in storage is empty");
-    };
-
-    Ok(())
-}
 
-// searches from last to first for a count with saved date >= duration
-// assumes that the counts are sorted ascendingly by date
-fn count_more_than_duration_ago(
-    counts: &LastHoldersCounts,
-    duration: Duration,
-    now: DateTime<Utc>,
-) -> Option<LastHoldersCount> {
-    let mut counts = counts.counts.clone();
-    counts.reverse();
-    counts
-        .into_iter()
-        .find(|count| now - count.date >= duration)
+        Ok(HoldersHistoryResJs { points })
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct LastHoldersCount {
-    date: DateTime<Utc>,
-    count: usize,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_node::{
+        fixtures::{
+            dao_chain, set_investor_shares, use_fixture_network_profile, CAPI_ADDRESS, CREATOR,
+            DAO_APP_ID, SHARES_ASSET_ID,
+        },
+        FakeNode,
+    };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct LastHoldersCounts {
-    counts: Vec<LastHoldersCount>,
+    #[tokio::test]
+    async fn get_counts_the_accounts_with_free_or_locked_shares() {
+        let mut chain = dao_chain();
+        // locked
+        set_investor_shares(&mut chain, 10);
+        // free
+        chain.set_asset_holding(CREATOR, SHARES_ASSET_ID, 5);
+        // opted in, without shares
+        chain.set_asset_holding(CAPI_ADDRESS, SHARES_ASSET_ID, 0);
+        let _node = FakeNode::start(chain).unwrap();
+        use_fixture_network_profile();
+
+        let res = HoldersCountProviderDef {}
+            .get(HoldersCountParJs {
+                asset_id: SHARES_ASSET_ID.to_string(),
+                app_id: DAO_APP_ID.to_string(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!("2", res.count);
+    }
 }
@@ -1,21 +1,20 @@
-use crate::dependencies::{capi_deps, funds_asset_specs, FundsAssetSpecs};
+use crate::dependencies::{algod, capi_deps, indexer, FundsAssetSpecs};
 use crate::error::FrError;
+use crate::locale::{locale, UserLocale};
 use crate::provider::income_vs_spending_provider::{
-    to_interval_data, ChartDataPointJs, IncomeVsSpendingParJs, IncomeVsSpendingProvider,
-    IncomeVsSpendingResJs, IntervalData,
+    to_interval_data, ChartBucketJs, ChartDataPointJs, ChartSeriesOptions, IncomeVsSpendingParJs,
+    IncomeVsSpendingProvider, IncomeVsSpendingResJs, IntervalData,
 };
-use crate::service::number_formats::base_units_to_display_units;
+use crate::service::dao_cache::load_dao;
+use crate::service::funds_asset_specs::funds_asset_specs_for;
+use crate::service::price_source::{fiat_converter, FiatConverter};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use base::{
-    flows::{create_dao::storage::load_dao::load_dao, withdraw::withdrawals::withdrawals},
-    queries::received_payments::received_payments,
+    flows::withdraw::withdrawals::withdrawals, queries::received_payments::received_payments,
 };
-use chrono::{DateTime, Duration, Timelike, Utc};
-use mbase::dependencies::{algod, indexer};
-use mbase::models::funds::FundsAmount;
-use std::convert::TryInto;
-use std::ops::Div;
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
 
 pub struct IncomeVsSpendingProviderDef {}
 
@@ -25,21 +24,24 @@ impl IncomeVsSpendingProvider for IncomeVsSpendingProviderDef {
     async fn get(&self, pars: IncomeVsSpendingParJs) -> Result<IncomeVsSpendingResJs, FrError> {
         let algod = algod();
         let indexer = indexer();
-        let funds_asset_specs = funds_asset_specs()?;
         let capi_deps = capi_deps()?;
 
         let dao_id = pars.dao_id.parse()?;
 
         let dao = load_dao(&algod, dao_id).await?;
+        let funds_asset_specs = funds_asset_specs_for(&algod, dao.funds_asset_id).await?;
 
-        let interval_data = to_interval_data(&pars.interval)?;
+        let interval_data = to_interval_data(&pars)?;
+        let series_options = ChartSeriesOptions::from(&pars);
+        // the first bucket can start before the range start
+        let since = interval_data.bucket.start_of(interval_data.start)?;
 
         let mut income = received_payments(
             &indexer,
             &dao.app_address(),
             dao.funds_asset_id,
             &None,
-            &Some(interval_data.start),
+            &Some(since),
             &capi_deps,
         )
         .await?;
@@ -49,9 +51,9 @@ impl IncomeVsSpendingProvider for IncomeVsSpendingProviderDef {
             &algod,
             &indexer,
             dao_id,
-            funds_asset_specs.id,
+            dao.funds_asset_id,
             &None,
-            &Some(interval_data.start),
+            &Some(since),
         )
         .await?;
         spending.sort_by(|p1, p2| p1.date.cmp(&p2.date));
@@ -74,11 +76,15 @@ impl IncomeVsSpendingProvider for IncomeVsSpendingProviderDef {
             })
             .collect();
 
+        let fiat = fiat_converter(&funds_asset_specs).await;
+
         to_income_vs_spending_res_static_bounds(
             income_data_points,
             spending_data_points,
             &funds_asset_specs,
             interval_data,
+            &series_options,
+            fiat.as_ref(),
         )
     }
 }
@@ -90,14 +96,16 @@ pub fn to_income_vs_spending_res_static_bounds(
     spending: Vec<ChartDataPoint>,
     funds_asset_specs: &FundsAssetSpecs,
     interval_data: IntervalData,
+    series_options: &ChartSeriesOptions,
+    fiat: Option<&FiatConverter>,
 ) -> Result<IncomeVsSpendingResJs, FrError> {
     to_income_vs_spending_res(
         income,
         spending,
         funds_asset_specs,
-        interval_data.start,
-        interval_data.end,
-        interval_data.interval,
+        &interval_data,
+        series_options,
+        fiat,
     )
 }
 
@@ -108,7 +116,9 @@ pub fn to_income_vs_spending_res_dynamic_bounds(
     income: Vec<ChartDataPoint>,
     spending: Vec<ChartDataPoint>,
     funds_asset_specs: &FundsAssetSpecs,
-    grouping_interval: Duration,
+    bucket: ChartBucketJs,
+    series_options: &ChartSeriesOptions,
+    fiat: Option<&FiatConverter>,
 ) -> Result<IncomeVsSpendingResJs, FrError> {
     let income_bounds = determine_min_max_local_bounds(&income);
     let spending_bounds = determine_min_max_local_bounds(&spending);
@@ -119,26 +129,24 @@ pub fn to_income_vs_spending_res_dynamic_bounds(
         .collect();
 
     match determine_min_max_bounds(&bounds_without_none) {
-        Some(bounds) => {
-            let bounds = DateBounds {
-                // days (for axis) start at 00:00 if using days interval, hours at 00 (if using hours/minutes..)
-                // TODO calculate dynamically
-                min: start_of_day(bounds.min)?,
-                // min: start_of_hour(bounds.min)?,
-                max: bounds.max,
-            };
-
-            to_income_vs_spending_res(
-                income,
-                spending,
-                funds_asset_specs,
-                bounds.min,
-                bounds.max,
-                grouping_interval,
-            )
-        }
+        // the buckets start at the bucket boundary before min (e.g. 00:00 for days)
+        Some(bounds) => to_income_vs_spending_res(
+            income,
+            spending,
+            funds_asset_specs,
+            &IntervalData {
+                start: bounds.min,
+                end: bounds.max,
+                bucket,
+            },
+            series_options,
+            fiat,
+        ),
         // No min max dates -> nothing to display on the chart
-        None => Ok(IncomeVsSpendingResJs { points: vec![] }),
+        None => Ok(IncomeVsSpendingResJs {
+            points: vec![],
+            fiat_currency: fiat.map(|f| f.currency().to_owned()),
+        }),
     }
 }
 
@@ -146,23 +154,24 @@ fn to_income_vs_spending_res(
     income: Vec<ChartDataPoint>,
     spending: Vec<ChartDataPoint>,
     funds_asset_specs: &FundsAssetSpecs,
-    start_time: DateTime<Utc>,
-    end_time: DateTime<Utc>,
-    interval: Duration,
+    interval_data: &IntervalData,
+    series_options: &ChartSeriesOptions,
+    fiat: Option<&FiatConverter>,
 ) -> Result<IncomeVsSpendingResJs, FrError> {
     let mut all_points = income;
     all_points.extend(spending);
 
     let all_grouped_points_js = group_and_format_data_points(
         &all_points,
-        start_time,
-        end_time,
-        interval,
+        interval_data,
+        series_options,
         funds_asset_specs,
+        fiat,
     )?;
 
     Ok(IncomeVsSpendingResJs {
         points: all_grouped_points_js,
+        fiat_currency: fiat.map(|f| f.currency().to_owned()),
     })
 }
 
@@ -196,28 +205,6 @@ fn determine_min_max_bounds(local_bounds: &[DateBounds]) -> Option<DateBounds> {
     }
 }
 
-#[allow(dead_code)]
-fn start_of_day(date: DateTime<Utc>) -> Result<DateTime<Utc>> {
-    date.with_hour(0)
-        .ok_or_else(|| anyhow!("Unexpected: couldn't set day 0 on date"))?
-        .with_minute(0)
-        .ok_or_else(|| anyhow!("Unexpected: couldn't set min 0 on date"))?
-        .with_second(0)
-        .ok_or_else(|| anyhow!("Unexpected: couldn't set second 0 on date"))?
-        .with_nanosecond(0)
-        .ok_or_else(|| anyhow!("Unexpected: couldn't set nanosecond 0 on date"))
-}
-
-#[allow(dead_code)]
-fn start_of_hour(date: DateTime<Utc>) -> Result<DateTime<Utc>> {
-    date.with_minute(0)
-        .ok_or_else(|| anyhow!("Unexpected: couldn't set min 0 on date"))?
-        .with_second(0)
-        .ok_or_else(|| anyhow!("Unexpected: couldn't set second 0 on date"))?
-        .with_nanosecond(0)
-        .ok_or_else(|| anyhow!("Unexpected: couldn't set nanosecond 0 on date"))
-}
-
 #[derive(Debug, Clone)]
 struct DateBounds {
     min: DateTime<Utc>,
@@ -232,118 +219,142 @@ struct IncomeAndSpending {
 
 pub fn group_and_format_data_points(
     points: &[ChartDataPoint],
-    start_time: DateTime<Utc>,
-    end_time: DateTime<Utc>,
-    interval_length: Duration,
+    interval_data: &IntervalData,
+    series_options: &ChartSeriesOptions,
     funds_asset_specs: &FundsAssetSpecs,
+    fiat: Option<&FiatConverter>,
 ) -> Result<Vec<ChartDataPointJs>> {
-    // we expect ticks_between to normally return a positive value here, as start_time should be <= end_time
-    let ticks_count: usize = ticks_between(end_time, start_time, interval_length)?.try_into()?;
-
-    // ticks (indices) on x axis with their respective y value - 0 by default, so the chart shows 0 for dates that have no data
-    let mut ticks: Vec<IncomeAndSpending> = vec![
+    let bucket_starts = interval_data.bucket_starts()?;
+    let (first_start, last_start) = match (bucket_starts.first(), bucket_starts.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Ok(vec![]),
+    };
+    let range_end = interval_data.bucket.next(last_start)?;
+    let locale = locale();
+
+    // buckets with their respective y value - 0 by default, so the chart shows 0 for dates that have no data
+    let mut buckets: Vec<IncomeAndSpending> = vec![
         IncomeAndSpending {
             income: 0,
             spending: 0
         };
-        ticks_count + 1
-    ]; // +1 -> inclusive range
+        bucket_starts.len()
+    ];
 
-    // aggregate data points on their respective ticks
+    // aggregate data points on their respective buckets
     for point in points {
-        // calculate tick (grouped date) on x-axis
-        let tick_index_maybe_negative = ticks_between(point.date, start_time, interval_length)?;
-        // the data point can precede start_time, which gives us a negative tick index
-        // data points preceding start_time (i.e. outside of our current x range) are ignored
-        let tick_index: usize = if tick_index_maybe_negative.is_positive() {
-            tick_index_maybe_negative.try_into()?
-        } else {
+        // data points outside of our current x range are ignored
+        if point.date < first_start || point.date >= range_end {
             continue;
-        };
+        }
+        // the last bucket starting before or at the point's date
+        let bucket_index = bucket_starts.partition_point(|start| *start <= point.date) - 1;
 
-        // add value to sum tick (keeping income and spending separate)
-        let tick_value = &ticks[tick_index];
+        // add value to sum bucket (keeping income and spending separate)
+        let bucket = &mut buckets[bucket_index];
         let curr_value = if point.is_income {
-            tick_value.income
+            &mut bucket.income
         } else {
-            tick_value.spending
+            &mut bucket.spending
         };
-
-        let new_value = curr_value
+        *curr_value = curr_value
             .checked_add(point.value)
             .ok_or_else(|| anyhow!("Overflow adding value and point.value: {:?}", point.value))?;
-
-        if point.is_income {
-            ticks[tick_index].income = new_value;
-        } else {
-            ticks[tick_index].spending = new_value;
-        };
     }
 
     let mut data_points = vec![];
-
-    // map the ticks indices and values to chart points (dates and values)
-    for (index, value) in ticks.into_iter().enumerate() {
+    let mut cumulative = IncomeAndSpending {
+        income: 0,
+        spending: 0,
+    };
+
+    // map the buckets to chart points (dates and values)
+    for (start, value) in bucket_starts.into_iter().zip(buckets) {
+        cumulative = IncomeAndSpending {
+            income: cumulative
+                .income
+                .checked_add(value.income)
+                .ok_or_else(|| anyhow!("Overflow adding cumulative income"))?,
+            spending: cumulative
+                .spending
+                .checked_add(value.spending)
+                .ok_or_else(|| anyhow!("Overflow adding cumulative spending"))?,
+        };
         let data_point_js = create_data_point_js(
-            start_time,
-            index.try_into()?,
-            interval_length,
+            start,
             &value,
+            &cumulative,
+            series_options,
             funds_asset_specs,
+            fiat,
+            &locale,
         )?;
-        log::debug!("mapped tick index: {index}, value: {value:?} to js point: {data_point_js:?}");
+        log::debug!("mapped bucket: {start}, value: {value:?} to js point: {data_point_js:?}");
         data_points.push(data_point_js);
     }
 
     Ok(data_points)
 }
 
-/// returns tick count between start and date, given an interval length
-fn ticks_between(
-    date: DateTime<Utc>,
-    start: DateTime<Utc>,
-    interval_length: Duration,
-) -> Result<i64> {
-    Ok((date - start)
-        .num_seconds()
-        .div(interval_length.num_seconds()))
-}
-
 fn create_data_point_js(
-    start_time: DateTime<Utc>,
-    interval_index: i64,
-    interval: Duration,
+    date: DateTime<Utc>,
     value: &IncomeAndSpending,
+    cumulative: &IncomeAndSpending,
+    series_options: &ChartSeriesOptions,
     funds_asset_specs: &FundsAssetSpecs,
+    fiat: Option<&FiatConverter>,
+    locale: &UserLocale,
 ) -> Result<ChartDataPointJs> {
-    let date = to_data_point_date(start_time, interval_index, interval)?;
-    let income = base_units_to_display_units(FundsAmount::new(value.income), funds_asset_specs);
-    let spending = base_units_to_display_units(FundsAmount::new(value.spending), funds_asset_specs);
+    let to_display_units = |amount: i128| {
+        Decimal::from_i128_with_scale(amount, funds_asset_specs.decimals).normalize()
+    };
+
+    let income = to_display_units(value.income.into());
+    let spending = to_display_units(value.spending.into());
+    let cumulative_income = to_display_units(cumulative.income.into());
+    let cumulative_spending = to_display_units(cumulative.spending.into());
+    let net_balance =
+        to_display_units(i128::from(cumulative.income) - i128::from(cumulative.spending));
+
+    let optional = |include: bool, value: Decimal| -> Result<(Option<String>, Option<f64>)> {
+        Ok(if include {
+            (Some(value.to_string()), Some(to_number(value)?))
+        } else {
+            (None, None)
+        })
+    };
+    let (cumulative_income, cumulative_income_number) =
+        optional(series_options.cumulative, cumulative_income)?;
+    let (cumulative_spending, cumulative_spending_number) =
+        optional(series_options.cumulative, cumulative_spending)?;
+    let (net_balance, net_balance_number) = optional(series_options.net_balance, net_balance)?;
+
+    let to_fiat_number = |value: Decimal| -> Result<Option<f64>> {
+        fiat.map(|f| to_number(f.to_fiat(value)?)).transpose()
+    };
 
     Ok(ChartDataPointJs {
-        // date: date.timestamp().to_string(),
-        date: date.to_rfc2822(),
+        date: locale.format_date(&date),
+        timestamp: date.timestamp_millis(),
         income: income.to_string(),
+        income_number: to_number(income)?,
         spending: spending.to_string(),
+        spending_number: to_number(spending)?,
+        cumulative_income,
+        cumulative_income_number,
+        cumulative_spending,
+        cumulative_spending_number,
+        net_balance,
+        net_balance_number,
+        income_fiat: to_fiat_number(income)?,
+        spending_fiat: to_fiat_number(spending)?,
     })
 }
 
-fn to_data_point_date(
-    start_time: DateTime<Utc>,
-    interval_index: i64,
-    interval: Duration,
-) -> Result<DateTime<Utc>> {
-    let date = start_time
-        .checked_add_signed(Duration::seconds(
-            interval
-                .num_seconds()
-                .checked_mul(interval_index)
-                .ok_or(anyhow!(
-                    "Error multiplying interval: {interval} with index: {interval_index}"
-                ))?,
-        ))
-        .ok_or(anyhow!("Error adding duration to start time: {start_time}"))?;
-    Ok(date)
+fn to_number(value: Decimal) -> Result<f64> {
+    value
+        .to_f64()
+        .ok_or_else(|| anyhow!("Couldn't convert: {value} to f64"))
 }
 
 // TODO rename, specific to income vs. spending
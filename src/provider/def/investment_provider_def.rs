@@ -1,33 +1,35 @@
-use crate::dependencies::FundsAssetSpecs;
+use crate::dependencies::{algod, FundsAssetSpecs};
 use crate::error::FrError;
+use crate::locale::locale;
 use crate::provider::investment_provider::{
     AvailableSharesParJs, AvailableSharesResJs, InvestmentProvider, LoadInvestorParJs,
     LoadInvestorResJs,
 };
-use crate::service::number_formats::{base_units_to_display_units_readable, format_short};
-use crate::{
-    dependencies::{capi_deps, funds_asset_specs},
-    service::constants::PRECISION,
+use crate::service::dao_cache::{dao_global_state, dao_investor_state, load_dao};
+use crate::service::funds_asset_specs::funds_asset_specs_for;
+use crate::service::number_formats::{
+    base_units_to_display_units_readable, format_percentage, format_short,
 };
+use crate::service::price_source::fiat_converter;
+use crate::{dependencies::capi_deps, service::constants::PRECISION};
 use algonaut::algod::v2::Algod;
 use algonaut::core::Address;
 use anyhow::{Error, Result};
 use async_trait::async_trait;
+use base::flows::claim::claim::claimable_dividend;
 use base::flows::create_dao::model::Dao;
 use base::flows::drain::drain::to_drain_amounts;
-use base::flows::{claim::claim::claimable_dividend, create_dao::storage::load_dao::load_dao};
 use base::state::account_state::asset_holdings;
 use base::state::dao_shares::dao_shares_with_dao_state;
+use futures::{try_join, TryFutureExt};
 use mbase::checked::{CheckedAdd, CheckedSub};
-use mbase::dependencies::algod;
 use mbase::models::capi_deps::CapiAssetDaoDeps;
 use mbase::models::dao_app_id::DaoAppId;
 use mbase::models::dao_id::DaoId;
 use mbase::models::funds::FundsAmount;
 use mbase::models::share_amount::ShareAmount;
 use mbase::state::app_state::ApplicationLocalStateError;
-use mbase::state::dao_app_state::{dao_global_state, dao_investor_state, CentralAppGlobalState};
-use mbase::util::decimal_util::DecimalExt;
+use mbase::state::dao_app_state::CentralAppGlobalState;
 
 pub struct InvestmentProviderDef {}
 
@@ -38,6 +40,7 @@ impl InvestmentProvider for InvestmentProviderDef {
         &self,
         pars: AvailableSharesParJs,
     ) -> Result<AvailableSharesResJs, FrError> {
+        let locale = locale();
         let algod = algod();
 
         let dao_id: DaoId = pars.dao_id.parse()?;
@@ -49,27 +52,30 @@ impl InvestmentProvider for InvestmentProviderDef {
 
         Ok(AvailableSharesResJs {
             available_shares_number: dao_shares.available.val().to_string(),
-            available_shares: format_short(dao_shares.available.as_decimal())?,
+            available_shares: format_short(dao_shares.available.as_decimal(), &locale)?,
         })
     }
 
-    // TODO parallelize requests if possible
     async fn get_investor_data(
         &self,
         pars: LoadInvestorParJs,
     ) -> Result<LoadInvestorResJs, FrError> {
+        let locale = locale();
         let algod = algod();
-        let funds_asset_specs = funds_asset_specs()?;
         let capi_deps = capi_deps()?;
 
         let dao_id = pars.dao_id.parse()?;
 
-        let dao = load_dao(&algod, dao_id).await?;
-
         let investor_address = &pars.investor_address.parse().map_err(Error::msg)?;
 
-        let investor_view_data =
-            investor_local_state_view_data(&algod, investor_address, dao.app_id).await?;
+        let dao = load_dao(&algod, dao_id).await?;
+
+        let (funds_asset_specs, investor_view_data, central_state, investor_holdings) = try_join!(
+            funds_asset_specs_for(&algod, dao.funds_asset_id),
+            investor_local_state_view_data(&algod, investor_address, dao.app_id),
+            dao_global_state(&algod, dao.app_id),
+            asset_holdings(&algod, investor_address, dao.shares_asset_id).map_err(Error::from),
+        )?;
 
         // The % of investor's locked shares relative to the total supply
         let investor_locked_percentage_of_supply =
@@ -78,8 +84,6 @@ impl InvestmentProvider for InvestmentProviderDef {
         let investor_dividend_percentage =
             investor_locked_percentage_of_supply * dao.investors_share.value();
 
-        let central_state = dao_global_state(&algod, dao.app_id).await?;
-
         let claimable_dividend = fetch_claimable_dividend(
             &algod,
             investor_view_data.claimed,
@@ -92,24 +96,31 @@ impl InvestmentProvider for InvestmentProviderDef {
         )
         .await?;
 
-        let investor_holdings =
-            asset_holdings(&algod, investor_address, dao.shares_asset_id).await?;
+        let fiat = fiat_converter(&funds_asset_specs).await;
+        let to_fiat = |funds| {
+            fiat.as_ref()
+                .and_then(|f| f.convert_or_log(funds, &funds_asset_specs, &locale))
+        };
 
         Ok(LoadInvestorResJs {
             investor_shares_count: investor_view_data.locked_shares.to_string(),
-            investor_share: investor_dividend_percentage.format_percentage(),
+            investor_share: format_percentage(investor_dividend_percentage, &locale)?,
 
             investor_already_retrieved_amount: base_units_to_display_units_readable(
                 investor_view_data.retrieved,
                 &funds_asset_specs,
+                &locale,
             )?,
             investor_claimable_dividend: base_units_to_display_units_readable(
                 claimable_dividend,
                 &funds_asset_specs,
+                &locale,
             )?,
             investor_claimable_dividend_microalgos: claimable_dividend.to_string(),
             investor_locked_shares: investor_view_data.locked_shares.to_string(),
             investor_unlocked_shares: investor_holdings.to_string(),
+            investor_already_retrieved_amount_fiat: to_fiat(investor_view_data.retrieved),
+            investor_claimable_dividend_fiat: to_fiat(claimable_dividend),
         })
     }
 }
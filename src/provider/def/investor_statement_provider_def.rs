@@ -0,0 +1,502 @@
+use crate::{
+    dependencies::{algod, indexer},
+    error::FrError,
+    js::explorer_links::explorer_tx_id_link_env,
+    provider::investor_statement_provider::{
+        investor_statement_csv, DaoStatementJs, InvestorStatementParJs, InvestorStatementProvider,
+        InvestorStatementResJs, StatementEntryJs, StatementEntryTypeJs,
+    },
+    service::{
+        dao_cache::load_dao,
+        funds_asset_specs::funds_asset_specs_for,
+        holders_history::group_purchases,
+        indexer_history::{account_txs_in_range, group_txs, tx_date},
+        number_formats::base_units_to_display_units,
+    },
+};
+use algonaut::{core::Address, model::indexer::v2::Transaction};
+use anyhow::{anyhow, Error, Result};
+use async_trait::async_trait;
+use base::flows::create_dao::model::Dao;
+use chrono::{DateTime, TimeZone, Utc};
+use mbase::models::{dao_app_id::DaoAppId, dao_id::DaoId, funds::FundsAmount, tx_id::TxId};
+use rust_decimal::Decimal;
+
+pub struct InvestorStatementProviderDef {}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl InvestorStatementProvider for InvestorStatementProviderDef {
+    async fn get(&self, pars: InvestorStatementParJs) -> Result<InvestorStatementResJs, FrError> {
+        let algod = algod();
+        let indexer = indexer();
+
+        let investor: Address = pars.investor_address.parse().map_err(Error::msg)?;
+        let year: i32 = pars.year.parse()?;
+        let (start, end) = (year_start(year)?, year_start(year + 1)?);
+
+        let txs = account_txs_in_range(&indexer, &investor, start, end).await?;
+        let groups = group_txs(txs);
+
+        let daos = match &pars.dao_id {
+            Some(dao_id) => vec![load_dao(&algod, dao_id.parse()?).await?],
+            None => {
+                let mut daos = vec![];
+                for app_id in called_app_ids(&groups, &investor) {
+                    // apps that aren't DAOs (or couldn't be loaded) aren't part of the statement
+                    match load_dao(&algod, DaoId(DaoAppId(app_id))).await {
+                        Ok(dao) => daos.push(dao),
+                        Err(e) => log::debug!("Skipping app: {app_id} in statement: {e:?}"),
+                    }
+                }
+                daos
+            }
+        };
+
+        let mut dao_statements = vec![];
+        for dao in &daos {
+            let entries = dao_entries(&groups, dao, &investor)?;
+            // for all DAOs, list only the ones with activity in the year
+            if entries.is_empty() && pars.dao_id.is_none() {
+                continue;
+            }
+            dao_statements.push(to_dao_statement(dao, entries).await?);
+        }
+
+        Ok(InvestorStatementResJs {
+            csv: investor_statement_csv(&pars.year, &dao_statements),
+            investor_address: pars.investor_address,
+            year: pars.year,
+            daos: dao_statements,
+        })
+    }
+}
+
+struct Entry {
+    type_: StatementEntryTypeJs,
+    date: DateTime<Utc>,
+    shares: u64,
+    amount: FundsAmount,
+    tx_id: TxId,
+}
+
+/// Apps called by the investor, in order of first call
+fn called_app_ids(groups: &[Vec<Transaction>], investor: &Address) -> Vec<u64> {
+    let mut app_ids = vec![];
+    for tx in groups.iter().flatten() {
+        if tx.sender.to_string() != investor.to_string() {
+            continue;
+        }
+        if let Some(app_id) = tx
+            .application_transaction
+            .as_ref()
+            .map(|call| call.application_id)
+            .filter(|id| *id != 0)
+        {
+            if !app_ids.contains(&app_id) {
+                app_ids.push(app_id);
+            }
+        }
+    }
+    app_ids
+}
+
+/// Asset flows between the investor and the DAO's app account in a group.
+/// Purchases are identified by the invest call instead, see [group_purchases].
+#[derive(Default)]
+struct Flows {
+    funds_received: u64,
+    shares_sent: u64,
+    shares_received: u64,
+}
+
+fn dao_entries(groups: &[Vec<Transaction>], dao: &Dao, investor: &Address) -> Result<Vec<Entry>> {
+    let app_address = dao.app_address().to_string();
+    let investor = investor.to_string();
+
+    let mut entries = vec![];
+    for group in groups {
+        let flows = group_flows(group, dao, &app_address, &investor)?;
+
+        // the app call identifies the operation, fall back to the first tx
+        let main_tx = group
+            .iter()
+            .find(|tx| {
+                tx.application_transaction
+                    .as_ref()
+                    .map_or(false, |call| call.application_id == dao.app_id.0)
+            })
+            .unwrap_or(&group[0]);
+        let date = tx_date(main_tx)?;
+        let tx_id: TxId = main_tx.id.parse()?;
+
+        let mut push = |type_, shares, amount| {
+            entries.push(Entry {
+                type_,
+                date,
+                shares,
+                amount: FundsAmount::new(amount),
+                tx_id: tx_id.clone(),
+            })
+        };
+
+        for purchase in group_purchases(group, dao) {
+            if purchase.investor == investor {
+                push(
+                    StatementEntryTypeJs::Purchase,
+                    purchase.shares,
+                    purchase.paid,
+                );
+            }
+        }
+        if flows.shares_sent > 0 && flows.funds_received > 0 {
+            push(
+                StatementEntryTypeJs::Reclaim,
+                flows.shares_sent,
+                flows.funds_received,
+            );
+        } else {
+            if flows.shares_sent > 0 {
+                push(StatementEntryTypeJs::Lock, flows.shares_sent, 0);
+            }
+            if flows.funds_received > 0 {
+                push(StatementEntryTypeJs::Dividend, 0, flows.funds_received);
+            }
+        }
+        if flows.shares_received > 0 {
+            push(StatementEntryTypeJs::Unlock, flows.shares_received, 0);
+        }
+    }
+    Ok(entries)
+}
+
+fn group_flows(
+    group: &[Transaction],
+    dao: &Dao,
+    app_address: &str,
+    investor: &str,
+) -> Result<Flows> {
+    let mut flows = Flows::default();
+    let txs = group
+        .iter()
+        .flat_map(|tx| std::iter::once(tx).chain(tx.inner_txns.iter().flatten()));
+
+    for tx in txs {
+        let xfer = match &tx.asset_transfer_transaction {
+            Some(xfer) => xfer,
+            None => continue,
+        };
+        let sender = tx.sender.to_string();
+        let receiver = xfer.receiver.to_string();
+        let paid = sender == investor && receiver == app_address;
+        let received = sender == app_address && receiver == investor;
+
+        let flow = if xfer.asset_id == dao.funds_asset_id.0 {
+            received.then(|| &mut flows.funds_received)
+        } else if xfer.asset_id == dao.shares_asset_id {
+            match (paid, received) {
+                (true, _) => Some(&mut flows.shares_sent),
+                (_, true) => Some(&mut flows.shares_received),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        if let Some(flow) = flow {
+            *flow = flow
+                .checked_add(xfer.amount)
+                .ok_or_else(|| anyhow!("Overflow adding flows in tx: {}", tx.id))?;
+        }
+    }
+    Ok(flows)
+}
+
+async fn to_dao_statement(dao: &Dao, mut entries: Vec<Entry>) -> Result<DaoStatementJs> {
+    let algod = algod();
+    let funds_asset_specs = funds_asset_specs_for(&algod, dao.funds_asset_id).await?;
+
+    entries.sort_by(|e1, e2| e1.date.cmp(&e2.date));
+
+    let total = |type_, amount: fn(&Entry) -> u64| -> Result<u64> {
+        entries
+            .iter()
+            .filter(|e| e.type_ == type_)
+            .try_fold(0u64, |sum, e| {
+                sum.checked_add(amount(e))
+                    .ok_or_else(|| anyhow!("Overflow adding statement totals"))
+            })
+    };
+    let funds = |amount| base_units_to_display_units(FundsAmount::new(amount), &funds_asset_specs);
+
+    let total_purchases = total(StatementEntryTypeJs::Purchase, |e| e.amount.val())?;
+    let total_dividends = total(StatementEntryTypeJs::Dividend, |e| e.amount.val())?;
+    let total_reclaimed = total(StatementEntryTypeJs::Reclaim, |e| e.amount.val())?;
+    let total_shares_bought = total(StatementEntryTypeJs::Purchase, |e| e.shares)?;
+    let total_shares_reclaimed = total(StatementEntryTypeJs::Reclaim, |e| e.shares)?;
+
+    Ok(DaoStatementJs {
+        dao_id: dao.id().to_string(),
+        dao_name: dao.name.clone(),
+        funds_asset_unit: funds_asset_specs.unit_name.clone(),
+        entries: entries
+            .iter()
+            .map(|e| StatementEntryJs {
+                type_: e.type_,
+                date: e.date.to_rfc3339(),
+                shares: e.shares.to_string(),
+                amount: funds(e.amount.val()).to_string(),
+                amount_base_units: e.amount.to_string(),
+                // the price paid in the purchase, which can differ from the DAO's current price
+                price_per_share: (e.type_ == StatementEntryTypeJs::Purchase && e.shares > 0).then(
+                    || {
+                        (funds(e.amount.val()) / Decimal::from(e.shares))
+                            .normalize()
+                            .to_string()
+                    },
+                ),
+                tx_id: e.tx_id.to_string(),
+                tx_link: explorer_tx_id_link_env(&e.tx_id),
+            })
+            .collect(),
+        total_purchases: funds(total_purchases).to_string(),
+        total_dividends: funds(total_dividends).to_string(),
+        total_reclaimed: funds(total_reclaimed).to_string(),
+        total_shares_bought: total_shares_bought.to_string(),
+        total_shares_reclaimed: total_shares_reclaimed.to_string(),
+    })
+}
+
+fn year_start(year: i32) -> Result<DateTime<Utc>> {
+    Ok(Utc
+        .ymd_opt(year, 1, 1)
+        .single()
+        .ok_or_else(|| anyhow!("Invalid year: {year}"))?
+        .and_hms(0, 0, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_node::{
+        fixtures::{
+            app_address, dao_chain, use_fixture_network_profile, DAO_APP_ID, FUNDS_ASSET_ID,
+            INVESTOR, SHARES_ASSET_ID, SHARE_PRICE,
+        },
+        FakeChain, FakeNode, FakeTx,
+    };
+    use data_encoding::BASE64;
+    use serde_json::json;
+
+    const PURCHASE_TX_ID: &str = "AEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQ";
+    const PURCHASE_PAYMENT_TX_ID: &str = "AIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBA";
+    const CLAIM_TX_ID: &str = "AMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQ";
+    const UNLOCK_TX_ID: &str = "AQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCA";
+    const LOCK_TX_ID: &str = "AUCQKBIFAUCQKBIFAUCQKBIFAUCQKBIFAUCQKBIFAUCQKBIFAUCQ";
+    const RECLAIM_TX_ID: &str = "AYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDA";
+    const PREVIOUS_YEAR_TX_ID: &str = "A4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQ";
+
+    /// 2022-04-15
+    const TIME: i64 = 1_650_000_000;
+    const DAY: i64 = 86_400;
+
+    fn group(id: u8) -> serde_json::Value {
+        json!(BASE64.encode(&[id; 32]))
+    }
+
+    fn funds_from_app(amount: u64, round_time: i64) -> serde_json::Value {
+        json!([FakeTx::asset_transfer(
+            "",
+            &app_address(),
+            INVESTOR,
+            FUNDS_ASSET_ID,
+            amount,
+            round_time
+        )
+        .0])
+    }
+
+    /// In 2022: a purchase of 5 shares, a claim of 1, an unlock of 5 shares, a lock of 3 shares and a reclaim of 2 shares for 2.
+    /// In 2020: another purchase.
+    fn chain_with_investor_activity() -> FakeChain {
+        let app_address = app_address();
+        let mut chain = dao_chain();
+
+        let invest_args: &[&[u8]] = &[b"invest", &5u64.to_be_bytes()];
+        chain.add_tx(
+            FakeTx::app_call(PURCHASE_TX_ID, INVESTOR, DAO_APP_ID, invest_args, TIME)
+                .with("group", group(1)),
+        );
+        chain.add_tx(
+            FakeTx::asset_transfer(
+                PURCHASE_PAYMENT_TX_ID,
+                INVESTOR,
+                &app_address,
+                FUNDS_ASSET_ID,
+                5 * SHARE_PRICE,
+                TIME,
+            )
+            .with("group", group(1)),
+        );
+
+        chain.add_tx(
+            FakeTx::app_call(CLAIM_TX_ID, INVESTOR, DAO_APP_ID, &[b"claim"], TIME + DAY)
+                .with("inner-txns", funds_from_app(1_000_000, TIME + DAY)),
+        );
+
+        chain.add_tx(
+            FakeTx::app_call(
+                UNLOCK_TX_ID,
+                INVESTOR,
+                DAO_APP_ID,
+                &[b"unlock"],
+                TIME + 2 * DAY,
+            )
+            .with(
+                "inner-txns",
+                json!([FakeTx::asset_transfer(
+                    "",
+                    &app_address,
+                    INVESTOR,
+                    SHARES_ASSET_ID,
+                    5,
+                    TIME + 2 * DAY
+                )
+                .0]),
+            ),
+        );
+
+        chain.add_tx(
+            FakeTx::app_call(LOCK_TX_ID, INVESTOR, DAO_APP_ID, &[b"lock"], TIME + 3 * DAY)
+                .with("group", group(4)),
+        );
+        chain.add_tx(
+            FakeTx::asset_transfer(
+                "",
+                INVESTOR,
+                &app_address,
+                SHARES_ASSET_ID,
+                3,
+                TIME + 3 * DAY,
+            )
+            .with("group", group(4)),
+        );
+
+        chain.add_tx(
+            FakeTx::app_call(
+                RECLAIM_TX_ID,
+                INVESTOR,
+                DAO_APP_ID,
+                &[b"reclaim"],
+                TIME + 4 * DAY,
+            )
+            .with("group", group(5))
+            .with("inner-txns", funds_from_app(2_000_000, TIME + 4 * DAY)),
+        );
+        chain.add_tx(
+            FakeTx::asset_transfer(
+                "",
+                INVESTOR,
+                &app_address,
+                SHARES_ASSET_ID,
+                2,
+                TIME + 4 * DAY,
+            )
+            .with("group", group(5)),
+        );
+
+        // 2020-09-13
+        chain.add_tx(FakeTx::app_call(
+            PREVIOUS_YEAR_TX_ID,
+            INVESTOR,
+            DAO_APP_ID,
+            invest_args,
+            1_600_000_000,
+        ));
+
+        chain
+    }
+
+    fn pars(dao_id: Option<u64>, year: &str) -> InvestorStatementParJs {
+        InvestorStatementParJs {
+            investor_address: INVESTOR.to_owned(),
+            dao_id: dao_id.map(|id| id.to_string()),
+            year: year.to_owned(),
+        }
+    }
+
+    #[tokio::test]
+    async fn get_lists_the_year_entries_with_totals() {
+        let _node = FakeNode::start(chain_with_investor_activity()).unwrap();
+        use_fixture_network_profile();
+
+        let res = InvestorStatementProviderDef {}
+            .get(pars(Some(DAO_APP_ID), "2022"))
+            .await
+            .unwrap();
+
+        assert_eq!(1, res.daos.len());
+        let statement = &res.daos[0];
+
+        let rows: Vec<(StatementEntryTypeJs, &str, &str, &str)> = statement
+            .entries
+            .iter()
+            .map(|e| {
+                (
+                    e.type_,
+                    e.shares.as_str(),
+                    e.amount.as_str(),
+                    e.tx_id.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (StatementEntryTypeJs::Purchase, "5", "5", PURCHASE_TX_ID),
+                (StatementEntryTypeJs::Dividend, "0", "1", CLAIM_TX_ID),
+                (StatementEntryTypeJs::Unlock, "5", "0", UNLOCK_TX_ID),
+                (StatementEntryTypeJs::Lock, "3", "0", LOCK_TX_ID),
+                (StatementEntryTypeJs::Reclaim, "2", "2", RECLAIM_TX_ID),
+            ],
+            rows
+        );
+        assert_eq!(Some("1".to_owned()), statement.entries[0].price_per_share);
+        assert!(statement.entries[1..]
+            .iter()
+            .all(|e| e.price_per_share.is_none()));
+
+        assert_eq!("5", statement.total_purchases);
+        assert_eq!("1", statement.total_dividends);
+        assert_eq!("2", statement.total_reclaimed);
+        assert_eq!("5", statement.total_shares_bought);
+        assert_eq!("2", statement.total_shares_reclaimed);
+    }
+
+    #[tokio::test]
+    async fn get_without_dao_lists_only_the_daos_with_activity_in_the_year() {
+        let _node = FakeNode::start(chain_with_investor_activity()).unwrap();
+        use_fixture_network_profile();
+
+        let res = InvestorStatementProviderDef {}
+            .get(pars(None, "2022"))
+            .await
+            .unwrap();
+        assert_eq!(1, res.daos.len());
+        assert_eq!(5, res.daos[0].entries.len());
+
+        // only the purchase of 2020
+        let res = InvestorStatementProviderDef {}
+            .get(pars(None, "2020"))
+            .await
+            .unwrap();
+        assert_eq!(1, res.daos.len());
+        assert_eq!(1, res.daos[0].entries.len());
+        assert_eq!(PREVIOUS_YEAR_TX_ID, res.daos[0].entries[0].tx_id);
+
+        let res = InvestorStatementProviderDef {}
+            .get(pars(None, "2021"))
+            .await
+            .unwrap();
+        assert!(res.daos.is_empty());
+    }
+}
@@ -1,16 +1,17 @@
+use crate::dependencies::algod;
 use crate::error::FrError;
-use crate::js::common::signed_js_tx_to_signed_tx1;
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs};
 use crate::js::to_sign_js::ToSignJs;
 use crate::provider::lock_provider::{
     LockParJs, LockProvider, LockResJs, SubmitLockParJs, SubmitLockResJs,
 };
+use crate::service::dao_cache::{invalidate_after_submit, load_dao};
 use crate::service::invest_or_lock::submit_apps_optins_from_js;
 use crate::service::number_formats::validate_share_amount_positive;
 use anyhow::{Error, Result};
 use async_trait::async_trait;
+use base::flows::lock::lock::lock;
 use base::flows::lock::lock::{submit_lock, LockSigned};
-use base::flows::{create_dao::storage::load_dao::load_dao, lock::lock::lock};
-use mbase::dependencies::algod;
 use mbase::util::network_util::wait_for_pending_transaction;
 
 pub struct LockProviderDef {}
@@ -36,10 +37,14 @@ impl LockProvider for LockProviderDef {
         )
         .await?;
 
-        let to_sign_txs = vec![to_sign.central_app_call_setup_tx, to_sign.shares_xfer_tx];
+        let message = format!("Lock {validated_share_amount} shares of {}", dao.name);
+        let to_sign_txs = vec![
+            (to_sign.central_app_call_setup_tx, message.clone()),
+            (to_sign.shares_xfer_tx, message),
+        ];
 
         Ok(LockResJs {
-            to_sign: ToSignJs::new(to_sign_txs)?,
+            to_sign: ToSignJs::with_messages(to_sign_txs).await?,
         })
     }
 
@@ -58,6 +63,8 @@ impl LockProvider for LockProviderDef {
             )));
         }
 
+        verify_signed_js_txs(&pars.txs)?;
+
         // lock tx group
         let central_app_call_tx = &pars.txs[0];
         let shares_xfer_tx = &pars.txs[1];
@@ -75,6 +82,8 @@ impl LockProvider for LockProviderDef {
 
         let _ = wait_for_pending_transaction(&algod, &tx_id).await?;
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         Ok(SubmitLockResJs {})
     }
 }
@@ -6,6 +6,7 @@ pub mod calculate_total_price_def;
 pub mod claim_provider_def;
 pub mod create_assets_provider_def;
 pub mod create_dao_provider_def;
+pub mod dao_content_provider_def;
 pub mod dao_provider_def;
 pub mod description_provider_def;
 pub mod dev_provider_def;
@@ -17,6 +18,7 @@ pub mod hash_provider_def;
 pub mod holders_count_provider_def;
 pub mod income_vs_spending_provider_def;
 pub mod investment_provider_def;
+pub mod investor_statement_provider_def;
 pub mod lock_provider_def;
 pub mod metadata_provider_def;
 pub mod my_daos_provider_def;
@@ -1,3 +1,4 @@
+use crate::dependencies::{algod, indexer};
 use crate::{
     error::FrError,
     provider::my_daos_provider::{MyDaosParJs, MyDaosProvider, MyDaosResJs},
@@ -5,7 +6,6 @@ use crate::{
 use anyhow::{Error, Result};
 use async_trait::async_trait;
 use base::queries::my_daos::my_daos;
-use mbase::dependencies::{algod, indexer};
 
 pub struct MyDaosProviderDef {}
 
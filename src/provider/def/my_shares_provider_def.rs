@@ -1,14 +1,13 @@
+use crate::dependencies::algod;
 use crate::error::FrError;
 use crate::provider::my_shares_provider::{MySharesParJs, MySharesProvider, MySharesResJs};
+use crate::service::dao_cache::{dao_investor_state, load_dao};
 use anyhow::{anyhow, Error, Result};
 use async_trait::async_trait;
-use base::flows::create_dao::storage::load_dao::load_dao;
 use base::state::account_state::asset_holdings;
 use mbase::checked::CheckedAdd;
-use mbase::dependencies::algod;
 use mbase::models::share_amount::ShareAmount;
 use mbase::state::app_state::ApplicationLocalStateError;
-use mbase::state::dao_app_state::dao_investor_state;
 
 pub struct MySharesProviderDef {}
 
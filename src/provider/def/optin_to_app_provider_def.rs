@@ -1,4 +1,5 @@
 use crate::{
+    dependencies::algod,
     error::FrError,
     js::to_sign_js::ToSignJs,
     provider::optin_to_app_provider::{OptInToAppParJs, OptInToAppResJs, OptinToAppProvider},
@@ -11,7 +12,7 @@ use algonaut::{
 use anyhow::{Error, Result};
 use async_trait::async_trait;
 use base::flows::shared::app::optin_to_dao_app;
-use mbase::{dependencies::algod, models::dao_app_id::DaoAppId};
+use mbase::models::dao_app_id::DaoAppId;
 
 pub struct OptinToAppProviderDef {}
 
@@ -50,8 +51,13 @@ impl OptinToAppProvider for OptinToAppProviderDef {
                 )));
             }
 
+            let optins = optins
+                .into_iter()
+                .map(|tx| (tx, "Opt in to the DAO's app, to hold shares".to_owned()))
+                .collect();
+
             Ok(OptInToAppResJs {
-                to_sign: Some(ToSignJs::new(optins)?),
+                to_sign: Some(ToSignJs::with_messages(optins).await?),
             })
         }
     }
@@ -1,17 +1,20 @@
+use crate::dependencies::algod;
 use crate::error::FrError;
-use crate::js::common::signed_js_tx_to_signed_tx1;
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs};
 use crate::js::to_sign_js::ToSignJs;
+use crate::locale::locale;
 use crate::provider::pay_dao_provider::{
     PayDaoParJs, PayDaoProvider, PayDaoResJs, SubmitPayDaoParJs, SubmitPayDaoResJs,
 };
-use crate::{
-    dependencies::funds_asset_specs, service::number_formats::validate_funds_amount_input,
+use crate::service::dao_cache::{invalidate_after_submit, load_dao};
+use crate::service::{
+    funds_asset_specs::funds_asset_specs_for,
+    number_formats::{base_units_to_display_units_readable, validate_funds_amount_input},
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
 use base::flows::pay_dao::pay_dao::pay_dao_app;
 use base::flows::pay_dao::pay_dao::{submit_pay_dao, PayDaoSigned};
-use mbase::dependencies::algod;
 use mbase::models::dao_id::DaoId;
 
 pub struct PayDaoProviderDef {}
@@ -20,11 +23,14 @@ pub struct PayDaoProviderDef {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl PayDaoProvider for PayDaoProviderDef {
     async fn txs(&self, pars: PayDaoParJs) -> Result<PayDaoResJs, FrError> {
+        let locale = locale();
         let algod = algod();
-        let funds_asset_specs = funds_asset_specs()?;
 
         let customer_address = pars.customer_address.parse().map_err(Error::msg)?;
         let dao_id: DaoId = pars.dao_id.parse().map_err(Error::msg)?;
+
+        let dao = load_dao(&algod, dao_id).await?;
+        let funds_asset_specs = funds_asset_specs_for(&algod, dao.funds_asset_id).await?;
         let amount = validate_funds_amount_input(&pars.amount, &funds_asset_specs)?;
 
         let to_sign = pay_dao_app(
@@ -37,7 +43,16 @@ impl PayDaoProvider for PayDaoProviderDef {
         .await?;
 
         Ok(PayDaoResJs {
-            to_sign: ToSignJs::new(vec![to_sign.tx])?,
+            to_sign: ToSignJs::with_messages(vec![(
+                to_sign.tx,
+                format!(
+                    "Pay {} {} to {}",
+                    base_units_to_display_units_readable(amount, &funds_asset_specs, &locale)?,
+                    funds_asset_specs.unit_name,
+                    dao.name
+                ),
+            )])
+            .await?,
         })
     }
 
@@ -50,6 +65,8 @@ impl PayDaoProvider for PayDaoProviderDef {
                 pars.txs.len()
             )));
         }
+
+        verify_signed_js_txs(&pars.txs)?;
         let tx = &pars.txs[0];
 
         let res = submit_pay_dao(
@@ -62,6 +79,8 @@ impl PayDaoProvider for PayDaoProviderDef {
 
         log::debug!("Submit pay dao res: {:?}", res);
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         Ok(SubmitPayDaoResJs {})
     }
 }
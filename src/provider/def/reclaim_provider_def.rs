@@ -1,16 +1,15 @@
+use crate::dependencies::algod;
 use crate::error::FrError;
-use crate::js::common::signed_js_tx_to_signed_tx1;
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs};
 use crate::js::to_sign_js::ToSignJs;
 use crate::provider::reclaim_provider::{
     ReclaimParJs, ReclaimProvider, ReclaimResJs, SubmitReclaimParJs, SubmitReclaimResJs,
 };
+use crate::service::dao_cache::{dao_investor_state, invalidate_after_submit, load_dao};
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use base::flows::create_dao::storage::load_dao::load_dao;
 use base::flows::reclaim::reclaim::{reclaim, submit_reclaim, ReclaimSigned};
-use mbase::dependencies::algod;
 use mbase::models::share_amount::ShareAmount;
-use mbase::state::dao_app_state::dao_investor_state;
 
 pub struct ReclaimProviderDef {}
 
@@ -39,10 +38,14 @@ impl ReclaimProvider for ReclaimProviderDef {
         )
         .await?;
 
-        let to_sign_txs = vec![to_sign.app_call_tx, to_sign.shares_xfer_tx];
+        let message = format!("Reclaim funds for {share_amount} shares of {}", dao.name);
+        let to_sign_txs = vec![
+            (to_sign.app_call_tx, message.clone()),
+            (to_sign.shares_xfer_tx, message),
+        ];
 
         Ok(ReclaimResJs {
-            to_sign: ToSignJs::new(to_sign_txs)?,
+            to_sign: ToSignJs::with_messages(to_sign_txs).await?,
         })
     }
 
@@ -56,6 +59,8 @@ impl ReclaimProvider for ReclaimProviderDef {
             )));
         }
 
+        verify_signed_js_txs(&pars.txs)?;
+
         let app_call_tx = signed_js_tx_to_signed_tx1(&pars.txs[0])?;
         let shares_xfer = signed_js_tx_to_signed_tx1(&pars.txs[1])?;
 
@@ -70,6 +75,8 @@ impl ReclaimProvider for ReclaimProviderDef {
 
         log::debug!("Submit reclaim res: {:?}", res);
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         Ok(SubmitReclaimResJs {})
     }
 }
@@ -1,15 +1,15 @@
+use crate::dependencies::algod;
 use crate::error::FrError;
-use crate::js::common::signed_js_tx_to_signed_tx1;
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs};
 use crate::js::to_sign_js::ToSignJs;
 use crate::provider::create_dao_provider::validate_address;
 use crate::provider::rekey_provider::{
     RekeyParJs, RekeyProvider, RekeyResJs, SubmitRekeyParJs, SubmitRekeyResJs,
 };
+use crate::service::dao_cache::{invalidate_after_submit, load_dao};
 use anyhow::Result;
 use async_trait::async_trait;
-use base::flows::create_dao::storage::load_dao::load_dao;
 use base::flows::rekey::rekey::{rekey, submit_rekey, RekeySigned};
-use mbase::dependencies::algod;
 
 pub struct RekeyProviderDef {}
 
@@ -28,7 +28,11 @@ impl RekeyProvider for RekeyProviderDef {
         let to_sign = rekey(&algod, &dao.owner, &auth).await?;
 
         Ok(RekeyResJs {
-            to_sign: ToSignJs::new(vec![to_sign.tx])?,
+            to_sign: ToSignJs::with_messages(vec![(
+                to_sign.tx,
+                format!("Rekey the owner account of {} to {auth}", dao.name),
+            )])
+            .await?,
         })
     }
 
@@ -42,6 +46,8 @@ impl RekeyProvider for RekeyProviderDef {
             )));
         }
 
+        verify_signed_js_txs(&pars.txs)?;
+
         submit_rekey(
             &algod,
             RekeySigned {
@@ -50,6 +56,8 @@ impl RekeyProvider for RekeyProviderDef {
         )
         .await?;
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         Ok(SubmitRekeyResJs {})
     }
 }
@@ -1,14 +1,21 @@
+use crate::dependencies::{algod, indexer};
 use crate::error::FrError;
 use crate::js::explorer_links::explorer_tx_id_link_env;
 use crate::provider::roadmap_provider::{
-    GetRoadmapParJs, GetRoadmapResJs, RoadmapItemJs, RoadmapProvider,
+    roadmap_calendar_event, roadmap_calendar_file, validate_dao_url, ExportRoadmapParJs,
+    GetRoadmapParJs, GetRoadmapResJs, RoadmapGroupingJs, RoadmapItemChangeViewJs, RoadmapItemJs,
+    RoadmapProvider,
 };
-use anyhow::{anyhow, Error, Result};
+use crate::service::dao_cache::load_dao;
+use crate::service::export::ExportResJs;
+use crate::service::roadmap::{resolve_roadmap, tx_id_to_hash, RoadmapChange, RoadmapEntry};
+use anyhow::{Error, Result};
 use async_trait::async_trait;
-use base::roadmap::get_roadmap::{get_roadmap, SavedRoadmapItem};
-use chrono::Datelike;
-use mbase::dependencies::indexer;
-use std::collections::BTreeMap;
+use base::roadmap::get_roadmap::get_roadmap;
+use chrono::{DateTime, Datelike, Utc};
+use futures::try_join;
+use mbase::models::tx_id::TxId;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 pub struct RoadmapProviderDef {}
 
@@ -20,102 +27,177 @@ impl RoadmapProvider for RoadmapProviderDef {
 
         let dao_creator = pars.creator_address.parse().map_err(Error::msg)?;
         let dao_id = pars.dao_id.parse()?;
+        let grouping = pars.grouping.unwrap_or(RoadmapGroupingJs::Quarter);
 
         let roadmap = get_roadmap(&indexer, &dao_creator, dao_id).await?;
-        let mut items = roadmap.items;
+        let mut items = resolve_roadmap(roadmap.items)?;
         // sort ascendingly by date
         items.sort_by(|i1, i2| i1.date.cmp(&i2.date));
 
-        let grouped = group_by_time_range(items)?;
+        let tree = to_tree(items)?;
+        let grouped = group_by_time_range(tree, grouping);
         let all_and_flat_items = to_flat_roadmap_items(grouped);
 
         Ok(GetRoadmapResJs {
             items: to_js_items(all_and_flat_items),
         })
     }
+
+    async fn export_calendar(&self, pars: ExportRoadmapParJs) -> Result<ExportResJs, FrError> {
+        let algod = algod();
+        let indexer = indexer();
+
+        validate_dao_url(&pars.dao_url)?;
+        let dao_creator = pars.creator_address.parse().map_err(Error::msg)?;
+        let dao_id = pars.dao_id.parse()?;
+
+        let (dao, roadmap) = try_join!(
+            load_dao(&algod, dao_id),
+            get_roadmap(&indexer, &dao_creator, dao_id)
+        )?;
+        let mut items = resolve_roadmap(roadmap.items)?;
+        items.sort_by(|i1, i2| i1.date.cmp(&i2.date));
+
+        let events: Vec<_> = items
+            .iter()
+            .map(|item| {
+                roadmap_calendar_event(
+                    &item.tx_id.to_string(),
+                    item.date,
+                    &item.title,
+                    item.description.as_deref(),
+                    item.link.as_deref(),
+                    item.completed(),
+                    &pars.dao_url,
+                )
+            })
+            .collect();
+
+        Ok(roadmap_calendar_file(&pars.dao_id, &dao.name, &events)?)
+    }
 }
 
 #[derive(Debug, Clone)]
 enum RoadmapItemView {
-    Item(SavedRoadmapItem),
-    Header(YearQuarter),
+    Item(RoadmapNode),
+    Header(TimeRange),
+}
+
+#[derive(Debug, Clone)]
+struct RoadmapNode {
+    item: RoadmapEntry,
+    children: Vec<RoadmapNode>,
+}
+
+/// Nests the items under their parents (e.g. epics).
+/// Items whose parent isn't in the roadmap (removed, or not a roadmap item) are top level items.
+/// Keeps the order of items, i.e. if they're sorted, the top level items and children are sorted too.
+fn to_tree(items: Vec<RoadmapEntry>) -> Result<Vec<RoadmapNode>> {
+    let item_hashes = items
+        .iter()
+        .map(|item| Ok(tx_id_to_hash(&item.tx_id)?.0))
+        .collect::<Result<HashSet<_>>>()?;
+
+    let mut top_level = vec![];
+    let mut children_by_parent: HashMap<[u8; 32], Vec<RoadmapEntry>> = HashMap::new();
+    for item in items {
+        match item.parent {
+            Some(parent) if item_hashes.contains(&parent.0) => {
+                children_by_parent.entry(parent.0).or_default().push(item)
+            }
+            _ => top_level.push(item),
+        }
+    }
+
+    top_level
+        .into_iter()
+        .map(|item| to_node(item, &mut children_by_parent))
+        .collect()
+}
+
+fn to_node(
+    item: RoadmapEntry,
+    children_by_parent: &mut HashMap<[u8; 32], Vec<RoadmapEntry>>,
+) -> Result<RoadmapNode> {
+    let children = children_by_parent
+        .remove(&tx_id_to_hash(&item.tx_id)?.0)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| to_node(child, children_by_parent))
+        .collect::<Result<_>>()?;
+    Ok(RoadmapNode { item, children })
 }
 
 /// Assumes that items is sorted ascendingly.
 /// The returned keys as well as the items in the values are sorted ascendingly as well.
+/// Sub-items are grouped with their top level item.
 fn group_by_time_range(
-    items: Vec<SavedRoadmapItem>,
-) -> Result<BTreeMap<YearQuarter, Vec<SavedRoadmapItem>>> {
+    items: Vec<RoadmapNode>,
+    grouping: RoadmapGroupingJs,
+) -> BTreeMap<TimeRange, Vec<RoadmapNode>> {
     let mut map = BTreeMap::new();
 
     for item in items {
-        let quarter = to_quarter(item.date.month())?;
-        log::debug!(
-            "date: {}, month: {}, quarter: {:?}",
-            item.date,
-            item.date.month(),
-            quarter
-        );
-        let year = item.date.year();
-        let year_quarter = YearQuarter { quarter, year };
+        let time_range = TimeRange::new(item.item.date, grouping);
+        log::debug!("date: {}, time range: {:?}", item.item.date, time_range);
 
-        map.entry(year_quarter).or_insert_with(Vec::new).push(item);
+        map.entry(time_range).or_insert_with(Vec::new).push(item);
     }
 
-    Ok(map)
-}
-
-// Note 1-indexed month
-fn to_quarter(month: u32) -> Result<Quarter> {
-    match month {
-        1..=3 => Ok(Quarter::Q1),
-        4..=6 => Ok(Quarter::Q2),
-        7..=9 => Ok(Quarter::Q3),
-        10..=12 => Ok(Quarter::Q4),
-        _ => Err(anyhow!("Invalid month number: {month}")),
-    }
+    map
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
-struct YearQuarter {
+struct TimeRange {
     // NOTE: don't change field order (PartialOrd macro)
     year: i32,
-    quarter: Quarter,
+    /// 0-indexed position in the year, e.g. 0..=3 for quarters
+    period: u32,
+    grouping: RoadmapGroupingJs,
 }
 
-impl ToString for YearQuarter {
-    fn to_string(&self) -> String {
-        format!("{} {}", self.quarter.to_string(), self.year)
+impl TimeRange {
+    fn new(date: DateTime<Utc>, grouping: RoadmapGroupingJs) -> TimeRange {
+        let months_per_period = match grouping {
+            RoadmapGroupingJs::Month => 1,
+            RoadmapGroupingJs::Quarter => 3,
+            RoadmapGroupingJs::HalfYear => 6,
+            RoadmapGroupingJs::Year => 12,
+        };
+        TimeRange {
+            year: date.year(),
+            period: date.month0() / months_per_period,
+            grouping,
+        }
     }
 }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
-enum Quarter {
-    // NOTE: don't change field order (PartialOrd macro)
-    Q1,
-    Q2,
-    Q3,
-    Q4,
-}
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
 
-impl ToString for Quarter {
+impl ToString for TimeRange {
     fn to_string(&self) -> String {
-        format!("{self:?}")
+        match self.grouping {
+            RoadmapGroupingJs::Month => format!("{} {}", MONTHS[self.period as usize], self.year),
+            RoadmapGroupingJs::Quarter => format!("Q{} {}", self.period + 1, self.year),
+            RoadmapGroupingJs::HalfYear => format!("H{} {}", self.period + 1, self.year),
+            RoadmapGroupingJs::Year => self.year.to_string(),
+        }
     }
 }
 
 fn to_flat_roadmap_items(
-    grouped_items: BTreeMap<YearQuarter, Vec<SavedRoadmapItem>>,
+    grouped_items: BTreeMap<TimeRange, Vec<RoadmapNode>>,
 ) -> Vec<RoadmapItemView> {
     let mut flat_items = vec![];
 
-    for year_quarter in grouped_items.keys() {
-        let items = grouped_items[year_quarter].clone();
+    for (time_range, items) in grouped_items {
         for item in items {
             flat_items.push(RoadmapItemView::Item(item));
         }
         // header at the end (for UI)
-        flat_items.push(RoadmapItemView::Header(year_quarter.to_owned()));
+        flat_items.push(RoadmapItemView::Header(time_range));
     }
 
     flat_items
@@ -127,19 +209,155 @@ fn to_js_items(items: Vec<RoadmapItemView>) -> Vec<RoadmapItemJs> {
 
 fn to_js_item(item: RoadmapItemView) -> RoadmapItemJs {
     match item {
-        RoadmapItemView::Item(item) => RoadmapItemJs {
-            item_type: "item".to_owned(),
-            tx_id: Some(item.tx_id.to_string()),
-            tx_link: Some(explorer_tx_id_link_env(&item.tx_id)),
-            date: Some(item.date.timestamp().to_string()),
-            text: item.title,
-        },
-        RoadmapItemView::Header(year_quarter) => RoadmapItemJs {
+        RoadmapItemView::Item(node) => to_js_node(node, None),
+        RoadmapItemView::Header(time_range) => RoadmapItemJs {
             item_type: "header".to_owned(),
             tx_id: None,
             tx_link: None,
             date: None,
-            text: year_quarter.to_string(),
+            text: time_range.to_string(),
+            description: None,
+            link: None,
+            parent_tx_id: None,
+            children: vec![],
+            status: None,
+            completed: false,
+            completed_date: None,
+            edited: false,
+            history: vec![],
         },
     }
 }
+
+fn to_js_node(node: RoadmapNode, parent: Option<&TxId>) -> RoadmapItemJs {
+    let item = node.item;
+    RoadmapItemJs {
+        item_type: "item".to_owned(),
+        tx_id: Some(item.tx_id.to_string()),
+        tx_link: Some(explorer_tx_id_link_env(&item.tx_id)),
+        date: Some(item.date.timestamp().to_string()),
+        status: Some(status(&item).to_owned()),
+        completed: item.completed(),
+        completed_date: item.completed_date.map(|d| d.timestamp().to_string()),
+        edited: item.edited(),
+        parent_tx_id: parent.map(|p| p.to_string()),
+        children: node
+            .children
+            .into_iter()
+            .map(|child| to_js_node(child, Some(&item.tx_id)))
+            .collect(),
+        text: item.title,
+        description: item.description,
+        link: item.link,
+        history: item.history.into_iter().map(to_js_change).collect(),
+    }
+}
+
+fn status(item: &RoadmapEntry) -> &'static str {
+    if item.completed() {
+        "completed"
+    } else if item.date < Utc::now() {
+        "overdue"
+    } else {
+        "planned"
+    }
+}
+
+fn to_js_change(change: RoadmapChange) -> RoadmapItemChangeViewJs {
+    RoadmapItemChangeViewJs {
+        op: change.op.to_string(),
+        tx_id: change.tx_id.to_string(),
+        tx_link: explorer_tx_id_link_env(&change.tx_id),
+        text: change.title,
+        date: change.date.timestamp().to_string(),
+        saved_date: change.saved_at.map(|d| d.timestamp().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use data_encoding::BASE32_NOPAD;
+
+    fn tx_id(seed: u8) -> TxId {
+        BASE32_NOPAD.encode(&[seed; 32]).parse().unwrap()
+    }
+
+    fn entry(seed: u8, month: u32, parent: Option<u8>) -> RoadmapEntry {
+        RoadmapEntry {
+            tx_id: tx_id(seed),
+            parent: parent.map(|p| tx_id_to_hash(&tx_id(p)).unwrap()),
+            title: format!("Item {seed}"),
+            date: Utc.ymd(2022, month, 1).and_hms(0, 0, 0),
+            description: None,
+            link: None,
+            completed_date: None,
+            history: vec![],
+        }
+    }
+
+    fn titles(nodes: &[RoadmapNode]) -> Vec<String> {
+        nodes.iter().map(|n| n.item.title.clone()).collect()
+    }
+
+    #[test]
+    fn to_tree_nests_sub_items_under_their_parents() {
+        let tree = to_tree(vec![
+            entry(1, 1, None),
+            entry(2, 2, Some(1)),
+            entry(3, 3, Some(2)),
+            entry(4, 4, None),
+            entry(5, 5, Some(1)),
+        ])
+        .unwrap();
+
+        assert_eq!(vec!["Item 1", "Item 4"], titles(&tree));
+        assert_eq!(vec!["Item 2", "Item 5"], titles(&tree[0].children));
+        assert_eq!(vec!["Item 3"], titles(&tree[0].children[0].children));
+        assert!(tree[1].children.is_empty());
+    }
+
+    #[test]
+    fn to_tree_shows_sub_items_of_removed_parents_as_top_level() {
+        // 9 isn't in the roadmap (e.g. removed)
+        let tree = to_tree(vec![entry(1, 1, None), entry(2, 2, Some(9))]).unwrap();
+
+        assert_eq!(vec!["Item 1", "Item 2"], titles(&tree));
+        assert!(tree.iter().all(|n| n.children.is_empty()));
+    }
+
+    #[test]
+    fn group_by_time_range_groups_sub_items_with_their_parent() {
+        // the sub-item is in Q3, the parent in Q1
+        let tree = to_tree(vec![
+            entry(1, 1, None),
+            entry(2, 4, None),
+            entry(3, 7, Some(1)),
+        ])
+        .unwrap();
+
+        let grouped = group_by_time_range(tree, RoadmapGroupingJs::Quarter);
+
+        let q1 = TimeRange::new(
+            Utc.ymd(2022, 1, 1).and_hms(0, 0, 0),
+            RoadmapGroupingJs::Quarter,
+        );
+        let q2 = TimeRange::new(
+            Utc.ymd(2022, 4, 1).and_hms(0, 0, 0),
+            RoadmapGroupingJs::Quarter,
+        );
+        assert_eq!(vec![&q1, &q2], grouped.keys().collect::<Vec<_>>());
+        assert_eq!(vec!["Item 1"], titles(&grouped[&q1]));
+        assert_eq!(vec!["Item 3"], titles(&grouped[&q1][0].children));
+        assert_eq!(vec!["Item 2"], titles(&grouped[&q2]));
+
+        let items = to_js_items(to_flat_roadmap_items(grouped));
+        let types: Vec<&str> = items.iter().map(|i| i.item_type.as_str()).collect();
+        assert_eq!(vec!["item", "header", "item", "header"], types);
+        assert_eq!(
+            Some(tx_id(1).to_string()),
+            items[0].children[0].parent_tx_id
+        );
+    }
+}
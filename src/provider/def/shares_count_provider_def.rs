@@ -1,3 +1,4 @@
+use crate::dependencies::algod;
 use crate::{
     error::FrError,
     provider::shares_count_provider::{GetUserSharesCountParJs, SharesCountProvider},
@@ -5,7 +6,6 @@ use crate::{
 use anyhow::{Error, Result};
 use async_trait::async_trait;
 use base::state::account_state::asset_holdings;
-use mbase::dependencies::algod;
 
 pub struct SharesCountProviderDef {}
 
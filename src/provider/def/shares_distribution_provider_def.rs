@@ -1,16 +1,21 @@
+use crate::dependencies::{algod, indexer};
 use crate::error::FrError;
 use crate::js::explorer_links::explorer_address_link_env;
+use crate::locale::{locale, UserLocale};
+use crate::provider::def::investment_provider_def::investor_local_state_view_data;
 use crate::provider::shares_distribution_provider::{
-    ShareHoldingPercentageJs, SharedDistributionParJs, SharedDistributionResJs,
+    ConcentrationJs, ShareHoldingPercentageJs, SharedDistributionParJs, SharedDistributionResJs,
     SharesDistributionProvider,
 };
-use crate::service::number_formats::format_short;
-use algonaut::core::Address;
+use crate::service::number_formats::{format_display_units, format_percentage, format_short};
+use algonaut::{algod::v2::Algod, core::Address};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use base::queries::shares_distribution::{shares_holders_distribution, ShareHoldingPercentage};
-use mbase::dependencies::{algod, indexer};
-use mbase::util::decimal_util::{AsDecimal, DecimalExt};
+use futures::{stream, StreamExt};
+use mbase::models::dao_app_id::DaoAppId;
+use mbase::util::decimal_util::AsDecimal;
+use rust_decimal::Decimal;
 
 pub struct SharesDistributionProviderDef {}
 
@@ -18,44 +23,238 @@ pub struct SharesDistributionProviderDef {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl SharesDistributionProvider for SharesDistributionProviderDef {
     async fn get(&self, pars: SharedDistributionParJs) -> Result<SharedDistributionResJs, FrError> {
+        let locale = locale();
         let algod = algod();
         let indexer = indexer();
 
         let asset_id = pars.asset_id.parse()?;
         let share_supply = pars.share_supply.parse()?;
         let app_id = pars.app_id.parse()?;
+        let top_n = pars.top_n.map(|n| n.parse()).transpose()?;
 
         let holders =
             shares_holders_distribution(&algod, &indexer, asset_id, app_id, share_supply).await?;
 
-        let mut holders_js = vec![];
-        for h in &holders {
-            holders_js.push(ShareHoldingPercentageJs {
-                address: h.address.to_string(),
-                label: shorten_address(&h.address)?,
-                address_browser_link: explorer_address_link_env(&h.address),
-                amount: h.amount.to_string(),
-                percentage_formatted: h.percentage.format_percentage(),
-                percentage_number: h.percentage.to_string(),
-                type_: "holder".to_owned(),
-            });
-        }
+        // bounded: DAOs can have many holders, and the node rate limits
+        let splits: Vec<Option<SharesSplit>> = stream::iter(
+            holders
+                .iter()
+                .map(|h| shares_split_or_log(&algod, h, app_id)),
+        )
+        .buffered(MAX_CONCURRENT_SPLIT_REQUESTS)
+        .collect()
+        .await;
+        let holders = holders
+            .into_iter()
+            .zip(splits)
+            .map(|(holding, split)| HolderWithSplit { holding, split })
+            .collect::<Vec<_>>();
 
-        let not_owned = not_owned_shares_holdings(&holders, share_supply)?;
-        let not_owned_shares = not_owned.amount.clone();
-        holders_js.push(not_owned);
+        Ok(to_shares_distribution_js(
+            holders,
+            share_supply,
+            top_n,
+            &locale,
+        )?)
+    }
+}
+
+/// Max. parallel requests to fetch the holders' splits
+const MAX_CONCURRENT_SPLIT_REQUESTS: usize = 10;
+
+/// The shares of a holder: locked in the app and held in the account
+#[derive(Debug, Clone, Copy)]
+pub struct SharesSplit {
+    /// App local state
+    pub locked: u64,
+    /// Asset holding
+    pub free: u64,
+}
 
-        Ok(SharedDistributionResJs {
-            holders: holders_js,
-            not_owned_shares,
+/// A holding, with its split
+pub struct HolderWithSplit {
+    pub holding: ShareHoldingPercentage,
+    /// None if it couldn't be fetched
+    pub split: Option<SharesSplit>,
+}
+
+/// The holding's amount includes the locked shares, so only the local state has to be fetched
+async fn shares_split(
+    algod: &Algod,
+    holding: &ShareHoldingPercentage,
+    app_id: DaoAppId,
+) -> Result<SharesSplit> {
+    // the local state is served from the session cache (see [crate::service::dao_cache])
+    let local_state = investor_local_state_view_data(algod, &holding.address, app_id).await?;
+    let locked = local_state.locked_shares.val();
+    Ok(SharesSplit {
+        locked,
+        free: holding.amount.val().checked_sub(locked).ok_or_else(|| {
+            anyhow!(
+                "Locked shares: {locked} exceed the holding: {}",
+                holding.amount.val()
+            )
+        })?,
+    })
+}
+
+/// The split is informative: a holder whose split can't be fetched is shown without it
+async fn shares_split_or_log(
+    algod: &Algod,
+    holding: &ShareHoldingPercentage,
+    app_id: DaoAppId,
+) -> Option<SharesSplit> {
+    shares_split(algod, holding, app_id)
+        .await
+        .map_err(|e| log::error!("Couldn't fetch shares split of: {}: {e:?}", holding.address))
+        .ok()
+}
+
+// pub: shares with mock data
+/// The holders (largest first, grouped into "Others" after `top_n`), with "Not owned" at the end
+pub fn to_shares_distribution_js(
+    mut holders: Vec<HolderWithSplit>,
+    supply: u64,
+    top_n: Option<usize>,
+    locale: &UserLocale,
+) -> Result<SharedDistributionResJs> {
+    holders.sort_by(|h1, h2| h2.holding.amount.val().cmp(&h1.holding.amount.val()));
+
+    let all_holdings: Vec<ShareHoldingPercentage> =
+        holders.iter().map(|h| h.holding.clone()).collect();
+    let concentration = concentration_metrics(&all_holdings, supply, locale)?;
+
+    let shown_count = top_n.unwrap_or(holders.len()).min(holders.len());
+    let (shown, others) = holders.split_at(shown_count);
+
+    let mut holders_js = vec![];
+    for h in shown {
+        holders_js.push(ShareHoldingPercentageJs {
+            address: h.holding.address.to_string(),
+            label: shorten_address(&h.holding.address)?,
+            address_browser_link: explorer_address_link_env(&h.holding.address),
+            amount: h.holding.amount.to_string(),
+            percentage_formatted: format_percentage(h.holding.percentage, locale)?,
+            percentage_number: h.holding.percentage.to_string(),
+            type_: "holder".to_owned(),
+            locked: h.split.map(|s| s.locked.to_string()),
+            free: h.split.map(|s| s.free.to_string()),
+        });
+    }
+    if !others.is_empty() {
+        holders_js.push(others_holdings(others, locale)?);
+    }
+
+    let not_owned = not_owned_shares_holdings(&all_holdings, supply, locale)?;
+    let not_owned_shares = not_owned.amount.clone();
+    holders_js.push(not_owned);
+
+    Ok(SharedDistributionResJs {
+        holders: holders_js,
+        not_owned_shares,
+        concentration,
+    })
+}
+
+fn others_holdings(
+    others: &[HolderWithSplit],
+    locale: &UserLocale,
+) -> Result<ShareHoldingPercentageJs> {
+    let sum = |values: &mut dyn Iterator<Item = u64>| -> Result<u64> {
+        values.try_fold(0u64, |sum, value| {
+            sum.checked_add(value)
+                .ok_or_else(|| anyhow!("Overflow adding others holdings"))
         })
+    };
+    // the splits are summed only if all are known
+    let splits: Option<Vec<SharesSplit>> = others.iter().map(|h| h.split).collect();
+    let split_sum = |value: fn(&SharesSplit) -> u64| -> Result<Option<String>> {
+        splits
+            .as_ref()
+            .map(|splits| sum(&mut splits.iter().map(value)).map(|sum| sum.to_string()))
+            .transpose()
+    };
+    let amount = sum(&mut others.iter().map(|h| h.holding.amount.val()))?;
+    let percentage: Decimal = others.iter().map(|h| h.holding.percentage).sum();
+
+    Ok(ShareHoldingPercentageJs {
+        address: "".to_owned(),
+        label: format!("Others ({})", others.len()),
+        address_browser_link: "".to_owned(),
+        amount: amount.to_string(),
+        percentage_formatted: format_percentage(percentage, locale)?,
+        percentage_number: percentage.to_string(),
+        type_: "others".to_owned(),
+        locked: split_sum(|s| s.locked)?,
+        free: split_sum(|s| s.free)?,
+    })
+}
+
+/// `holders` are expected to be sorted by amount, largest first
+fn concentration_metrics(
+    holders: &[ShareHoldingPercentage],
+    supply: u64,
+    locale: &UserLocale,
+) -> Result<ConcentrationJs> {
+    let largest_percentage = holders.first().map_or(Decimal::ZERO, |h| h.percentage);
+    let gini = gini_coefficient(holders)?;
+
+    // more than half of the supply
+    let majority = Decimal::from(supply) / Decimal::from(2);
+    let mut accumulated = Decimal::ZERO;
+    let mut holders_for_majority = None;
+    for (index, h) in holders.iter().enumerate() {
+        accumulated += Decimal::from(h.amount.val());
+        if accumulated > majority {
+            holders_for_majority = Some(index + 1);
+            break;
+        }
+    }
+
+    Ok(ConcentrationJs {
+        holders_count: holders.len().to_string(),
+        largest_holder_percentage_formatted: format_percentage(largest_percentage, locale)?,
+        largest_holder_percentage_number: largest_percentage.to_string(),
+        gini_formatted: format_display_units(gini, locale),
+        gini_number: gini.to_string(),
+        holders_for_majority: holders_for_majority.map(|n| n.to_string()),
+    })
+}
+
+/// With the amounts sorted ascendingly (x_1..x_n): G = 2 * Σ(i * x_i) / (n * Σx_i) - (n + 1) / n
+fn gini_coefficient(holders: &[ShareHoldingPercentage]) -> Result<Decimal> {
+    let mut amounts: Vec<Decimal> = holders
+        .iter()
+        .map(|h| Decimal::from(h.amount.val()))
+        .collect();
+    amounts.sort();
+
+    let n = Decimal::from(amounts.len());
+    let total: Decimal = amounts.iter().sum();
+    if total.is_zero() {
+        return Ok(Decimal::ZERO);
     }
+
+    let weighted_sum =
+        amounts
+            .iter()
+            .enumerate()
+            .try_fold(Decimal::ZERO, |sum, (index, amount)| {
+                amount
+                    .checked_mul(Decimal::from(index + 1))
+                    .and_then(|weighted| sum.checked_add(weighted))
+                    .ok_or_else(|| anyhow!("Overflow calculating gini coefficient"))
+            })?;
+
+    let gini = Decimal::from(2) * weighted_sum / (n * total) - (n + Decimal::ONE) / n;
+    Ok(gini.round_dp(4).normalize())
 }
 
 // pub: shares with mock data
 pub fn not_owned_shares_holdings(
     holders: &[ShareHoldingPercentage],
     supply: u64,
+    locale: &UserLocale,
 ) -> Result<ShareHoldingPercentageJs> {
     let total_holders_amount: u64 = holders.iter().map(|h| h.amount.val()).sum();
 
@@ -77,10 +276,12 @@ pub fn not_owned_shares_holdings(
         address: "".to_owned(),
         label: "Not owned".to_owned(),
         address_browser_link: "".to_owned(),
-        amount: format_short(not_owned_amount.as_decimal())?,
-        percentage_formatted: not_owned_percentage.format_percentage(),
+        amount: format_short(not_owned_amount.as_decimal(), locale)?,
+        percentage_formatted: format_percentage(not_owned_percentage, locale)?,
         percentage_number: not_owned_percentage.to_string(),
         type_: "not_owned".to_owned(),
+        locked: None,
+        free: None,
     })
 }
 
@@ -100,3 +301,201 @@ pub fn shorten_address(address: &Address) -> Result<String> {
         address_str[len - 3..len].to_owned()
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fake_node::{
+            fixtures::{
+                dao_chain, set_investor_shares, use_fixture_network_profile, DAO_APP_ID, INVESTOR,
+            },
+            FakeNode,
+        },
+        locale::to_user_locale,
+    };
+    use mbase::models::share_amount::ShareAmount;
+    use std::str::FromStr;
+
+    const SUPPLY: u64 = 100;
+
+    fn holding(index: u8, amount: u64) -> ShareHoldingPercentage {
+        ShareHoldingPercentage {
+            address: Address([index; 32]),
+            amount: ShareAmount::new(amount),
+            percentage: Decimal::from(amount) / Decimal::from(SUPPLY),
+        }
+    }
+
+    fn holders(amounts: &[u64]) -> Vec<ShareHoldingPercentage> {
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(index, amount)| holding(index as u8, *amount))
+            .collect()
+    }
+
+    /// Split as locked, free: half locked, rounded down
+    fn holders_with_split(amounts: &[u64]) -> Vec<HolderWithSplit> {
+        holders(amounts)
+            .into_iter()
+            .map(|holding| {
+                let locked = holding.amount.val() / 2;
+                HolderWithSplit {
+                    split: Some(SharesSplit {
+                        locked,
+                        free: holding.amount.val() - locked,
+                    }),
+                    holding,
+                }
+            })
+            .collect()
+    }
+
+    fn distribution(amounts: &[u64], top_n: Option<usize>) -> SharedDistributionResJs {
+        to_shares_distribution_js(
+            holders_with_split(amounts),
+            SUPPLY,
+            top_n,
+            &to_user_locale("en").unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn types(res: &SharedDistributionResJs) -> Vec<&str> {
+        res.holders.iter().map(|h| h.type_.as_str()).collect()
+    }
+
+    fn gini(amounts: &[u64]) -> Decimal {
+        gini_coefficient(&holders(amounts)).unwrap()
+    }
+
+    fn holders_for_majority(amounts: &[u64]) -> Option<String> {
+        concentration_metrics(&holders(amounts), SUPPLY, &to_user_locale("en").unwrap())
+            .unwrap()
+            .holders_for_majority
+    }
+
+    #[test]
+    fn to_shares_distribution_js_sorts_and_groups_after_top_n() {
+        let res = distribution(&[10, 40, 20, 5], Some(2));
+
+        assert_eq!(vec!["holder", "holder", "others", "not_owned"], types(&res));
+
+        assert_eq!("40", res.holders[0].amount);
+        assert_eq!(Some("20".to_owned()), res.holders[0].locked);
+        assert_eq!(Some("20".to_owned()), res.holders[0].free);
+        assert_eq!("20", res.holders[1].amount);
+
+        let others = &res.holders[2];
+        assert_eq!("Others (2)", others.label);
+        assert_eq!("15", others.amount);
+        assert_eq!("0.15", others.percentage_number);
+        // 10: 5 + 5, 5: 2 + 3
+        assert_eq!(Some("7".to_owned()), others.locked);
+        assert_eq!(Some("8".to_owned()), others.free);
+
+        let not_owned = &res.holders[3];
+        assert_eq!("0.25", not_owned.percentage_number);
+        assert_eq!(None, not_owned.locked);
+
+        // not affected by top_n
+        assert_eq!("4", res.concentration.holders_count);
+        assert_eq!("0.4", res.concentration.largest_holder_percentage_number);
+    }
+
+    #[test]
+    fn to_shares_distribution_js_without_top_n_shows_all_holders() {
+        let res = distribution(&[10, 40], None);
+
+        assert_eq!(vec!["holder", "holder", "not_owned"], types(&res));
+    }
+
+    #[test]
+    fn others_holdings_with_top_n_zero_contains_all_holders() {
+        let res = distribution(&[10, 40, 20], Some(0));
+
+        assert_eq!(vec!["others", "not_owned"], types(&res));
+        assert_eq!("Others (3)", res.holders[0].label);
+        assert_eq!("70", res.holders[0].amount);
+    }
+
+    #[test]
+    fn others_holdings_is_omitted_with_top_n_at_least_the_holders() {
+        assert_eq!(
+            vec!["holder", "holder", "not_owned"],
+            types(&distribution(&[10, 40], Some(2)))
+        );
+        assert_eq!(
+            vec!["holder", "holder", "not_owned"],
+            types(&distribution(&[10, 40], Some(5)))
+        );
+    }
+
+    #[test]
+    fn others_holdings_without_split_if_one_is_unknown() {
+        let mut holders = holders_with_split(&[10, 5]);
+        holders[1].split = None;
+
+        let others = others_holdings(&holders, &to_user_locale("en").unwrap()).unwrap();
+
+        assert_eq!("15", others.amount);
+        assert_eq!(None, others.locked);
+        assert_eq!(None, others.free);
+    }
+
+    #[test]
+    fn holders_for_majority_needs_more_than_half_of_the_supply() {
+        assert_eq!(Some("1".to_owned()), holders_for_majority(&[60, 40]));
+        // exactly half isn't a majority
+        assert_eq!(Some("2".to_owned()), holders_for_majority(&[50, 30, 20]));
+        assert_eq!(Some("3".to_owned()), holders_for_majority(&[30, 20, 10, 5]));
+        // the holders own half of the supply or less
+        assert_eq!(None, holders_for_majority(&[30, 20]));
+        assert_eq!(None, holders_for_majority(&[]));
+    }
+
+    #[test]
+    fn gini_coefficient_is_zero_for_equal_holdings() {
+        assert_eq!(Decimal::ZERO, gini(&[10, 10, 10, 10]));
+        assert_eq!(Decimal::ZERO, gini(&[10]));
+    }
+
+    #[test]
+    fn gini_coefficient_is_zero_without_holdings() {
+        assert_eq!(Decimal::ZERO, gini(&[]));
+    }
+
+    #[test]
+    fn gini_coefficient_of_unequal_holdings() {
+        assert_eq!(Decimal::from_str("0.25").unwrap(), gini(&[3, 1]));
+        // one holder has everything: (n - 1) / n
+        assert_eq!(Decimal::from_str("0.75").unwrap(), gini(&[0, 0, 10, 0]));
+    }
+
+    #[tokio::test]
+    async fn shares_split_derives_free_from_the_holding() {
+        let mut chain = dao_chain();
+        set_investor_shares(&mut chain, 10);
+        let _node = FakeNode::start(chain).unwrap();
+        use_fixture_network_profile();
+
+        let mut investor_holding = holding(0, 15);
+        investor_holding.address = INVESTOR.parse().unwrap();
+
+        let split = shares_split(&algod(), &investor_holding, DaoAppId(DAO_APP_ID))
+            .await
+            .unwrap();
+
+        assert_eq!(10, split.locked);
+        assert_eq!(5, split.free);
+
+        // inconsistent: the holding doesn't include the locked shares
+        investor_holding.amount = ShareAmount::new(5);
+        assert!(
+            shares_split(&algod(), &investor_holding, DaoAppId(DAO_APP_ID))
+                .await
+                .is_err()
+        );
+    }
+}
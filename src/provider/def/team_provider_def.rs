@@ -1,22 +1,28 @@
+use crate::dependencies::algod;
 use crate::error::FrError;
 use crate::inputs_validation::ValidationError;
-use crate::js::common::signed_js_tx_to_signed_tx1;
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs};
 use crate::js::to_sign_js::ToSignJs;
 use crate::provider::create_dao_provider::{
     validate_text_min_max_length, validate_url, validate_url_opt,
 };
 use crate::provider::team_provider::{
     AddTeamMemberParsJs, AddTeamMemberResJs, EditTeamMemberParsJs, EditTeamMemberResJs,
-    GetTeamParsJs, GetTeamResJs, SetTeamParsJs, SetTeamResJs, SubmitSetTeamParJs, TeamMemberInputs,
-    TeamMemberJs, TeamProvider,
+    GetTeamParsJs, GetTeamResJs, RemoveTeamMemberParsJs, RemoveTeamMemberResJs,
+    ReorderTeamMembersParsJs, ReorderTeamMembersResJs, SetTeamParsJs, SetTeamResJs,
+    SubmitSetTeamParJs, TeamMemberInputs, TeamMemberJs, TeamProvider,
 };
-use anyhow::{Error, Result};
+use crate::service::content_hash::{
+    check_content_hash, url_with_content_hash, url_without_fragment,
+};
+use crate::service::dao_cache::invalidate_after_submit;
+use crate::service::verified_content::{fetch_verified, ContentStatusJs};
+use anyhow::{anyhow, Error, Result};
 use async_trait::async_trait;
 use base::api::fetcher::Fetcher;
 use base::dependencies::fetcher;
 use base::dev_settings::{submit_dev_settings, DevSettingsSigned};
 use base::team::{team, TeamMember};
-use mbase::dependencies::algod;
 use mbase::util::network_util::wait_for_pending_transaction;
 use serde::Serialize;
 use tsify::Tsify;
@@ -28,12 +34,17 @@ pub struct TeamProviderDef {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl TeamProvider for TeamProviderDef {
     async fn get(&self, pars: GetTeamParsJs) -> Result<GetTeamResJs, FrError> {
-        let fetcher = fetcher();
-        let bytes = fetcher.get(&pars.url).await?;
+        let content = fetch_verified(&pars.url, None).await;
 
-        let team: Vec<TeamMemberJs> = serde_json::from_slice(&bytes)?;
+        let team: Vec<TeamMemberJs> = match &content.bytes {
+            Some(bytes) => serde_json::from_slice(bytes)?,
+            None => vec![],
+        };
 
-        Ok(GetTeamResJs { team })
+        Ok(GetTeamResJs {
+            team,
+            status: content.status,
+        })
     }
 
     async fn add_team_member(
@@ -50,17 +61,46 @@ impl TeamProvider for TeamProviderDef {
         edit_team_member_shared(pars).await
     }
 
+    async fn remove_team_member(
+        &self,
+        pars: RemoveTeamMemberParsJs,
+    ) -> Result<RemoveTeamMemberResJs, FrError> {
+        remove_team_member_shared(pars).await
+    }
+
+    async fn reorder_team_members(
+        &self,
+        pars: ReorderTeamMembersParsJs,
+    ) -> Result<ReorderTeamMembersResJs, FrError> {
+        reorder_team_members_shared(pars).await
+    }
+
     async fn set(&self, pars: SetTeamParsJs) -> Result<SetTeamResJs, FrError> {
         let algod = algod();
+        let fetcher = fetcher();
 
         let owner = pars.owner_address.parse().map_err(Error::msg)?;
 
         let dao_id = pars.dao_id.parse()?;
 
-        let to_sign = team(&algod, &owner, dao_id, &pars.url).await?;
+        // the team has to be valid, otherwise investors would get an error when loading it
+        let _: Vec<TeamMemberJs> = serde_json::from_str(&pars.team)?;
+
+        // check that the url serves the team, otherwise we'd save a hash that never matches
+        let url = url_with_content_hash(&pars.url, pars.team.as_bytes());
+        let uploaded = fetcher.get(url_without_fragment(&url)).await?;
+        if check_content_hash(&url, &uploaded) != ContentStatusJs::Verified {
+            return Err(anyhow!("The content at: {} isn't the team to be saved", pars.url).into());
+        }
+
+        let to_sign = team(&algod, &owner, dao_id, &url).await?;
 
         Ok(SetTeamResJs {
-            to_sign: ToSignJs::new(vec![to_sign.app_call_tx])?,
+            to_sign: ToSignJs::with_messages(vec![(
+                to_sign.app_call_tx,
+                "Save the team members".to_owned(),
+            )])
+            .await?,
         })
     }
 
@@ -73,6 +113,8 @@ impl TeamProvider for TeamProviderDef {
                 pars.txs.len()
             )));
         }
+
+        verify_signed_js_txs(&pars.txs)?;
         let tx = &pars.txs[0];
 
         let tx_id = submit_dev_settings(
@@ -87,6 +129,8 @@ impl TeamProvider for TeamProviderDef {
 
         let _ = wait_for_pending_transaction(&algod, &tx_id).await?;
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         Ok(())
     }
 }
@@ -132,6 +176,61 @@ pub async fn edit_team_member_shared(
     })
 }
 
+/// shared def / mock
+pub async fn remove_team_member_shared(
+    pars: RemoveTeamMemberParsJs,
+) -> Result<RemoveTeamMemberResJs, FrError> {
+    let mut members = pars.existing_members;
+
+    if let Some(index) = members.iter().position(|m| m.uuid == pars.uuid) {
+        members.remove(index);
+    } else {
+        return Err(FrError::Internal(
+            "Invalid state: removed team member must be in existing members".to_owned(),
+        ));
+    }
+
+    let team_to_save = serde_json::to_string(&members)?;
+
+    Ok(RemoveTeamMemberResJs {
+        team: members,
+        to_save: team_to_save,
+    })
+}
+
+/// shared def / mock
+pub async fn reorder_team_members_shared(
+    pars: ReorderTeamMembersParsJs,
+) -> Result<ReorderTeamMembersResJs, FrError> {
+    let mut existing_members = pars.existing_members;
+
+    if pars.uuids.len() != existing_members.len() {
+        return Err(FrError::Internal(format!(
+            "Invalid state: reordered uuids count: {} must be the members count: {}",
+            pars.uuids.len(),
+            existing_members.len()
+        )));
+    }
+
+    let mut members = vec![];
+    for uuid in &pars.uuids {
+        if let Some(index) = existing_members.iter().position(|m| &m.uuid == uuid) {
+            members.push(existing_members.remove(index));
+        } else {
+            return Err(FrError::Internal(format!(
+                "Invalid state: reordered team member: {uuid} must be in existing members (once)"
+            )));
+        }
+    }
+
+    let team_to_save = serde_json::to_string(&members)?;
+
+    Ok(ReorderTeamMembersResJs {
+        team: members,
+        to_save: team_to_save,
+    })
+}
+
 fn validate_team_member_inputs(
     inputs: &TeamMemberInputs,
 ) -> Result<ValidatedTeamMemberInputs, ValidateTeamMemberInputsError> {
@@ -254,3 +353,83 @@ fn validate_linkedin_url(url: &Option<String>) -> Result<Option<String>, Validat
 fn validate_image_url(url: &Option<String>) -> Result<Option<String>, ValidationError> {
     validate_url_opt(url)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(uuid: &str) -> TeamMemberJs {
+        TeamMemberJs {
+            uuid: uuid.to_owned(),
+            name: format!("Member {uuid}"),
+            descr: "".to_owned(),
+            role: "Dev".to_owned(),
+            picture: "https://capi.finance/picture.png".to_owned(),
+            github_url: None,
+            twitter_url: None,
+            linkedin_url: None,
+        }
+    }
+
+    fn members() -> Vec<TeamMemberJs> {
+        vec![member("a"), member("b"), member("c")]
+    }
+
+    fn uuids(members: &[TeamMemberJs]) -> Vec<&str> {
+        members.iter().map(|m| m.uuid.as_str()).collect()
+    }
+
+    async fn reorder(new_order: &[&str]) -> Result<ReorderTeamMembersResJs, FrError> {
+        reorder_team_members_shared(ReorderTeamMembersParsJs {
+            uuids: new_order.iter().map(|u| u.to_string()).collect(),
+            existing_members: members(),
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn remove_team_member_removes_the_member() {
+        let res = remove_team_member_shared(RemoveTeamMemberParsJs {
+            uuid: "b".to_owned(),
+            existing_members: members(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(vec!["a", "c"], uuids(&res.team));
+        let saved: Vec<TeamMemberJs> = serde_json::from_str(&res.to_save).unwrap();
+        assert_eq!(vec!["a", "c"], uuids(&saved));
+    }
+
+    #[tokio::test]
+    async fn remove_team_member_fails_if_the_member_isnt_in_the_team() {
+        let res = remove_team_member_shared(RemoveTeamMemberParsJs {
+            uuid: "x".to_owned(),
+            existing_members: members(),
+        })
+        .await;
+
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn reorder_team_members_reorders_the_members() {
+        let res = reorder(&["c", "a", "b"]).await.unwrap();
+
+        assert_eq!(vec!["c", "a", "b"], uuids(&res.team));
+        let saved: Vec<TeamMemberJs> = serde_json::from_str(&res.to_save).unwrap();
+        assert_eq!(vec!["c", "a", "b"], uuids(&saved));
+    }
+
+    #[tokio::test]
+    async fn reorder_team_members_fails_with_a_duplicate() {
+        // same count, but "c" would be lost
+        assert!(reorder(&["a", "b", "a"]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reorder_team_members_fails_with_a_missing_or_unknown_member() {
+        assert!(reorder(&["a", "b"]).await.is_err());
+        assert!(reorder(&["a", "b", "x"]).await.is_err());
+    }
+}
@@ -1,15 +1,14 @@
+use crate::dependencies::algod;
 use crate::error::FrError;
-use crate::js::common::signed_js_tx_to_signed_tx1;
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs};
 use crate::js::to_sign_js::ToSignJs;
 use crate::provider::unlock_provider::{
     SubmitUnlockParJs, SubmitUnlockResJs, UnlockParJs, UnlockProvider, UnlockResJs,
 };
+use crate::service::dao_cache::{dao_investor_state, invalidate_after_submit, load_dao};
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use base::flows::create_dao::storage::load_dao::load_dao;
 use base::flows::unlock::unlock::{submit_unlock, unlock, UnlockSigned};
-use mbase::dependencies::algod;
-use mbase::state::dao_app_state::dao_investor_state;
 use mbase::util::network_util::wait_for_pending_transaction;
 
 pub struct UnlockProviderDef {}
@@ -30,10 +29,13 @@ impl UnlockProvider for UnlockProviderDef {
 
         let to_sign = unlock(&algod, investor_address, dao.app_id, dao.shares_asset_id).await?;
 
-        let to_sign_txs = vec![to_sign.central_app_optout_tx];
+        let to_sign_txs = vec![(
+            to_sign.central_app_optout_tx,
+            format!("Unlock {} shares of {}", investor_state.shares, dao.name),
+        )];
 
         Ok(UnlockResJs {
-            to_sign: ToSignJs::new(to_sign_txs)?,
+            to_sign: ToSignJs::with_messages(to_sign_txs).await?,
         })
     }
 
@@ -46,6 +48,8 @@ impl UnlockProvider for UnlockProviderDef {
                 pars.txs.len()
             )));
         }
+
+        verify_signed_js_txs(&pars.txs)?;
         let app_call_tx = &pars.txs[0];
 
         let tx_id = submit_unlock(
@@ -60,6 +64,8 @@ impl UnlockProvider for UnlockProviderDef {
 
         let _ = wait_for_pending_transaction(&algod, &tx_id).await?;
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         Ok(SubmitUnlockResJs {})
     }
 }
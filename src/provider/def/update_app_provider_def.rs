@@ -1,24 +1,23 @@
-use crate::dependencies::capi_deps;
+use crate::dependencies::{algod, capi_deps};
 use crate::error::FrError;
-use crate::js::common::signed_js_tx_to_signed_tx1;
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs};
 use crate::js::to_sign_js::ToSignJs;
 use crate::provider::update_app_provider::{
     SubmitUpdateAppParJs, SubmitUpdateAppResJs, UpdateAppProvider, UpdateDaoAppParJs,
     UpdateDaoAppResJs,
 };
 use crate::service::constants::{MAX_RAISABLE_AMOUNT, PRECISION};
+use crate::service::dao_cache::{invalidate_after_submit, load_dao};
 use anyhow::{Error, Result};
 use async_trait::async_trait;
 use base::dependencies::teal_api;
 use base::flows::create_dao::setup::create_app::{
     render_and_compile_app_approval, render_and_compile_app_clear,
 };
-use base::flows::create_dao::storage::load_dao::load_dao;
 use base::flows::update_app::update::{submit_update, update, UpdateAppSigned};
 use base::teal::TealApi;
 use mbase::api::contract::Contract;
 use mbase::api::version::Version;
-use mbase::dependencies::algod;
 use mbase::models::funds::FundsAmount;
 
 pub struct UpdateAppProviderDef {}
@@ -70,7 +69,11 @@ impl UpdateAppProvider for UpdateAppProviderDef {
         let to_sign = update(&algod, &owner, dao_id.0, app_source, clear_source).await?;
 
         Ok(UpdateDaoAppResJs {
-            to_sign: ToSignJs::new(vec![to_sign.update])?,
+            to_sign: ToSignJs::with_messages(vec![(
+                to_sign.update,
+                format!("Update the app of {}", dao.name),
+            )])
+            .await?,
         })
     }
 
@@ -83,6 +86,8 @@ impl UpdateAppProvider for UpdateAppProviderDef {
                 pars.txs.len()
             )));
         }
+
+        verify_signed_js_txs(&pars.txs)?;
         let tx = &pars.txs[0];
 
         let submit_update_res = submit_update(
@@ -95,6 +100,8 @@ impl UpdateAppProvider for UpdateAppProviderDef {
 
         log::debug!("Submit update res: {:?}", submit_update_res);
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         Ok(SubmitUpdateAppResJs {})
     }
 }
@@ -1,6 +1,7 @@
+use crate::dependencies::algod;
 use crate::error::FrError;
 use crate::inputs_validation::ValidationError;
-use crate::js::common::signed_js_tx_to_signed_tx1;
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs};
 use crate::js::to_sign_js::ToSignJs;
 use crate::provider::create_dao_provider::{
     validate_dao_description_url_opt, validate_dao_name, validate_image_url,
@@ -11,18 +12,21 @@ use crate::provider::update_data_provider::{
     SubmitUpdateDataParJs, UpdatableDataParJs, UpdatableDataResJs, UpdateDataParJs,
     UpdateDataPassthroughJs, UpdateDataProvider, UpdateDataResJs,
 };
+use crate::service::dao_cache::{dao_global_state, invalidate_after_submit, load_dao};
+use crate::service::passthrough::Passthrough;
+use crate::service::verified_content::{
+    fetch_verified_image_opt, fetch_verified_opt, image_hash_if_changed,
+    url_with_fetched_content_hash_if_changed, verify_opt,
+};
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use base::api::fetcher::Fetcher;
-use base::dependencies::fetcher;
-use base::flows::create_dao::storage::load_dao::load_dao;
 use base::flows::update_data::update_data::{
     submit_update_data, update_data, UpdatableDaoData, UpdateDaoDataSigned,
 };
 use data_encoding::BASE64;
-use mbase::dependencies::algod;
+use futures::join;
 use mbase::models::dao_id::DaoId;
-use mbase::state::dao_app_state::{dao_global_state, Prospectus};
+use mbase::state::dao_app_state::Prospectus;
 use serde::Serialize;
 use tsify::Tsify;
 
@@ -33,7 +37,6 @@ pub struct UpdateDataProviderDef {}
 impl UpdateDataProvider for UpdateDataProviderDef {
     async fn get(&self, pars: UpdatableDataParJs) -> Result<UpdatableDataResJs, FrError> {
         let algod = algod();
-        let fetcher = fetcher();
 
         let dao_id = pars.dao_id.parse::<DaoId>().map_err(Error::msg)?;
 
@@ -42,29 +45,41 @@ impl UpdateDataProvider for UpdateDataProviderDef {
         let dao = load_dao(&algod, dao_id).await?;
 
         // TODO optimize: fetch description separately, DaoJs has just url
-        let description = match dao.descr_url {
-            Some(descr) => Some(String::from_utf8(fetcher.get(&descr).await?)?),
-            None => None,
-        };
+        let (description, image, prospectus_status) = join!(
+            fetch_verified_opt(dao.descr_url.as_deref()),
+            fetch_verified_image_opt(
+                &algod,
+                dao.image_nft
+                    .as_ref()
+                    .map(|nft| (nft.asset_id, nft.url.as_str()))
+            ),
+            verify_opt(
+                app_state.prospectus.as_ref().map(|p| p.url.as_str()),
+                app_state.prospectus.as_ref().map(|p| p.hash.as_str())
+            ),
+        );
 
-        let image_base64 = match dao.image_nft {
-            Some(nft) => {
-                let bytes = fetcher.get(&nft.url).await?;
-                let base64 = BASE64.encode(&bytes);
-                Some(base64)
-            }
+        let project_desc = match description.as_ref().and_then(|d| d.bytes.clone()) {
+            Some(bytes) => Some(String::from_utf8(bytes)?),
             None => None,
         };
+        let image_base64 = image
+            .as_ref()
+            .and_then(|i| i.bytes.as_ref())
+            .map(|bytes| BASE64.encode(bytes));
 
         Ok(UpdatableDataResJs {
             project_name: app_state.project_name,
-            project_desc: description,
+            project_desc,
+            project_desc_status: description.map(|d| d.status),
             share_price: app_state.share_price.to_string(),
 
             image_base64,
+            image_status: image.map(|i| i.status),
             social_media_url: app_state.social_media_url,
 
             prospectus: app_state.prospectus.map(|p| p.into()),
+            prospectus_status,
             min_invest_amount: app_state.min_invest_amount.val().to_string(),
             max_invest_amount: app_state.max_invest_amount.val().to_string(),
         })
@@ -82,23 +97,59 @@ impl UpdateDataProvider for UpdateDataProviderDef {
         // and use this version to retrieve the program
         // the teal has to be updated to store the version, either in the same field as the address or a separate field with all the escrow's versions
 
-        let updatable_data = validate_inputs(pars)?;
+        let mut updatable_data = validate_inputs(pars)?;
+
+        let dao = load_dao(&algod, dao_id).await?;
+
+        // save the hashes of the content, to verify it when loading it:
+        // the description's with the url, the image's in the NFT's metadata hash.
+        // Unchanged urls keep their saved hash.
+        updatable_data.project_desc_url = url_with_fetched_content_hash_if_changed(
+            updatable_data.project_desc_url,
+            dao.descr_url.as_deref(),
+        )
+        .await?;
+        updatable_data.image_hash = image_hash_if_changed(
+            &algod,
+            updatable_data.image_url.as_deref(),
+            dao.image_nft
+                .as_ref()
+                .map(|nft| (nft.asset_id, nft.url.as_str())),
+        )
+        .await?;
+        // the hash makes the url longer, so check it again
+        validate_dao_description_url_opt(&updatable_data.project_desc_url).map_err(|e| {
+            ValidateDataUpdateInputsError::AllFieldsValidation(ValidateUpateDataInputErrors {
+                description: Some(e),
+                ..ValidateUpateDataInputErrors::default()
+            })
+        })?;
+
         let to_sign = update_data(&algod, &owner, dao_id.0, &updatable_data).await?;
 
         let mut txs = vec![to_sign.update];
+        let mut messages = vec![format!(
+            "Update the data of {}",
+            updatable_data.project_name
+        )];
         if let Some(pay) = to_sign.increase_min_balance_tx {
             txs.push(pay);
+            messages.push("Pay the app's min balance increase for the new data".to_owned());
         }
 
         Ok(UpdateDataResJs {
-            to_sign: ToSignJs::new(txs)?,
+            to_sign: ToSignJs::with_messages(txs.iter().cloned().zip(messages).collect()).await?,
             pt: UpdateDataPassthroughJs {
                 dao_id: dao_id.to_string(),
-            },
+                mac: String::new(),
+            }
+            .signed(&txs)?,
         })
     }
 
     async fn submit(&self, pars: SubmitUpdateDataParJs) -> Result<(), FrError> {
+        pars.pt.verify(&pars.txs)?;
+
         let algod = algod();
 
         if pars.txs.len() != 1 && pars.txs.len() != 2 {
@@ -107,6 +158,8 @@ impl UpdateDataProvider for UpdateDataProviderDef {
                 pars.txs.len()
             )));
         }
+
+        verify_signed_js_txs(&pars.txs)?;
         let update_tx = &pars.txs[0];
         let maybe_increase_min_balance_tx = if pars.txs.len() == 2 {
             Some(pars.txs[1].clone())
@@ -126,6 +179,8 @@ impl UpdateDataProvider for UpdateDataProviderDef {
         )
         .await?;
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         Ok(())
     }
 }
@@ -181,6 +236,8 @@ fn validate_inputs(
                 project_name: dao_name,
                 project_desc_url: dao_descr,
                 image_url,
+                // set after the validation, see [UpdateDataProviderDef::txs]
+                image_hash: None,
                 social_media_url,
                 prospectus,
                 min_invest_shares,
@@ -244,7 +301,7 @@ fn to_maybe_prospectus(
     })
 }
 
-#[derive(Tsify, Debug, Clone, Serialize)]
+#[derive(Tsify, Debug, Clone, Serialize, Default)]
 #[tsify(into_wasm_abi)]
 pub struct ValidateUpateDataInputErrors {
     pub name: Option<ValidationError>,
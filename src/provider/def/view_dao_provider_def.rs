@@ -1,16 +1,19 @@
-use crate::dependencies::{capi_deps, funds_asset_specs};
+use crate::dependencies::{algod, capi_deps};
 use crate::error::FrError;
+use crate::locale::locale;
 use crate::model::dao_js::ToDaoJs;
 use crate::provider::view_dao_provider::{ViewDaoParJs, ViewDaoProvider, ViewDaoResJs};
 use crate::service::available_funds::owned_funds;
-use crate::service::number_formats::base_units_to_display_units_readable;
+use crate::service::dao_cache::{dao_global_state, load_dao};
+use crate::service::funds_asset_specs::funds_asset_specs_for;
+use crate::service::number_formats::{base_units_to_display_units_readable, format_percentage};
+use crate::service::price_source::fiat_converter;
 use algonaut::core::MicroAlgos;
 use algonaut::transaction::url::LinkableTransactionBuilder;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Error, Result};
 use async_trait::async_trait;
-use base::flows::create_dao::storage::load_dao::load_dao;
-use mbase::dependencies::algod;
-use mbase::util::decimal_util::DecimalExt;
+use futures::{try_join, TryFutureExt};
+use mbase::models::dao_id::DaoId;
 
 pub struct ViewDaoProviderDef {}
 
@@ -18,13 +21,16 @@ pub struct ViewDaoProviderDef {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl ViewDaoProvider for ViewDaoProviderDef {
     async fn get(&self, pars: ViewDaoParJs) -> Result<ViewDaoResJs, FrError> {
+        let locale = locale();
         let algod = algod();
-        let funds_asset_specs = funds_asset_specs()?;
         let capi_deps = capi_deps()?;
 
-        let dao_id = pars.dao_id.parse()?;
+        let dao_id: DaoId = pars.dao_id.parse()?;
 
-        let dao = load_dao(&algod, dao_id).await?;
+        // the global state is fetched once here and passed to the owned funds.
+        // base's load_dao reads it on its own, but its result is cached too.
+        let (dao, dao_state) =
+            try_join!(load_dao(&algod, dao_id), dao_global_state(&algod, dao_id.0))?;
 
         // TODO investor count: get all holders of asset (indexer?)
 
@@ -33,14 +39,16 @@ impl ViewDaoProvider for ViewDaoProviderDef {
                 .build()
                 .as_url();
 
-        // TODO optimize: we're fetching the global state here again (it's also fetched to create the dao)
-        // maybe add available funds field to dao? or retrieve the global state first and create dao and this with it?
-        let owned_funds = owned_funds(&algod, &dao, funds_asset_specs.id, &capi_deps).await?;
+        let (funds_asset_specs, owned_funds, app_account) = try_join!(
+            funds_asset_specs_for(&algod, dao.funds_asset_id),
+            owned_funds(&algod, &dao, &dao_state, dao.funds_asset_id, &capi_deps),
+            algod
+                .account_information(&dao.app_address())
+                .map_err(Error::from),
+        )?;
 
         // TODO!! not-locked shares (use global function to get not-locked (name prob. "available" shares))
-        let shares_available = algod
-            .account_information(&dao.app_address())
-            .await?
+        let shares_available = app_account
             .assets
             .iter()
             .find(|a| a.asset_id == dao.shares_asset_id)
@@ -48,16 +56,30 @@ impl ViewDaoProvider for ViewDaoProviderDef {
                 anyhow!("Invalid app state: Investor escrow doesn't have shares asset, Please contact support.")
             })?.amount;
 
-        let investos_share_formatted = dao.investors_share.value().format_percentage();
+        let investos_share_formatted = format_percentage(dao.investors_share.value(), &locale)?;
 
         let dao_view_data = dao.to_js(&funds_asset_specs)?;
 
+        let fiat = fiat_converter(&funds_asset_specs).await;
+        let available_funds_fiat = fiat
+            .as_ref()
+            .and_then(|f| f.convert_or_log(owned_funds, &funds_asset_specs, &locale));
+        let share_price_fiat = fiat
+            .as_ref()
+            .and_then(|f| f.convert_or_log(dao.share_price, &funds_asset_specs, &locale));
+
         Ok(ViewDaoResJs {
             dao: dao_view_data,
             shares_available: shares_available.to_string(),
             investors_share: investos_share_formatted,
-            available_funds: base_units_to_display_units_readable(owned_funds, &funds_asset_specs)?,
+            available_funds: base_units_to_display_units_readable(
+                owned_funds,
+                &funds_asset_specs,
+                &locale,
+            )?,
             customer_payment_deeplink: customer_payment_deeplink.to_string(),
+            available_funds_fiat,
+            share_price_fiat,
         })
     }
 }
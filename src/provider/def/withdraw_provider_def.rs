@@ -1,3 +1,4 @@
+use crate::dependencies::algod;
 use crate::error::FrError;
 use crate::js::explorer_links::explorer_tx_id_link_env;
 use crate::js::to_sign_js::ToSignJs;
@@ -6,25 +7,32 @@ use crate::provider::withdraw_provider::{
     SubmitWithdrawResJs, WithdrawInputsPassthroughJs, WithdrawParJs, WithdrawProvider,
     WithdrawResJs,
 };
+use crate::service::dao_cache::{invalidate_after_submit, load_dao};
+use crate::service::passthrough::Passthrough;
 use crate::{
-    dependencies::FundsAssetSpecs, provider::withdrawal_history_provider::WithdrawalViewData,
-    service::number_formats::base_units_to_display_units_str,
+    dependencies::capi_deps,
+    locale::{locale, UserLocale},
+    service::{
+        drain_if_needed::{drain_if_needed_tx, drain_message},
+        funds_asset_specs::funds_asset_specs_for,
+    },
 };
 use crate::{
-    dependencies::{capi_deps, funds_asset_specs},
-    service::drain_if_needed::drain_if_needed_tx,
+    dependencies::FundsAssetSpecs,
+    provider::withdrawal_history_provider::WithdrawalViewData,
+    service::number_formats::{
+        base_units_to_display_units_readable, base_units_to_display_units_str,
+    },
 };
 use crate::{
-    js::common::signed_js_tx_to_signed_tx1, service::drain_if_needed::prepare_pars_and_submit_drain,
+    js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs},
+    service::drain_if_needed::prepare_pars_and_submit_drain,
 };
+use algonaut::transaction::Transaction;
 use anyhow::{Error, Result};
 use async_trait::async_trait;
 use base::flows::withdraw::withdraw::{submit_withdraw, WithdrawSigned};
-use base::flows::{
-    create_dao::storage::load_dao::load_dao,
-    withdraw::withdraw::{withdraw, WithdrawalInputs},
-};
-use mbase::dependencies::algod;
+use base::flows::withdraw::withdraw::{withdraw, WithdrawalInputs};
 use mbase::models::funds::FundsAmount;
 use mbase::models::tx_id::TxId;
 use mbase::util::network_util::wait_for_pending_transaction;
@@ -35,13 +43,14 @@ pub struct WithdrawProviderDef {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl WithdrawProvider for WithdrawProviderDef {
     async fn txs(&self, pars: WithdrawParJs) -> Result<WithdrawResJs, FrError> {
+        let locale = locale();
         log::debug!("_bridge_withdraw, pars: {:?}", pars);
 
         let algod = algod();
-        let funds_asset_specs = funds_asset_specs()?;
         let capi_deps = capi_deps()?;
 
         let dao = load_dao(&algod, pars.dao_id.parse()?).await?;
+        let funds_asset_specs = funds_asset_specs_for(&algod, dao.funds_asset_id).await?;
 
         let inputs_par = WithdrawInputsPassthroughJs {
             sender: pars.sender.clone(),
@@ -67,32 +76,53 @@ impl WithdrawProvider for WithdrawProviderDef {
         )
         .await?;
 
-        let mut to_sign = vec![to_sign_for_withdrawal.withdraw_tx];
+        let mut to_sign = vec![(
+            to_sign_for_withdrawal.withdraw_tx,
+            format!(
+                "Withdraw {} {} from {}",
+                base_units_to_display_units_readable(
+                    validated_inputs.amount,
+                    &funds_asset_specs,
+                    &locale
+                )?,
+                funds_asset_specs.unit_name,
+                dao.name
+            ),
+        )];
 
         let maybe_to_sign_for_drain = drain_if_needed_tx(
             &algod,
             &dao,
             &pars.sender.parse().map_err(Error::msg)?,
-            funds_asset_specs.id,
+            dao.funds_asset_id,
             &capi_deps,
         )
         .await?;
         // we append drain at the end since it's optional, so the indices of the non optional txs are fixed
         if let Some(to_sign_for_drain) = maybe_to_sign_for_drain {
-            to_sign.push(to_sign_for_drain.app_call_tx);
+            to_sign.push((to_sign_for_drain.app_call_tx, drain_message(&dao)));
         }
 
+        let txs: Vec<Transaction> = to_sign.iter().map(|(tx, _)| tx.clone()).collect();
+
         Ok(WithdrawResJs {
-            to_sign: ToSignJs::new(to_sign)?,
+            to_sign: ToSignJs::with_messages(to_sign).await?,
             pt: SubmitWithdrawPassthroughParJs {
+                dao_id: pars.dao_id.clone(),
                 inputs: inputs_par.clone(),
-            },
+                mac: String::new(),
+            }
+            .signed(&txs)?,
         })
     }
 
     async fn submit(&self, pars: SubmitWithdrawParJs) -> Result<SubmitWithdrawResJs, FrError> {
+        pars.pt.verify(&pars.txs)?;
+
         let algod = algod();
-        let funds_asset_specs = funds_asset_specs()?;
+
+        let dao = load_dao(&algod, pars.pt.dao_id.parse()?).await?;
+        let funds_asset_specs = funds_asset_specs_for(&algod, dao.funds_asset_id).await?;
 
         let withdrawal_inputs = validate_withdrawal_inputs(&pars.pt.inputs, &funds_asset_specs)?;
 
@@ -104,22 +134,23 @@ impl WithdrawProvider for WithdrawProviderDef {
             )));
         }
 
+        verify_signed_js_txs(&pars.txs)?;
+
+        // decode before submitting the drain, so we don't submit anything if the withdrawal tx is invalid
+        let withdraw_tx = signed_js_tx_to_signed_tx1(&pars.txs[0])?;
+
         if pars.txs.len() == 2 {
             prepare_pars_and_submit_drain(&algod, &pars.txs[1]).await?;
         }
 
-        let withdraw_tx_id = submit_withdraw(
-            &algod,
-            &WithdrawSigned {
-                withdraw_tx: signed_js_tx_to_signed_tx1(&pars.txs[0])?,
-            },
-        )
-        .await?;
+        let withdraw_tx_id = submit_withdraw(&algod, &WithdrawSigned { withdraw_tx }).await?;
 
         log::debug!("Submit withdrawal tx id: {:?}", withdraw_tx_id);
 
         let _ = wait_for_pending_transaction(&algod, &withdraw_tx_id).await?;
 
+        invalidate_after_submit(&algod, &pars.txs).await;
+
         Ok(SubmitWithdrawResJs {
             saved_withdrawal: withdrawal_view_data(
                 withdrawal_inputs.amount,
@@ -127,6 +158,7 @@ impl WithdrawProvider for WithdrawProviderDef {
                 withdrawal_inputs.description,
                 "Just now".to_owned(),
                 withdraw_tx_id,
+                &locale(),
             ),
         })
     }
@@ -138,9 +170,10 @@ pub fn withdrawal_view_data(
     description: String,
     date_str: String,
     tx_id: TxId,
+    locale: &UserLocale,
 ) -> WithdrawalViewData {
     WithdrawalViewData {
-        amount: base_units_to_display_units_str(amount, funds_asset_specs),
+        amount: base_units_to_display_units_str(amount, funds_asset_specs, locale),
         description,
         date: date_str,
         tx_id: tx_id.to_string(),
@@ -148,3 +181,69 @@ pub fn withdrawal_view_data(
         amount_not_formatted: amount.to_string(), // microalgos
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_node::{
+        fixtures::{
+            dao_chain, signed_js_txs, to_sign_txs, use_fixture_network_profile, CREATOR, DAO_APP_ID,
+        },
+        FakeNode,
+    };
+    use algonaut::{core::Address, transaction::transaction::TransactionType};
+
+    fn withdraw_pars(amount: &str) -> WithdrawParJs {
+        WithdrawParJs {
+            dao_id: DAO_APP_ID.to_string(),
+            sender: CREATOR.to_owned(),
+            withdrawal_amount: amount.to_owned(),
+            description: "Office rent".to_owned(),
+        }
+    }
+
+    #[tokio::test]
+    async fn txs_returns_withdrawal_and_passthrough_with_inputs() {
+        let _node = FakeNode::start(dao_chain()).unwrap();
+        use_fixture_network_profile();
+
+        let res = WithdrawProviderDef {}
+            .txs(withdraw_pars("12.5"))
+            .await
+            .unwrap();
+
+        let txs = to_sign_txs(&res.to_sign);
+        assert_eq!(1, txs.len());
+        let creator: Address = CREATOR.parse().unwrap();
+        match &txs[0].txn_type {
+            TransactionType::ApplicationCallTransaction(call) => {
+                assert_eq!(creator, call.sender);
+                assert_eq!(Some(DAO_APP_ID), call.app_id);
+                // 12.5 with the funds asset's 6 decimals
+                let amount_arg = 12_500_000u64.to_be_bytes().to_vec();
+                assert!(call
+                    .app_arguments
+                    .iter()
+                    .flatten()
+                    .any(|a| a == &amount_arg));
+            }
+            other => panic!("Expected app call, got: {other:?}"),
+        }
+        assert_eq!(Some(b"Office rent".to_vec()), txs[0].note);
+
+        assert_eq!("12.5", res.pt.inputs.withdrawal_amount);
+        assert_eq!("Office rent", res.pt.inputs.description);
+        // the passthrough is handed back to submit with the signed txs: it has to be signed for them
+        assert!(res.pt.verify(&signed_js_txs(&txs)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn txs_rejects_an_invalid_amount() {
+        let _node = FakeNode::start(dao_chain()).unwrap();
+        use_fixture_network_profile();
+
+        let res = WithdrawProviderDef {}.txs(withdraw_pars("abc")).await;
+
+        assert!(res.is_err());
+    }
+}
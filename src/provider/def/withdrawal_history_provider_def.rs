@@ -1,18 +1,18 @@
+use crate::service::dao_cache::dao_global_state;
 use crate::{
-    dependencies::{funds_asset_specs, FundsAssetSpecs},
+    dependencies::{algod, indexer, FundsAssetSpecs},
     error::FrError,
+    locale::locale,
     provider::withdrawal_history_provider::{
         LoadWithdrawalParJs, LoadWithdrawalResJs, WithdrawalHistoryProvider, WithdrawalViewData,
     },
+    service::funds_asset_specs::funds_asset_specs_for,
 };
 use algonaut::{algod::v2::Algod, indexer::v2::Indexer};
 use anyhow::Result;
 use async_trait::async_trait;
 use base::flows::withdraw::withdrawals::withdrawals;
-use mbase::{
-    dependencies::{algod, indexer},
-    models::dao_id::DaoId,
-};
+use mbase::models::dao_id::DaoId;
 
 use super::withdraw_provider_def::withdrawal_view_data;
 
@@ -25,9 +25,12 @@ impl WithdrawalHistoryProvider for WithdrawalHistoryProviderDef {
         let algod = algod();
         let indexer = indexer();
 
-        let dao_id = pars.dao_id.parse()?;
+        let dao_id: DaoId = pars.dao_id.parse()?;
+
+        let dao_state = dao_global_state(&algod, dao_id.0).await?;
+        let funds_asset_specs = funds_asset_specs_for(&algod, dao_state.funds_asset_id).await?;
 
-        let entries = load_withdrawals(&algod, &indexer, &funds_asset_specs()?, dao_id).await?;
+        let entries = load_withdrawals(&algod, &indexer, &funds_asset_specs, dao_id).await?;
 
         Ok(LoadWithdrawalResJs { entries })
     }
@@ -39,6 +42,7 @@ pub async fn load_withdrawals(
     funds_asset_specs: &FundsAssetSpecs,
     dao_id: DaoId,
 ) -> Result<Vec<WithdrawalViewData>, FrError> {
+    let locale = locale();
     let entries = withdrawals(algod, indexer, dao_id, funds_asset_specs.id, &None, &None).await?;
     let mut reqs_view_data = vec![];
     for entry in entries {
@@ -46,8 +50,9 @@ pub async fn load_withdrawals(
             entry.amount,
             funds_asset_specs,
             entry.description,
-            entry.date.to_rfc2822(),
+            locale.format_date_time(&entry.date),
             entry.tx_id,
+            &locale,
         ));
     }
     Ok(reqs_view_data)
@@ -1,19 +1,29 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::Serialize;
+use tsify::Tsify;
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::{error::FrError, js::bridge::log_wrap_new};
+use crate::{error::FrError, js::bridge::log_wrap_new, service::verified_content::ContentStatusJs};
 
 use super::providers;
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 pub trait DescriptionProvider {
-    async fn get(&self, id: String) -> Result<String, FrError>;
+    async fn get(&self, id: String) -> Result<DescriptionResJs, FrError>;
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct DescriptionResJs {
+    /// None if the description changed or is unreachable (see status)
+    pub text: Option<String>,
+    pub status: ContentStatusJs,
 }
 
 #[wasm_bindgen]
-pub async fn description(pars: String) -> Result<String, FrError> {
+pub async fn description(pars: String) -> Result<DescriptionResJs, FrError> {
     log_wrap_new("description", pars, async move |pars| {
         providers()?.description.get(pars).await
     })
@@ -1,6 +1,7 @@
 use crate::{
     error::FrError,
     js::{bridge::log_wrap_new, common::SignedTxFromJs, to_sign_js::ToSignJs},
+    service::passthrough::Passthrough,
 };
 use anyhow::Result;
 use async_trait::async_trait;
@@ -46,6 +47,16 @@ pub struct SubmitDrainParJs {
 #[tsify(from_wasm_abi, into_wasm_abi)]
 pub struct SubmitDrainPassthroughParJs {
     pub dao_id: String,
+    /// See [crate::service::passthrough]
+    pub mac: String,
+}
+
+impl Passthrough for SubmitDrainPassthroughParJs {
+    const OPERATION: &'static str = "drain";
+
+    fn mac_mut(&mut self) -> &mut String {
+        &mut self.mac
+    }
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
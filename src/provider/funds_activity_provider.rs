@@ -4,7 +4,15 @@ use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::{error::FrError, js::bridge::log_wrap_new};
+use crate::{
+    error::FrError,
+    inputs_validation::ValidationError,
+    js::bridge::log_wrap_new,
+    service::{
+        export::{to_csv, ExportFormat, ExportResJs},
+        price_source::FiatAmountJs,
+    },
+};
 
 use super::providers;
 
@@ -12,24 +20,59 @@ use super::providers;
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 pub trait FundsActivityProvider {
     async fn get(&self, pars: LoadFundsActivityParJs) -> Result<LoadFundsActivityResJs, FrError>;
+
+    /// The complete history (oldest first), unformatted, for accounting
+    async fn export(&self, pars: ExportFundsActivityParJs) -> Result<ExportResJs, FrError>;
 }
 
 #[derive(Tsify, Debug, Clone, Deserialize)]
 #[tsify(from_wasm_abi)]
 pub struct LoadFundsActivityParJs {
     pub dao_id: String,
+    /// Page size, at least 1. None: [DEFAULT_FUNDS_ACTIVITY_PAGE_SIZE]. Capped to [MAX_FUNDS_ACTIVITY_PAGE_SIZE].
     pub max_results: Option<String>,
+    /// The `next` of the previous page. None: first page.
+    pub cursor: Option<String>,
+    pub filter: Option<FundsActivityFilterJs>,
+}
+
+pub const DEFAULT_FUNDS_ACTIVITY_PAGE_SIZE: u64 = 50;
+/// Each page entry can need several indexer requests (e.g. for the inner txs), so the page size is limited
+pub const MAX_FUNDS_ACTIVITY_PAGE_SIZE: u64 = 500;
+
+#[derive(Tsify, Debug, Clone, Default, Deserialize)]
+pub struct FundsActivityFilterJs {
+    #[serde(rename = "type")]
+    pub type_: Option<FundsActivityTypeJs>,
+    /// RFC 3339 date, inclusive
+    pub after: Option<String>,
+    /// RFC 3339 date, exclusive
+    pub before: Option<String>,
+    /// The sender of income / receiver of spending
+    pub counterparty: Option<String>,
+    /// In the funds asset's display units
+    pub min_amount: Option<String>,
+}
+
+#[derive(Tsify, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FundsActivityTypeJs {
+    Income,
+    Spending,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
 #[tsify(into_wasm_abi)]
 pub struct LoadFundsActivityResJs {
     pub entries: Vec<FundsActivityViewData>,
+    /// Pass as `cursor` to load the next page. None if there are no more entries.
+    /// Note that a page can have less than `max_results` entries (also 0) and still have a next page.
+    pub next: Option<String>,
 }
 
 unsafe impl Send for LoadFundsActivityResJs {}
 
-#[derive(Tsify, Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Tsify, Debug, Clone, Serialize, PartialEq)]
 #[tsify(into_wasm_abi)]
 pub struct FundsActivityViewData {
     pub amount: String,
@@ -45,6 +88,114 @@ pub struct FundsActivityViewData {
     pub tx_id: String,
     pub tx_link: String,
     pub address: String,
+    /// Fiat equivalents in the user's selected currency, if any (current price, not the price at the tx's date)
+    pub amount_fiat: Option<FiatAmountJs>,
+    pub amount_without_fee_fiat: Option<FiatAmountJs>,
+}
+
+#[derive(Tsify, Debug, Clone, Deserialize)]
+#[tsify(from_wasm_abi)]
+pub struct ExportFundsActivityParJs {
+    pub dao_id: String,
+    pub format: ExportFormat,
+}
+
+/// Unlike [FundsActivityViewData], not shortened or formatted for display
+#[derive(Debug, Clone, Serialize)]
+pub struct FundsActivityExportEntry {
+    /// "income" / "spending"
+    pub type_: String,
+    /// RFC 3339
+    pub date: String,
+    pub amount_base_units: String,
+    pub amount: String,
+    pub fee_base_units: String,
+    pub fee: String,
+    /// Amount - fee
+    pub net_amount_base_units: String,
+    pub net_amount: String,
+    pub funds_asset_id: String,
+    pub funds_asset_unit: String,
+    pub description: String,
+    pub address: String,
+    pub tx_id: String,
+    pub tx_link: String,
+}
+
+impl FundsActivityExportEntry {
+    const CSV_HEADERS: [&'static str; 14] = [
+        "type",
+        "date",
+        "amount_base_units",
+        "amount",
+        "fee_base_units",
+        "fee",
+        "net_amount_base_units",
+        "net_amount",
+        "funds_asset_id",
+        "funds_asset_unit",
+        "description",
+        "address",
+        "tx_id",
+        "tx_link",
+    ];
+
+    fn csv_row(&self) -> Vec<String> {
+        vec![
+            self.type_.clone(),
+            self.date.clone(),
+            self.amount_base_units.clone(),
+            self.amount.clone(),
+            self.fee_base_units.clone(),
+            self.fee.clone(),
+            self.net_amount_base_units.clone(),
+            self.net_amount.clone(),
+            self.funds_asset_id.clone(),
+            self.funds_asset_unit.clone(),
+            self.description.clone(),
+            self.address.clone(),
+            self.tx_id.clone(),
+            self.tx_link.clone(),
+        ]
+    }
+}
+
+/// Shared by the providers, so the def and mock exports have the same format
+pub fn funds_activity_export_file(
+    format: ExportFormat,
+    dao_id: &str,
+    entries: &[FundsActivityExportEntry],
+) -> Result<ExportResJs> {
+    let content = match format {
+        ExportFormat::Csv => to_csv(
+            &FundsActivityExportEntry::CSV_HEADERS,
+            &entries.iter().map(|e| e.csv_row()).collect::<Vec<_>>(),
+        ),
+        ExportFormat::Json => serde_json::to_string_pretty(entries)?,
+    };
+    Ok(ExportResJs::new(
+        format,
+        &format!("funds_activity_{dao_id}"),
+        content,
+    ))
+}
+
+/// At least 1, capped to [MAX_FUNDS_ACTIVITY_PAGE_SIZE]
+pub fn funds_activity_page_size(max_results: Option<&str>) -> Result<u64, ValidationError> {
+    match max_results {
+        Some(max_results) => {
+            let size: u64 = max_results
+                .parse()
+                .map_err(|_| ValidationError::NotAnInteger)?;
+            if size < 1 {
+                return Err(ValidationError::Min {
+                    min: "1".to_owned(),
+                });
+            }
+            Ok(size.min(MAX_FUNDS_ACTIVITY_PAGE_SIZE))
+        }
+        None => Ok(DEFAULT_FUNDS_ACTIVITY_PAGE_SIZE),
+    }
 }
 
 #[wasm_bindgen(js_name=loadFundsActivity)]
@@ -56,3 +207,37 @@ pub async fn load_funds_activity(
     })
     .await
 }
+
+#[wasm_bindgen(js_name=exportFundsActivity)]
+pub async fn export_funds_activity(pars: ExportFundsActivityParJs) -> Result<ExportResJs, FrError> {
+    log_wrap_new("export_funds_activity", pars, async move |pars| {
+        providers()?.funds_activity.export(pars).await
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_size_defaults_validates_and_caps() {
+        assert_eq!(
+            DEFAULT_FUNDS_ACTIVITY_PAGE_SIZE,
+            funds_activity_page_size(None).unwrap()
+        );
+        assert_eq!(1, funds_activity_page_size(Some("1")).unwrap());
+        assert_eq!(
+            MAX_FUNDS_ACTIVITY_PAGE_SIZE,
+            funds_activity_page_size(Some("1000000")).unwrap()
+        );
+        assert!(matches!(
+            funds_activity_page_size(Some("0")),
+            Err(ValidationError::Min { .. })
+        ));
+        assert!(matches!(
+            funds_activity_page_size(Some("-1")),
+            Err(ValidationError::NotAnInteger)
+        ));
+    }
+}
@@ -1,22 +1,29 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use tsify::Tsify;
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::{error::FrError, js::bridge::log_wrap_new, model::QuantityChangeJs};
+use crate::{
+    error::FrError, js::bridge::log_wrap_new, model::QuantityChangeJs,
+    provider::income_vs_spending_provider::ChartBucketJs,
+};
 
 use super::providers;
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 pub trait HoldersCountProvider {
+    /// Current holders count (free or locked shares), from the current holdings
     async fn get(&self, pars: HoldersCountParJs) -> Result<HoldersCountResJs, FrError>;
 
-    /// returns "up"/"down"/"eq" if holders went up/down/staid the same, and "unknown" if nothing to compare with
-    /// note that here we use local storage (this is currently inconsistent with other places with "change"),
-    /// thus "unknown" if there's no older entry to compare with.
+    /// returns "up"/"down"/"eq" if holders went up/down/staid the same since the given date,
+    /// with the counts reconstructed from the chain (see [crate::service::holders_history])
     async fn change(&self, pars: HoldersChangeParJs) -> Result<HoldersChangeResJs, FrError>;
+
+    /// Holders count at the end of each bucket, for charts
+    async fn history(&self, pars: HoldersHistoryParJs) -> Result<HoldersHistoryResJs, FrError>;
 }
 
 // TODO use dao_id (convention?), in HoldersChangeParJs too
@@ -37,14 +44,69 @@ pub struct HoldersCountResJs {
 #[derive(Tsify, Debug, Clone, Deserialize)]
 #[tsify(from_wasm_abi)]
 pub struct HoldersChangeParJs {
+    /// The DAO's shares asset
     pub asset_id: String,
     pub app_id: String,
+    /// Start of the period (RFC 3339), defaults to a day ago
+    pub since: Option<String>,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
 #[tsify(into_wasm_abi)]
 pub struct HoldersChangeResJs {
     pub change: QuantityChangeJs,
+    pub count: String,
+    /// Count at `since`
+    pub previous_count: String,
+    /// Signed, e.g. "+3", "-1", "0"
+    pub difference: String,
+}
+
+#[derive(Tsify, Debug, Clone, Deserialize)]
+#[tsify(from_wasm_abi)]
+pub struct HoldersHistoryParJs {
+    pub dao_id: String,
+    /// Preset range ending today: "days7", "months3" or "year". Ignored if `start` is set.
+    pub interval: Option<String>,
+    /// Custom range start (RFC 3339). Requires `bucket`.
+    pub start: Option<String>,
+    /// Custom range end (RFC 3339), defaults to now
+    pub end: Option<String>,
+    pub bucket: Option<ChartBucketJs>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct HoldersHistoryResJs {
+    pub points: Vec<HoldersCountPointJs>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+pub struct HoldersCountPointJs {
+    /// Bucket start
+    pub date: String,
+    /// Bucket start, milliseconds since epoch
+    pub timestamp: i64,
+    /// Holders at the end of the bucket
+    pub count: String,
+    pub count_number: u64,
+}
+
+pub fn to_quantity_change(previous: usize, current: usize) -> QuantityChangeJs {
+    match current.cmp(&previous) {
+        Ordering::Less => QuantityChangeJs::Down,
+        Ordering::Equal => QuantityChangeJs::Eq,
+        Ordering::Greater => QuantityChangeJs::Up,
+    }
+}
+
+/// e.g. "+3", "-1", "0"
+pub fn format_difference(previous: usize, current: usize) -> String {
+    match current.cmp(&previous) {
+        Ordering::Less => format!("-{}", previous - current),
+        Ordering::Equal => "0".to_owned(),
+        Ordering::Greater => format!("+{}", current - previous),
+    }
 }
 
 #[wasm_bindgen(js_name=holdersCount)]
@@ -62,3 +124,11 @@ pub async fn holders_change(pars: HoldersChangeParJs) -> Result<HoldersChangeRes
     })
     .await
 }
+
+#[wasm_bindgen(js_name=holdersHistory)]
+pub async fn holders_history(pars: HoldersHistoryParJs) -> Result<HoldersHistoryResJs, FrError> {
+    log_wrap_new("holders_history", pars, async move |pars| {
+        providers()?.holders_count.history(pars).await
+    })
+    .await
+}
@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
 use mbase::date_util::DateTimeExt;
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
@@ -20,51 +20,212 @@ pub trait IncomeVsSpendingProvider {
 #[tsify(from_wasm_abi)]
 pub struct IncomeVsSpendingParJs {
     pub dao_id: String,
-    pub interval: String,
+    /// Preset range ending today: "days7", "months3" or "year". Ignored if `start` is set.
+    pub interval: Option<String>,
+    /// Custom range start (RFC 3339). Requires `bucket`.
+    pub start: Option<String>,
+    /// Custom range end (RFC 3339), defaults to now
+    pub end: Option<String>,
+    pub bucket: Option<ChartBucketJs>,
+    /// Include cumulative income and spending
+    pub cumulative: Option<bool>,
+    /// Include the net balance: cumulative income - cumulative spending, accumulated from the start of the range.
+    /// It's not the DAO's balance: income and spending before the range aren't included.
+    pub net_balance: Option<bool>,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
 #[tsify(into_wasm_abi)]
 pub struct IncomeVsSpendingResJs {
     pub points: Vec<ChartDataPointJs>,
+    /// Currency of the points' fiat values, None if the user didn't select one (or the price isn't available)
+    pub fiat_currency: Option<String>,
 }
 
+/// A bucket (x axis tick). The `*_number` fields have the same values as the strings, for the chart library.
+/// Cumulative values and net balance are accumulated from the start of the range and only set if requested.
 #[derive(Debug, Clone, Serialize)]
 pub struct ChartDataPointJs {
+    /// Bucket start
     pub date: String,
+    /// Bucket start, milliseconds since epoch
+    pub timestamp: i64,
     pub income: String,
+    pub income_number: f64,
     pub spending: String,
+    pub spending_number: f64,
+    pub cumulative_income: Option<String>,
+    pub cumulative_income_number: Option<f64>,
+    pub cumulative_spending: Option<String>,
+    pub cumulative_spending_number: Option<f64>,
+    /// Cumulative from the start of the range (see [IncomeVsSpendingParJs::net_balance]). Can be negative.
+    pub net_balance: Option<String>,
+    pub net_balance_number: Option<f64>,
+    /// Income and spending in `fiat_currency`, at the current price (not the price at the bucket's date)
+    pub income_fiat: Option<f64>,
+    pub spending_fiat: Option<f64>,
 }
 
+#[derive(Tsify, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChartBucketJs {
+    Day,
+    /// Starts on Monday
+    Week,
+    Month,
+    Quarter,
+    /// 4 weeks, starting on the Monday of the range start (the "year" preset)
+    FourWeeks,
+}
+
+impl ChartBucketJs {
+    /// Start of the bucket that contains `date`.
+    /// [ChartBucketJs::FourWeeks] buckets are anchored at the range start, so for it this is the start of the week.
+    pub fn start_of(&self, date: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let day = date.zero_time()?;
+        match self {
+            ChartBucketJs::Day => Ok(day),
+            ChartBucketJs::Week | ChartBucketJs::FourWeeks => {
+                Ok(day - Duration::days(day.weekday().num_days_from_monday().into()))
+            }
+            ChartBucketJs::Month => first_of_month(day.year(), day.month()),
+            ChartBucketJs::Quarter => first_of_month(day.year(), day.month0() / 3 * 3 + 1),
+        }
+    }
+
+    /// Start of the bucket after the one starting at `bucket_start`
+    pub fn next(&self, bucket_start: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        match self {
+            ChartBucketJs::Day => Ok(bucket_start + Duration::days(1)),
+            ChartBucketJs::Week => Ok(bucket_start + Duration::weeks(1)),
+            ChartBucketJs::Month => add_months(bucket_start, 1),
+            ChartBucketJs::Quarter => add_months(bucket_start, 3),
+            ChartBucketJs::FourWeeks => Ok(bucket_start + Duration::weeks(4)),
+        }
+    }
+}
+
+/// Generous for any chart, protects against ranges that are too long for the bucket size (e.g. 10 years in days)
+const MAX_BUCKETS: usize = 1000;
+
 #[derive(Debug, Clone)]
 pub struct IntervalData {
     pub start: DateTime<Utc>,
+    /// Inclusive: the last bucket contains this date
     pub end: DateTime<Utc>,
-    pub interval: Duration,
+    pub bucket: ChartBucketJs,
+}
+
+impl IntervalData {
+    /// Start dates of the buckets covering the range. The first bucket can start before `start`.
+    pub fn bucket_starts(&self) -> Result<Vec<DateTime<Utc>>> {
+        let mut starts = vec![];
+        let mut bucket_start = self.bucket.start_of(self.start)?;
+        while bucket_start <= self.end {
+            if starts.len() == MAX_BUCKETS {
+                return Err(anyhow!(
+                    "Too many buckets: range: {} - {} with bucket: {:?}. Max: {MAX_BUCKETS}",
+                    self.start,
+                    self.end,
+                    self.bucket
+                ));
+            }
+            starts.push(bucket_start);
+            bucket_start = self.bucket.next(bucket_start)?;
+        }
+        Ok(starts)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ChartSeriesOptions {
+    pub cumulative: bool,
+    pub net_balance: bool,
+}
+
+impl From<&IncomeVsSpendingParJs> for ChartSeriesOptions {
+    fn from(pars: &IncomeVsSpendingParJs) -> Self {
+        ChartSeriesOptions {
+            cumulative: pars.cumulative.unwrap_or(false),
+            net_balance: pars.net_balance.unwrap_or(false),
+        }
+    }
+}
+
+pub fn to_interval_data(pars: &IncomeVsSpendingParJs) -> Result<IntervalData> {
+    interval_data(
+        pars.interval.as_deref(),
+        pars.start.as_deref(),
+        pars.end.as_deref(),
+        pars.bucket,
+    )
 }
 
-pub fn to_interval_data(interval_str: &str) -> Result<IntervalData> {
+/// Range and bucket of a chart: a preset `interval` ("days7", "months3" or "year") ending today,
+/// or a custom range (`start`, optional `end`) with a `bucket`. The custom range has priority.
+pub fn interval_data(
+    interval: Option<&str>,
+    start: Option<&str>,
+    end: Option<&str>,
+    bucket: Option<ChartBucketJs>,
+) -> Result<IntervalData> {
+    if let Some(start) = start {
+        let start = parse_date(start)?;
+        let end = match end {
+            Some(end) => parse_date(end)?,
+            None => Utc::now(),
+        };
+        if start > end {
+            return Err(anyhow!("Start: {start} is after end: {end}"));
+        }
+        let bucket = bucket.ok_or_else(|| anyhow!("A bucket is required with a custom range"))?;
+        return Ok(IntervalData { start, end, bucket });
+    }
+
+    let interval_str =
+        interval.ok_or_else(|| anyhow!("Either an interval or a start date is required"))?;
     let end = Utc::now().zero_time()?;
     match interval_str {
         "days7" => Ok(IntervalData {
             start: end - Duration::days(6),
             end,
-            interval: Duration::days(1),
+            bucket: ChartBucketJs::Day,
         }),
         "months3" => Ok(IntervalData {
             start: end - Duration::weeks(11),
             end,
-            interval: Duration::weeks(1),
+            bucket: ChartBucketJs::Week,
         }),
         "year" => Ok(IntervalData {
             start: end - Duration::weeks(47),
             end,
-            interval: Duration::weeks(4),
+            bucket: ChartBucketJs::FourWeeks,
         }),
         _ => Err(anyhow!("Not supported interval str: {:?}", interval_str)),
     }
 }
 
+pub fn parse_date(str: &str) -> Result<DateTime<Utc>> {
+    Ok(DateTime::parse_from_rfc3339(str)?.with_timezone(&Utc))
+}
+
+fn first_of_month(year: i32, month: u32) -> Result<DateTime<Utc>> {
+    Ok(Utc
+        .ymd_opt(year, month, 1)
+        .single()
+        .ok_or_else(|| anyhow!("Invalid year: {year} or month: {month}"))?
+        .and_hms(0, 0, 0))
+}
+
+/// `date` is expected to be the first of a month
+fn add_months(date: DateTime<Utc>, months: i32) -> Result<DateTime<Utc>> {
+    let month0 = date.month0() as i32 + months;
+    first_of_month(
+        date.year() + month0.div_euclid(12),
+        month0.rem_euclid(12) as u32 + 1,
+    )
+}
+
 #[wasm_bindgen(js_name=incomeVsSpending)]
 pub async fn income_vs_spending(
     pars: IncomeVsSpendingParJs,
@@ -74,3 +235,80 @@ pub async fn income_vs_spending(
     })
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.ymd(year, month, day).and_hms(0, 0, 0)
+    }
+
+    fn bucket_starts(
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        bucket: ChartBucketJs,
+    ) -> Vec<DateTime<Utc>> {
+        IntervalData { start, end, bucket }.bucket_starts().unwrap()
+    }
+
+    #[test]
+    fn bucket_starts_days_include_the_end() {
+        let starts = bucket_starts(date(2022, 3, 1), date(2022, 3, 3), ChartBucketJs::Day);
+
+        assert_eq!(
+            vec![date(2022, 3, 1), date(2022, 3, 2), date(2022, 3, 3)],
+            starts
+        );
+    }
+
+    #[test]
+    fn bucket_starts_weeks_start_on_monday_before_the_start() {
+        // Wednesday to the Monday after next
+        let starts = bucket_starts(date(2022, 3, 2), date(2022, 3, 14), ChartBucketJs::Week);
+
+        assert_eq!(
+            vec![date(2022, 2, 28), date(2022, 3, 7), date(2022, 3, 14)],
+            starts
+        );
+    }
+
+    #[test]
+    fn bucket_starts_months_and_quarters_cross_years() {
+        let months = bucket_starts(date(2022, 11, 15), date(2023, 1, 1), ChartBucketJs::Month);
+        assert_eq!(
+            vec![date(2022, 11, 1), date(2022, 12, 1), date(2023, 1, 1)],
+            months
+        );
+
+        let quarters = bucket_starts(date(2022, 8, 15), date(2023, 2, 1), ChartBucketJs::Quarter);
+        assert_eq!(
+            vec![date(2022, 7, 1), date(2022, 10, 1), date(2023, 1, 1)],
+            quarters
+        );
+    }
+
+    #[test]
+    fn bucket_starts_four_weeks_are_anchored_at_the_start_week() {
+        let starts = bucket_starts(
+            date(2022, 3, 2),
+            // the day before the third bucket
+            date(2022, 4, 24),
+            ChartBucketJs::FourWeeks,
+        );
+
+        assert_eq!(vec![date(2022, 2, 28), date(2022, 3, 28)], starts);
+    }
+
+    #[test]
+    fn bucket_starts_fails_with_too_many_buckets() {
+        let res = IntervalData {
+            start: date(2000, 1, 1),
+            end: date(2022, 1, 1),
+            bucket: ChartBucketJs::Day,
+        }
+        .bucket_starts();
+
+        assert!(res.is_err());
+    }
+}
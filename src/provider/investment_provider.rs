@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::{error::FrError, js::bridge::log_wrap_new};
+use crate::{error::FrError, js::bridge::log_wrap_new, service::price_source::FiatAmountJs};
 
 use super::providers;
 
@@ -38,6 +38,9 @@ pub struct LoadInvestorResJs {
     pub investor_claimable_dividend_microalgos: String, // passthrough
     pub investor_locked_shares: String,
     pub investor_unlocked_shares: String,
+    /// Fiat equivalents in the user's selected currency, if any (current price)
+    pub investor_already_retrieved_amount_fiat: Option<FiatAmountJs>,
+    pub investor_claimable_dividend_fiat: Option<FiatAmountJs>,
 }
 
 #[derive(Tsify, Debug, Clone, Deserialize)]
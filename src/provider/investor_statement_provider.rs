@@ -0,0 +1,147 @@
+use crate::{
+    error::FrError,
+    js::bridge::log_wrap_new,
+    service::export::{to_csv, ExportFormat, ExportResJs},
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::providers;
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait InvestorStatementProvider {
+    async fn get(&self, pars: InvestorStatementParJs) -> Result<InvestorStatementResJs, FrError>;
+}
+
+#[derive(Tsify, Debug, Clone, Deserialize)]
+#[tsify(from_wasm_abi)]
+pub struct InvestorStatementParJs {
+    pub investor_address: String,
+    /// If not set, the statement includes all the DAOs the investor interacted with during the year
+    pub dao_id: Option<String>,
+    pub year: String,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct InvestorStatementResJs {
+    pub investor_address: String,
+    pub year: String,
+    pub daos: Vec<DaoStatementJs>,
+    /// All the entries of all the DAOs
+    pub csv: ExportResJs,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+pub struct DaoStatementJs {
+    pub dao_id: String,
+    pub dao_name: String,
+    pub funds_asset_unit: String,
+    /// Chronological
+    pub entries: Vec<StatementEntryJs>,
+    /// Cost basis of all the purchases (funds asset)
+    pub total_purchases: String,
+    /// Dividends claimed (funds asset)
+    pub total_dividends: String,
+    /// Funds received for reclaimed shares (funds asset)
+    pub total_reclaimed: String,
+    pub total_shares_bought: String,
+    pub total_shares_reclaimed: String,
+}
+
+#[derive(Tsify, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementEntryTypeJs {
+    Purchase,
+    Dividend,
+    Reclaim,
+    Lock,
+    Unlock,
+}
+
+impl StatementEntryTypeJs {
+    fn label(&self) -> &'static str {
+        match self {
+            StatementEntryTypeJs::Purchase => "purchase",
+            StatementEntryTypeJs::Dividend => "dividend",
+            StatementEntryTypeJs::Reclaim => "reclaim",
+            StatementEntryTypeJs::Lock => "lock",
+            StatementEntryTypeJs::Unlock => "unlock",
+        }
+    }
+}
+
+/// Amounts aren't formatted for display: full precision, in display units (e.g. "12.5"), with the base units alongside.
+#[derive(Tsify, Debug, Clone, Serialize)]
+pub struct StatementEntryJs {
+    pub type_: StatementEntryTypeJs,
+    /// RFC 3339
+    pub date: String,
+    /// Shares bought, reclaimed, locked or unlocked. "0" for dividends.
+    pub shares: String,
+    /// Funds paid (purchase: the cost basis) or received (dividend, reclaim). "0" for lock / unlock.
+    pub amount: String,
+    pub amount_base_units: String,
+    /// Purchases only
+    pub price_per_share: Option<String>,
+    pub tx_id: String,
+    pub tx_link: String,
+}
+
+const CSV_HEADERS: [&str; 11] = [
+    "dao_id",
+    "dao_name",
+    "type",
+    "date",
+    "shares",
+    "amount",
+    "amount_base_units",
+    "funds_asset_unit",
+    "price_per_share",
+    "tx_id",
+    "tx_link",
+];
+
+/// CSV with the entries of all the DAOs, in the order of `daos`
+pub fn investor_statement_csv(year: &str, daos: &[DaoStatementJs]) -> ExportResJs {
+    let rows = daos
+        .iter()
+        .flat_map(|dao| {
+            dao.entries.iter().map(move |e| {
+                vec![
+                    dao.dao_id.clone(),
+                    dao.dao_name.clone(),
+                    e.type_.label().to_owned(),
+                    e.date.clone(),
+                    e.shares.clone(),
+                    e.amount.clone(),
+                    e.amount_base_units.clone(),
+                    dao.funds_asset_unit.clone(),
+                    e.price_per_share.clone().unwrap_or_default(),
+                    e.tx_id.clone(),
+                    e.tx_link.clone(),
+                ]
+            })
+        })
+        .collect::<Vec<_>>();
+
+    ExportResJs::new(
+        ExportFormat::Csv,
+        &format!("statement_{year}"),
+        to_csv(&CSV_HEADERS, &rows),
+    )
+}
+
+#[wasm_bindgen(js_name=investorStatement)]
+pub async fn investor_statement(
+    pars: InvestorStatementParJs,
+) -> Result<InvestorStatementResJs, FrError> {
+    log_wrap_new("investor_statement", pars, async move |pars| {
+        providers()?.investor_statement.get(pars).await
+    })
+    .await
+}
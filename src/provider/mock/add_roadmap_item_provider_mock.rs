@@ -1,14 +1,14 @@
 use super::{mock_to_sign, mock_tx_id, req_delay};
+use crate::dependencies::algod;
 use crate::error::FrError;
 use crate::provider::add_roadmap_item_provider::{
     AddRoadmapItemParJs, AddRoadmapItemResJs, SubmitAddRoadmapItemParJs,
 };
 use crate::provider::add_roadmap_item_provider::{
-    AddRoadmapItemProvider, SubmitAddRoadmapItemResJs,
+    AddRoadmapItemProvider, ChangeRoadmapItemParJs, SubmitAddRoadmapItemResJs,
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use mbase::dependencies::algod;
 
 pub struct AddRoadmapItemProviderMock {}
 
@@ -26,6 +26,20 @@ impl AddRoadmapItemProvider for AddRoadmapItemProviderMock {
         })
     }
 
+    async fn change_txs(
+        &self,
+        pars: ChangeRoadmapItemParJs,
+    ) -> Result<AddRoadmapItemResJs, FrError> {
+        let algod = algod();
+        let dao_creator = pars.creator_address.parse().map_err(Error::msg)?;
+
+        req_delay().await;
+
+        Ok(AddRoadmapItemResJs {
+            to_sign: mock_to_sign(&algod, &dao_creator).await?,
+        })
+    }
+
     async fn submit(
         &self,
         _: SubmitAddRoadmapItemParJs,
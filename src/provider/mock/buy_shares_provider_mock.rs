@@ -1,4 +1,7 @@
-use super::{mock_to_sign, req_delay};
+use super::{mock_tx, req_delay};
+use crate::dependencies::algod;
+use crate::js::to_sign_js::ToSignJs;
+use crate::service::passthrough::Passthrough;
 use crate::{
     error::FrError,
     provider::{
@@ -12,7 +15,6 @@ use crate::{
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use mbase::dependencies::algod;
 
 pub struct BuySharesProviderMock {}
 
@@ -27,13 +29,17 @@ impl BuySharesProvider for BuySharesProviderMock {
         // validate to show error messages in mock
         validate_share_amount_positive(&pars.share_count)?;
 
+        let txs = vec![mock_tx(&algod, investor_address).await?];
+
         req_delay().await;
 
         Ok(InvestResJs {
-            to_sign: mock_to_sign(&algod, investor_address).await?,
+            to_sign: ToSignJs::new(txs.clone()).await?,
             pt: SubmitBuySharesPassthroughParJs {
                 dao_msg_pack: mock_msgpack_tx(&algod, investor_address).await?,
-            },
+                mac: String::new(),
+            }
+            .signed(&txs)?,
         })
     }
 
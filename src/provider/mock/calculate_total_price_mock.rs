@@ -1,5 +1,6 @@
 use crate::{
     error::FrError,
+    locale::locale,
     provider::calculate_total_price::{
         CalculateMaxFundsParJs, CalculateMaxFundsResJs, CalculateTotalPriceParJs,
         CalculateTotalPriceProvider, CalculateTotalPriceResJs,
@@ -15,8 +16,9 @@ pub struct CalculateTotalPriceMock {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl CalculateTotalPriceProvider for CalculateTotalPriceMock {
     async fn get(&self, _: CalculateTotalPriceParJs) -> Result<CalculateTotalPriceResJs, FrError> {
+        let locale = locale();
         Ok(CalculateTotalPriceResJs {
-            total_price: format_u64_readable(12345)?,
+            total_price: format_u64_readable(12345, &locale)?,
             total_price_number: "12345".to_owned(),
             profit_percentage: "0.23 %".to_owned(),
         })
@@ -26,8 +28,9 @@ impl CalculateTotalPriceProvider for CalculateTotalPriceMock {
         &self,
         _pars: CalculateMaxFundsParJs,
     ) -> Result<CalculateMaxFundsResJs, FrError> {
+        let locale = locale();
         Ok(CalculateMaxFundsResJs {
-            total_price: format_u64_readable(12345)?,
+            total_price: format_u64_readable(12345, &locale)?,
             total_price_number: "12345".to_owned(),
         })
     }
@@ -1,4 +1,5 @@
 use super::{mock_to_sign, req_delay};
+use crate::dependencies::algod;
 use crate::{
     error::FrError,
     provider::claim_provider::{
@@ -7,7 +8,6 @@ use crate::{
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use mbase::dependencies::algod;
 
 pub struct ClaimProviderMock {}
 
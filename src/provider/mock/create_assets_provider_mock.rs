@@ -1,14 +1,15 @@
-use super::{mock_to_sign, req_delay};
-use crate::dependencies::funds_asset_specs;
+use super::{mock_funds_asset_specs, mock_tx, req_delay};
+use crate::dependencies::algod;
 use crate::error::FrError;
+use crate::js::to_sign_js::ToSignJs;
 use crate::provider::create_assets_provider::{
     CreateAssetsProvider, CreateDaoAssetsParJs, CreateDaoAssetsResJs,
 };
 use crate::provider::create_dao_provider::validate_dao_inputs;
 use crate::provider::create_dao_provider::CreateDaoPassthroughParJs;
+use crate::service::passthrough::Passthrough;
 use anyhow::Result;
 use async_trait::async_trait;
-use mbase::dependencies::algod;
 
 pub struct CreateAssetsProviderMock {}
 
@@ -18,18 +19,22 @@ impl CreateAssetsProvider for CreateAssetsProviderMock {
     async fn txs(&self, pars: CreateDaoAssetsParJs) -> Result<CreateDaoAssetsResJs, FrError> {
         let algod = algod();
 
-        let funds_asset_specs = funds_asset_specs()?;
+        let funds_asset_specs = mock_funds_asset_specs()?;
 
         // this is a mock, but we validate, to be able to see the validation UI
         let validated_inputs = validate_dao_inputs(&pars.inputs, &funds_asset_specs)?;
 
+        let txs = vec![mock_tx(&algod, &validated_inputs.creator).await?];
+
         req_delay().await;
 
         Ok(CreateDaoAssetsResJs {
-            to_sign: mock_to_sign(&algod, &validated_inputs.creator).await?,
+            to_sign: ToSignJs::new(txs.clone()).await?,
             pt: CreateDaoPassthroughParJs {
                 inputs: pars.inputs,
-            },
+                mac: String::new(),
+            }
+            .signed(&txs)?,
         })
     }
 }
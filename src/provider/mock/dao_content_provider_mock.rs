@@ -0,0 +1,27 @@
+use super::req_delay;
+use crate::{
+    error::FrError,
+    provider::dao_content_provider::{
+        DaoContentProvider, VerifyDaoContentParJs, VerifyDaoContentResJs,
+    },
+    service::verified_content::ContentStatusJs,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+
+pub struct DaoContentProviderMock {}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl DaoContentProvider for DaoContentProviderMock {
+    async fn verify(&self, _: VerifyDaoContentParJs) -> Result<VerifyDaoContentResJs, FrError> {
+        req_delay().await;
+
+        Ok(VerifyDaoContentResJs {
+            description: Some(ContentStatusJs::Verified),
+            image: Some(ContentStatusJs::Unverified),
+            team: Some(ContentStatusJs::Verified),
+            prospectus: None,
+        })
+    }
+}
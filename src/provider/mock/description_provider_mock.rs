@@ -1,5 +1,9 @@
 use super::req_delay;
-use crate::{error::FrError, provider::description_provider::DescriptionProvider};
+use crate::{
+    error::FrError,
+    provider::description_provider::{DescriptionProvider, DescriptionResJs},
+    service::verified_content::ContentStatusJs,
+};
 use anyhow::Result;
 use async_trait::async_trait;
 
@@ -8,9 +12,12 @@ pub struct DescriptionProviderMock {}
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl DescriptionProvider for DescriptionProviderMock {
-    async fn get(&self, _id: String) -> Result<String, FrError> {
+    async fn get(&self, _id: String) -> Result<DescriptionResJs, FrError> {
         req_delay().await;
 
-        Ok("Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.".to_owned())
+        Ok(DescriptionResJs {
+            text: Some("Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.".to_owned()),
+            status: ContentStatusJs::Verified,
+        })
     }
 }
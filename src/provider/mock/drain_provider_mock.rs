@@ -1,4 +1,7 @@
-use super::{mock_to_sign, req_delay};
+use super::{mock_tx, req_delay};
+use crate::dependencies::algod;
+use crate::js::to_sign_js::ToSignJs;
+use crate::service::passthrough::Passthrough;
 use crate::{
     error::FrError,
     provider::drain_provider::{
@@ -8,7 +11,6 @@ use crate::{
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use mbase::dependencies::algod;
 
 pub struct DrainProviderMock {}
 
@@ -19,13 +21,17 @@ impl DrainProvider for DrainProviderMock {
         let algod = algod();
         let drainer_address = pars.drainer_address.parse().map_err(Error::msg)?;
 
+        let txs = vec![mock_tx(&algod, &drainer_address).await?];
+
         req_delay().await;
 
         Ok(DrainResJs {
-            to_sign: mock_to_sign(&algod, &drainer_address).await?,
+            to_sign: ToSignJs::new(txs.clone()).await?,
             pt: SubmitDrainPassthroughParJs {
                 dao_id: "12312132".to_owned(),
-            },
+                mac: String::new(),
+            }
+            .signed(&txs)?,
         })
     }
 
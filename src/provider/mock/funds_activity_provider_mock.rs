@@ -1,14 +1,19 @@
-use super::req_delay;
+use super::{mock_fiat_converter, req_delay};
 use crate::{
     error::FrError,
+    locale::locale,
     provider::{
         def::shares_distribution_provider_def::shorten_address,
         funds_activity_provider::{
-            FundsActivityProvider, FundsActivityViewData, LoadFundsActivityParJs,
-            LoadFundsActivityResJs,
+            funds_activity_export_file, funds_activity_page_size, ExportFundsActivityParJs,
+            FundsActivityExportEntry, FundsActivityProvider, FundsActivityTypeJs,
+            FundsActivityViewData, LoadFundsActivityParJs, LoadFundsActivityResJs,
         },
     },
-    service::number_formats::{format_decimal_readable, format_short},
+    service::{
+        export::ExportResJs,
+        number_formats::{format_decimal_readable, format_short},
+    },
 };
 use anyhow::Result;
 use async_trait::async_trait;
@@ -19,14 +24,17 @@ pub struct FundsActivityProviderMock {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl FundsActivityProvider for FundsActivityProviderMock {
     async fn get(&self, pars: LoadFundsActivityParJs) -> Result<LoadFundsActivityResJs, FrError> {
+        let locale = locale();
         req_delay().await;
 
-        // note that short_amount will be set at the end, based on amount
+        // note that short_amount (and the fiat amounts) will be set at the end, based on amount
         // normally we'd set short_amount manually, but tedious + not bad to ensure correctness to not confuse testers here
         let raw_entries = vec![FundsActivityViewData {
             amount: "123".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "111".to_owned(),
             is_income: "true".to_owned(), 
@@ -40,6 +48,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "923".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "111".to_owned(),
             is_income: "false".to_owned(), 
@@ -53,6 +63,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "10001100".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "112.2".to_owned(),
             amount_without_fee: "11100000".to_owned(),
             is_income: "true".to_owned(), 
@@ -66,6 +78,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "10".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "0.4".to_owned(),
             amount_without_fee: "9".to_owned(),
             is_income: "true".to_owned(), 
@@ -79,6 +93,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "33333".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.23".to_owned(),
             amount_without_fee: "22222".to_owned(),
             is_income: "false".to_owned(), 
@@ -92,6 +108,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "0.23".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "0.001".to_owned(),
             amount_without_fee: "0.01".to_owned(),
             is_income: "false".to_owned(), 
@@ -105,6 +123,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "10".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1".to_owned(),
             amount_without_fee: "0.1".to_owned(),
             is_income: "true".to_owned(), 
@@ -118,6 +138,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "123000".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "111".to_owned(),
             is_income: "true".to_owned(), 
@@ -131,6 +153,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "12".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "111".to_owned(),
             is_income: "true".to_owned(), 
@@ -144,6 +168,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "12123123422324233".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "111".to_owned(),
             is_income: "false".to_owned(), 
@@ -157,6 +183,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "1000".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "111".to_owned(),
             is_income: "true".to_owned(), 
@@ -170,6 +198,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "12312312112".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "111".to_owned(),
             is_income: "true".to_owned(), 
@@ -183,6 +213,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "123".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "111".to_owned(),
             is_income: "false".to_owned(), 
@@ -196,6 +228,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "123000".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "123000".to_owned(),
             is_income: "true".to_owned(), 
@@ -209,6 +243,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "123".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "123".to_owned(),
             is_income: "true".to_owned(), 
@@ -222,6 +258,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "5".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "4.9".to_owned(),
             is_income: "false".to_owned(), 
@@ -235,6 +273,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "500".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "489".to_owned(),
             is_income: "true".to_owned(), 
@@ -248,6 +288,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "1111".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "1110".to_owned(),
             is_income: "false".to_owned(), 
@@ -261,6 +303,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "123".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "111".to_owned(),
             is_income: "false".to_owned(), 
@@ -274,6 +318,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "550.123".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "548.123".to_owned(),
             is_income: "true".to_owned(), 
@@ -287,6 +333,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "1212".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "1211".to_owned(),
             is_income: "true".to_owned(), 
@@ -300,6 +348,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "88.123137899".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "84.123137899".to_owned(),
             is_income: "true".to_owned(), 
@@ -313,6 +363,8 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             amount: "2999".to_owned(),
             short_amount: "".to_owned(),
             short_amount_without_fee: "".to_owned(),
+            amount_fiat: None,
+            amount_without_fee_fiat: None,
             fee: "1.2".to_owned(),
             amount_without_fee: "111".to_owned(),
             is_income: "false".to_owned(), 
@@ -324,32 +376,65 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             tx_link: "https://testnet.algoexplorer.io/tx/FYIRN74JXW54KHOMNRLM42JKAYVGUA33JKXCGPHQIFWVDBY5SAQA".to_owned(),
         }];
 
-        let truncated_raw_entries = if let Some(max_results) = pars.max_results {
-            let max_results = max_results.parse()?;
-            raw_entries.into_iter().take(max_results).collect()
+        // only type filter: the mock data has just one counterparty and date
+        let filter = pars.filter.unwrap_or_default();
+        let filtered_raw_entries: Vec<FundsActivityViewData> = raw_entries
+            .into_iter()
+            .filter(|e| match filter.type_ {
+                Some(FundsActivityTypeJs::Income) => e.is_income == "true",
+                Some(FundsActivityTypeJs::Spending) => e.is_income == "false",
+                None => true,
+            })
+            .collect();
+
+        // the cursor is the index of the first entry of the page
+        let start: usize = pars.cursor.map(|c| c.parse()).transpose()?.unwrap_or(0);
+        let page_size = funds_activity_page_size(pars.max_results.as_deref())? as usize;
+        let end = (start + page_size).min(filtered_raw_entries.len());
+        let next = if end < filtered_raw_entries.len() {
+            Some(end.to_string())
         } else {
-            raw_entries
+            None
         };
+        let truncated_raw_entries: Vec<FundsActivityViewData> = filtered_raw_entries
+            .into_iter()
+            .skip(start)
+            .take(page_size)
+            .collect();
 
+        let fiat = mock_fiat_converter().await;
         let mut entries = vec![];
 
         // set short and readable amount
         for e in truncated_raw_entries {
             let amount = e.amount.parse()?;
-            let readable_amount = format_decimal_readable(amount)?;
-            let short_amount = format_short(amount)?;
+            let readable_amount = format_decimal_readable(amount, &locale)?;
+            let short_amount = format_short(amount, &locale)?;
             // log::debug!("{} -> {}", amount, short_amount);
 
             let amount_without_fee = e.amount_without_fee.parse()?;
-            let readable_amount_without_fee = format_decimal_readable(amount_without_fee)?;
-            let short_amount_without_fee = format_short(amount_without_fee)?;
+            let readable_amount_without_fee = format_decimal_readable(amount_without_fee, &locale)?;
+            let short_amount_without_fee = format_short(amount_without_fee, &locale)?;
             // log::debug!("{} -> {}", amount_without_fee, short_amount_without_fee);
 
+            let to_fiat = |display_units| {
+                fiat.as_ref().and_then(|f| {
+                    f.convert_display_units(display_units, &locale)
+                        .map_err(|e| log::error!("Couldn't convert to fiat: {e:?}"))
+                        .ok()
+                })
+            };
+            let amount_fiat = to_fiat(amount);
+            let amount_without_fee_fiat = to_fiat(amount_without_fee);
+
             entries.push(FundsActivityViewData {
                 // overwrite the dummy empty with short amounts (note that they can be equal to original, if no need to shorten)
                 short_amount,
                 short_amount_without_fee,
 
+                amount_fiat,
+                amount_without_fee_fiat,
+
                 // overwrite amount with readable amount
                 amount: readable_amount,
                 amount_without_fee: readable_amount_without_fee,
@@ -358,6 +443,51 @@ impl FundsActivityProvider for FundsActivityProviderMock {
             });
         }
 
-        Ok(LoadFundsActivityResJs { entries })
+        Ok(LoadFundsActivityResJs { entries, next })
+    }
+
+    async fn export(&self, pars: ExportFundsActivityParJs) -> Result<ExportResJs, FrError> {
+        req_delay().await;
+
+        let entries = vec![
+            FundsActivityExportEntry {
+                type_: "income".to_owned(),
+                date: "2022-04-20T10:15:00+00:00".to_owned(),
+                amount_base_units: "123000000".to_owned(),
+                amount: "123".to_owned(),
+                fee_base_units: "1230000".to_owned(),
+                fee: "1.23".to_owned(),
+                net_amount_base_units: "121770000".to_owned(),
+                net_amount: "121.77".to_owned(),
+                funds_asset_id: "123".to_owned(),
+                funds_asset_unit: "USDC".to_owned(),
+                description: "".to_owned(),
+                address: "7XSZQUQ2GJB25W37LVM5R4CMKKVC4VNSMIPCIWJYWM5ORA5VA4JRCNOJ4Y".to_owned(),
+                tx_id: "4VWUNOA5RH5OKMCSGEBETHLSPYQMDN3KBQNCQMQNDZZO7P4VDG3A".to_owned(),
+                tx_link: "https://testnet.algoexplorer.io/tx/4VWUNOA5RH5OKMCSGEBETHLSPYQMDN3KBQNCQMQNDZZO7P4VDG3A".to_owned(),
+            },
+            FundsActivityExportEntry {
+                type_: "spending".to_owned(),
+                date: "2022-04-21T16:40:00+00:00".to_owned(),
+                amount_base_units: "50000000".to_owned(),
+                amount: "50".to_owned(),
+                fee_base_units: "0".to_owned(),
+                fee: "0".to_owned(),
+                net_amount_base_units: "50000000".to_owned(),
+                net_amount: "50".to_owned(),
+                funds_asset_id: "123".to_owned(),
+                funds_asset_unit: "USDC".to_owned(),
+                description: "Bought supplies and services, \"office\"".to_owned(),
+                address: "7XSZQUQ2GJB25W37LVM5R4CMKKVC4VNSMIPCIWJYWM5ORA5VA4JRCNOJ4Y".to_owned(),
+                tx_id: "IR2PR2LQQZAGN3LC3BURTM5IR2ZVTXM3AVSL2Q5YUKTSNMDEIPXQ".to_owned(),
+                tx_link: "https://testnet.algoexplorer.io/tx/IR2PR2LQQZAGN3LC3BURTM5IR2ZVTXM3AVSL2Q5YUKTSNMDEIPXQ".to_owned(),
+            },
+        ];
+
+        Ok(funds_activity_export_file(
+            pars.format,
+            &pars.dao_id,
+            &entries,
+        )?)
     }
 }
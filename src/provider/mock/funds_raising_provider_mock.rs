@@ -1,5 +1,6 @@
 use crate::{
     error::FrError,
+    locale::locale,
     provider::funds_raising_provider::{
         FundsRaisingParsJs, FundsRaisingProvider, FundsRaisingResJs, FundsRaisingStateJs,
     },
@@ -14,9 +15,10 @@ pub struct FundsRaisingProviderMock {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl FundsRaisingProvider for FundsRaisingProviderMock {
     async fn data(&self, _pars: FundsRaisingParsJs) -> Result<FundsRaisingResJs, FrError> {
+        let locale = locale();
         Ok(FundsRaisingResJs {
             raised_number: 300_500.to_string(),
-            raised: format_u64_readable(300_500)?,
+            raised: format_u64_readable(300_500, &locale)?,
             state: FundsRaisingStateJs::GoalExceeded,
             goal_exceeded_percentage: Some("40%".to_owned()),
         })
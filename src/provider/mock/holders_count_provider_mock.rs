@@ -1,10 +1,15 @@
 use super::req_delay;
 use crate::{
     error::FrError,
+    locale::locale,
     model::QuantityChangeJs,
-    provider::holders_count_provider::{
-        HoldersChangeParJs, HoldersChangeResJs, HoldersCountParJs, HoldersCountProvider,
-        HoldersCountResJs,
+    provider::{
+        holders_count_provider::{
+            format_difference, HoldersChangeParJs, HoldersChangeResJs, HoldersCountParJs,
+            HoldersCountPointJs, HoldersCountProvider, HoldersCountResJs, HoldersHistoryParJs,
+            HoldersHistoryResJs,
+        },
+        income_vs_spending_provider::interval_data,
     },
 };
 use anyhow::Result;
@@ -26,6 +31,39 @@ impl HoldersCountProvider for HoldersCountProviderMock {
     async fn change(&self, _pars: HoldersChangeParJs) -> Result<HoldersChangeResJs, FrError> {
         Ok(HoldersChangeResJs {
             change: QuantityChangeJs::Down,
+            count: "2315".to_owned(),
+            previous_count: "2320".to_owned(),
+            difference: format_difference(2320, 2315),
         })
     }
+
+    async fn history(&self, pars: HoldersHistoryParJs) -> Result<HoldersHistoryResJs, FrError> {
+        let locale = locale();
+        let interval_data = interval_data(
+            pars.interval.as_deref(),
+            pars.start.as_deref(),
+            pars.end.as_deref(),
+            pars.bucket,
+        )?;
+
+        req_delay().await;
+
+        // grows steadily, with a dip every 5 buckets
+        let points = interval_data
+            .bucket_starts()?
+            .into_iter()
+            .enumerate()
+            .map(|(index, start)| {
+                let count = 2000 + index as u64 * 15 - if index % 5 == 4 { 20 } else { 0 };
+                HoldersCountPointJs {
+                    date: locale.format_date(&start),
+                    timestamp: start.timestamp_millis(),
+                    count: count.to_string(),
+                    count_number: count,
+                }
+            })
+            .collect();
+
+        Ok(HoldersHistoryResJs { points })
+    }
 }
@@ -1,16 +1,16 @@
-use crate::dependencies::funds_asset_specs;
 use crate::error::FrError;
 use crate::provider::def::income_vs_spending_provider_def::{
     to_income_vs_spending_res_static_bounds, ChartDataPoint,
 };
 use crate::provider::income_vs_spending_provider::{
-    to_interval_data, IncomeVsSpendingParJs, IncomeVsSpendingProvider, IncomeVsSpendingResJs,
+    to_interval_data, ChartSeriesOptions, IncomeVsSpendingParJs, IncomeVsSpendingProvider,
+    IncomeVsSpendingResJs,
 };
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
 
-use super::req_delay;
+use super::{mock_fiat_converter, mock_funds_asset_specs, req_delay};
 
 pub struct IncomeVsSpendingProviderMock {}
 
@@ -18,14 +18,16 @@ pub struct IncomeVsSpendingProviderMock {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl IncomeVsSpendingProvider for IncomeVsSpendingProviderMock {
     async fn get(&self, pars: IncomeVsSpendingParJs) -> Result<IncomeVsSpendingResJs, FrError> {
-        let funds_asset_specs = funds_asset_specs()?;
+        let funds_asset_specs = mock_funds_asset_specs()?;
 
         let now = Utc::now();
 
         let income_data_points = test_income_points(now);
         let spending_data_points = test_spending_points(now);
 
-        let interval_data = to_interval_data(&pars.interval)?;
+        let interval_data = to_interval_data(&pars)?;
+
+        let fiat = mock_fiat_converter().await;
 
         req_delay().await;
 
@@ -34,6 +36,8 @@ impl IncomeVsSpendingProvider for IncomeVsSpendingProviderMock {
             spending_data_points,
             &funds_asset_specs,
             interval_data,
+            &ChartSeriesOptions::from(&pars),
+            fiat.as_ref(),
         )
     }
 }
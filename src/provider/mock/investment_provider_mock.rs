@@ -1,6 +1,7 @@
-use super::req_delay;
+use super::{mock_fiat_converter, mock_funds_asset_specs, req_delay};
 use crate::{
     error::FrError,
+    locale::locale,
     provider::investment_provider::{
         AvailableSharesParJs, AvailableSharesResJs, InvestmentProvider, LoadInvestorParJs,
         LoadInvestorResJs,
@@ -8,6 +9,7 @@ use crate::{
 };
 use anyhow::Result;
 use async_trait::async_trait;
+use mbase::models::funds::FundsAmount;
 
 pub struct InvestmentProviderMock {}
 
@@ -27,8 +29,17 @@ impl InvestmentProvider for InvestmentProviderMock {
     }
 
     async fn get_investor_data(&self, _: LoadInvestorParJs) -> Result<LoadInvestorResJs, FrError> {
+        let locale = locale();
         req_delay().await;
 
+        let funds_asset_specs = mock_funds_asset_specs()?;
+        let fiat = mock_fiat_converter().await;
+        let to_fiat = |base_units| {
+            fiat.as_ref().and_then(|f| {
+                f.convert_or_log(FundsAmount::new(base_units), &funds_asset_specs, &locale)
+            })
+        };
+
         Ok(LoadInvestorResJs {
             investor_shares_count: "123".to_owned(),
             investor_share: "12 %".to_owned(),
@@ -39,6 +50,9 @@ impl InvestmentProvider for InvestmentProviderMock {
 
             investor_locked_shares: "20".to_owned(),
             investor_unlocked_shares: "10".to_owned(),
+
+            investor_already_retrieved_amount_fiat: to_fiat(11_100_000_000),
+            investor_claimable_dividend_fiat: to_fiat(240_000_000),
         })
     }
 }
@@ -0,0 +1,77 @@
+use super::req_delay;
+use crate::{
+    error::FrError,
+    provider::investor_statement_provider::{
+        investor_statement_csv, DaoStatementJs, InvestorStatementParJs, InvestorStatementProvider,
+        InvestorStatementResJs, StatementEntryJs, StatementEntryTypeJs,
+    },
+};
+use anyhow::Result;
+use async_trait::async_trait;
+
+pub struct InvestorStatementProviderMock {}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl InvestorStatementProvider for InvestorStatementProviderMock {
+    async fn get(&self, pars: InvestorStatementParJs) -> Result<InvestorStatementResJs, FrError> {
+        req_delay().await;
+
+        let year = &pars.year;
+        let entry = |type_, date: &str, shares: &str, amount: &str, amount_base_units: &str| {
+            StatementEntryJs {
+                type_,
+                date: format!("{year}-{date}T10:00:00+00:00"),
+                shares: shares.to_owned(),
+                amount: amount.to_owned(),
+                amount_base_units: amount_base_units.to_owned(),
+                price_per_share: (type_ == StatementEntryTypeJs::Purchase).then(|| "1".to_owned()),
+                tx_id: "FXDAG5ACAS2PYAW3M5NWVXBLCYKDTJ6Q2VUK2QRUMYWXJDSDDV3Q".to_owned(),
+                tx_link: "https://testnet.algoexplorer.io/tx/FXDAG5ACAS2PYAW3M5NWVXBLCYKDTJ6Q2VUK2QRUMYWXJDSDDV3Q".to_owned(),
+            }
+        };
+
+        let daos = vec![DaoStatementJs {
+            dao_id: pars.dao_id.clone().unwrap_or_else(|| "123".to_owned()),
+            dao_name: "My project".to_owned(),
+            funds_asset_unit: "USDC".to_owned(),
+            entries: vec![
+                entry(
+                    StatementEntryTypeJs::Purchase,
+                    "02-03",
+                    "1000",
+                    "1000",
+                    "1000000000",
+                ),
+                entry(
+                    StatementEntryTypeJs::Dividend,
+                    "05-10",
+                    "0",
+                    "12.5",
+                    "12500000",
+                ),
+                entry(StatementEntryTypeJs::Unlock, "08-21", "1000", "0", "0"),
+                entry(StatementEntryTypeJs::Lock, "09-01", "400", "0", "0"),
+                entry(
+                    StatementEntryTypeJs::Reclaim,
+                    "12-15",
+                    "200",
+                    "180",
+                    "180000000",
+                ),
+            ],
+            total_purchases: "1000".to_owned(),
+            total_dividends: "12.5".to_owned(),
+            total_reclaimed: "180".to_owned(),
+            total_shares_bought: "1000".to_owned(),
+            total_shares_reclaimed: "200".to_owned(),
+        }];
+
+        Ok(InvestorStatementResJs {
+            csv: investor_statement_csv(year, &daos),
+            investor_address: pars.investor_address.clone(),
+            year: year.clone(),
+            daos,
+        })
+    }
+}
@@ -1,3 +1,4 @@
+use crate::dependencies::algod;
 use crate::{
     error::FrError,
     provider::lock_provider::{
@@ -6,7 +7,6 @@ use crate::{
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use mbase::dependencies::algod;
 
 use super::{mock_to_sign, req_delay};
 
@@ -1,6 +1,17 @@
 use crate::{
-    error::FrError, js::to_sign_js::ToSignJs, model::dao_js::DaoJs,
-    service::number_formats::format_u64_readable,
+    dependencies::{funds_asset_id, FundsAssetSpecs},
+    error::FrError,
+    js::{
+        explorer_links::{explorer_app_link_env, explorer_asset_link_env},
+        to_sign_js::ToSignJs,
+    },
+    fiat::fiat_settings,
+    locale::locale,
+    model::dao_js::DaoJs,
+    service::{
+        number_formats::format_u64_readable,
+        price_source::{FiatConverter, MockPriceSource, PriceSource},
+    },
 };
 use algonaut::{
     algod::v2::Algod,
@@ -19,6 +30,7 @@ pub mod calculate_total_price_mock;
 pub mod claim_provider_mock;
 pub mod create_assets_provider_mock;
 pub mod create_dao_provider_mock;
+pub mod dao_content_provider_mock;
 pub mod dao_provider_mock;
 pub mod description_provider_mock;
 pub mod dividends_provider_mock;
@@ -28,6 +40,7 @@ pub mod funds_raising_provider_mock;
 pub mod holders_count_provider_mock;
 pub mod income_vs_spending_provider_mock;
 pub mod investment_provider_mock;
+pub mod investor_statement_provider_mock;
 pub mod lock_provider_mock;
 pub mod my_daos_provider_mock;
 pub mod my_shares_provider_mock;
@@ -62,7 +75,7 @@ pub async fn mock_msgpack_tx(algod: &Algod, address: &Address) -> Result<Vec<u8>
 }
 
 pub async fn mock_to_sign(algod: &Algod, address: &Address) -> Result<ToSignJs> {
-    ToSignJs::new(vec![mock_tx(algod, address).await?])
+    ToSignJs::new(vec![mock_tx(algod, address).await?]).await
 }
 
 pub fn mock_address() -> Result<Address> {
@@ -80,21 +93,46 @@ pub fn mock_tx_id() -> String {
     "3CUYREVXKFMJOSWJRC3GY6UEAJ3BA36RGN4PKSL7CYRLCWZSIT3A".to_string()
 }
 
+/// Converter for the user's selected currency, always with the mock prices (mocks shouldn't access the price API)
+pub async fn mock_fiat_converter() -> Option<FiatConverter> {
+    let currency = fiat_settings().ok()?.currency?;
+    let price = MockPriceSource {}
+        .price(&mock_funds_asset_specs().ok()?, &currency)
+        .await
+        .ok()?;
+    Some(FiatConverter::new(&currency, price))
+}
+
+/// Mocks don't access the chain, so we can't fetch the params
+pub fn mock_funds_asset_specs() -> Result<FundsAssetSpecs> {
+    Ok(FundsAssetSpecs {
+        id: funds_asset_id()?,
+        decimals: 6,
+        unit_name: "USDC".to_owned(),
+        name: "USD Coin".to_owned(),
+    })
+}
+
 pub fn mock_dao_for_users_view_data() -> Result<DaoJs, FrError> {
+    let locale = locale();
     Ok(DaoJs {
         name: "Test name".to_owned(),
         descr_url: None,
-        share_supply: format_u64_readable(123123123)?,
+        share_supply: format_u64_readable(123123123, &locale)?,
         share_supply_number: "123123123".to_owned(),
         investors_share: "0.4".to_owned(),
         share_asset_name: "My asset name".to_owned(),
         share_price: "100".to_owned(),
         share_price_number_algo: "100".to_owned(),
         shares_asset_id: "1231231231".to_owned(),
+        shares_asset_explorer_link: explorer_asset_link_env(1231231231),
+        funds_asset_id: funds_asset_id()?.0.to_string(),
+        funds_asset_unit: mock_funds_asset_specs()?.unit_name,
         image_url: Some("https://placekitten.com/1033/360".to_owned()),
         social_media_url: "https://twitter.com/foobardoesntexist".to_owned(),
         app_id: "111112222".to_owned(),
         app_address: mock_address()?.to_string(),
+        app_explorer_link: explorer_app_link_env(111112222),
         // note that the paths here have to match to what the UI expects, to open the correct views (the parameters/ids can be arbitrary)
         invest_link: format!("/{}", "111112222"),
         my_investment_link: format!("/{}/investment", "111112222"),
@@ -103,8 +141,8 @@ pub fn mock_dao_for_users_view_data() -> Result<DaoJs, FrError> {
         creator_address: mock_address()?.to_string(),
         raise_end_date: (Utc::now() + Duration::weeks(8)).timestamp().to_string(),
         raise_min_target_number: 200_000.to_string(),
-        raise_min_target: format_u64_readable(200_000)?,
-        total_raisable: format_u64_readable(500_000)?,
+        raise_min_target: format_u64_readable(200_000, &locale)?,
+        total_raisable: format_u64_readable(500_000, &locale)?,
         total_raisable_number: 500_000.to_string(),
         funds_raised: "true".to_owned(),
         setup_date: Utc::now().timestamp().to_string(),
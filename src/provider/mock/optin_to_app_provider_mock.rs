@@ -1,11 +1,11 @@
 use super::{mock_to_sign, req_delay};
+use crate::dependencies::algod;
 use crate::{
     error::FrError,
     provider::optin_to_app_provider::{OptInToAppParJs, OptInToAppResJs, OptinToAppProvider},
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use mbase::dependencies::algod;
 
 pub struct OptinToAppProviderMock {}
 
@@ -1,5 +1,6 @@
 use super::mock_to_sign;
 use super::req_delay;
+use crate::dependencies::algod;
 use crate::error::FrError;
 use crate::provider::pay_dao_provider::{
     PayDaoParJs, PayDaoProvider, PayDaoResJs, SubmitPayDaoParJs, SubmitPayDaoResJs,
@@ -7,7 +8,6 @@ use crate::provider::pay_dao_provider::{
 use anyhow::Error;
 use anyhow::Result;
 use async_trait::async_trait;
-use mbase::dependencies::algod;
 
 pub struct PayDaoProviderMock {}
 
@@ -1,11 +1,14 @@
 use crate::{
     error::FrError,
     provider::roadmap_provider::{
-        GetRoadmapParJs, GetRoadmapResJs, RoadmapItemJs, RoadmapProvider,
+        roadmap_calendar_event, roadmap_calendar_file, validate_dao_url, ExportRoadmapParJs,
+        GetRoadmapParJs, GetRoadmapResJs, RoadmapItemChangeViewJs, RoadmapItemJs, RoadmapProvider,
     },
+    service::export::ExportResJs,
 };
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
 
 use super::req_delay;
 
@@ -20,11 +23,11 @@ impl RoadmapProvider for RoadmapProviderMock {
         Ok(GetRoadmapResJs {
             items: vec![
                 mock_header("Q1 2023"),
-                mock_item("Lorem ipsum dolor sit amet, consectetur adipiscing elit"),
-                mock_item("Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua"),
+                mock_completed_item("Lorem ipsum dolor sit amet, consectetur adipiscing elit"),
+                mock_edited_item("Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua"),
                 mock_item("Some roadmap item descr"),
                 mock_header("Q2 2023"),
-                mock_item("Some roadmap item descr"),
+                mock_epic("Some roadmap epic descr"),
                 mock_item("Lorem ipsum dolor sit amet, consectetur adipiscing elit sed do eiusmod tempor incididunt ut labore et dolore magna aliqua sed do eiusmod tempor incididunt ut labore et dolore magna aliqua sed do eiusmod tempor incididunt ut labore et dolore magna aliqua"),
                 mock_header("Q3 2023"),
                 mock_item("Some roadmap item descr"),
@@ -62,6 +65,35 @@ impl RoadmapProvider for RoadmapProviderMock {
                 ],
         })
     }
+
+    async fn export_calendar(&self, pars: ExportRoadmapParJs) -> Result<ExportResJs, FrError> {
+        validate_dao_url(&pars.dao_url)?;
+
+        req_delay().await;
+
+        let events = vec![
+            roadmap_calendar_event(
+                "5ZKN6IXOQOVHWCN2EGKIL3Z4SSW6ZEBPJXDZDLXESCAXMYFBPTZA",
+                Utc.ymd(2023, 2, 15).and_hms(0, 0, 0),
+                "Lorem ipsum dolor sit amet, consectetur adipiscing elit",
+                None,
+                None,
+                true,
+                &pars.dao_url,
+            ),
+            roadmap_calendar_event(
+                "LSN3PC4J3EPZCKIJUS3MXMM7XH3N3VKVDTR6HXKGL7ANJXR2JN7A",
+                Utc.ymd(2023, 5, 1).and_hms(0, 0, 0),
+                "Some roadmap epic descr",
+                Some("Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua"),
+                Some("https://github.com/ivanschuetz/capi-wasm"),
+                false,
+                &pars.dao_url,
+            ),
+        ];
+
+        Ok(roadmap_calendar_file(&pars.dao_id, "Mock DAO", &events)?)
+    }
 }
 
 fn mock_header(text: &str) -> RoadmapItemJs {
@@ -71,6 +103,15 @@ fn mock_header(text: &str) -> RoadmapItemJs {
         tx_link: None,
         date: None,
         text: text.to_owned(),
+        description: None,
+        link: None,
+        parent_tx_id: None,
+        children: vec![],
+        status: None,
+        completed: false,
+        completed_date: None,
+        edited: false,
+        history: vec![],
     }
 }
 
@@ -81,5 +122,56 @@ fn mock_item(text: &str) -> RoadmapItemJs {
         tx_link: Some("https://testnet.algoexplorer.io/tx/5ZKN6IXOQOVHWCN2EGKIL3Z4SSW6ZEBPJXDZDLXESCAXMYFBPTZA".to_owned()),
         date: Some("Wed, 20 Apr 2022 12:01:00 +0000".to_owned()),
         text: text.to_owned(),
+        description: None,
+        link: None,
+        parent_tx_id: None,
+        children: vec![],
+        status: Some("planned".to_owned()),
+        completed: false,
+        completed_date: None,
+        edited: false,
+        history: vec![mock_change("add", text)],
+    }
+}
+
+fn mock_epic(text: &str) -> RoadmapItemJs {
+    let mut item = mock_item(text);
+    item.description =
+        Some("Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua".to_owned());
+    item.link = Some("https://github.com/ivanschuetz/capi-wasm".to_owned());
+    item.children = vec![
+        mock_completed_item("Some roadmap sub-item descr"),
+        mock_item("Some roadmap sub-item descr"),
+    ];
+    for child in &mut item.children {
+        child.parent_tx_id = item.tx_id.clone();
+    }
+    item
+}
+
+fn mock_completed_item(text: &str) -> RoadmapItemJs {
+    let mut item = mock_item(text);
+    item.status = Some("completed".to_owned());
+    item.completed = true;
+    item.completed_date = Some("1650456060".to_owned());
+    item.history.push(mock_change("complete", ""));
+    item
+}
+
+fn mock_edited_item(text: &str) -> RoadmapItemJs {
+    let mut item = mock_item(text);
+    item.edited = true;
+    item.history.push(mock_change("edit", text));
+    item
+}
+
+fn mock_change(op: &str, text: &str) -> RoadmapItemChangeViewJs {
+    RoadmapItemChangeViewJs {
+        op: op.to_owned(),
+        tx_id: "5ZKN6IXOQOVHWCN2EGKIL3Z4SSW6ZEBPJXDZDLXESCAXMYFBPTZA".to_owned(),
+        tx_link: "https://testnet.algoexplorer.io/tx/5ZKN6IXOQOVHWCN2EGKIL3Z4SSW6ZEBPJXDZDLXESCAXMYFBPTZA".to_owned(),
+        text: text.to_owned(),
+        date: "1650456060".to_owned(),
+        saved_date: Some("1650456060".to_owned()),
     }
 }
@@ -1,18 +1,16 @@
 use crate::error::FrError;
-use crate::js::explorer_links::explorer_address_link_env;
+use crate::locale::locale;
 use crate::provider::def::shares_distribution_provider_def::{
-    not_owned_shares_holdings, shorten_address,
+    not_owned_shares_holdings, to_shares_distribution_js, HolderWithSplit, SharesSplit,
 };
 use crate::provider::mock::req_delay;
 use crate::provider::shares_distribution_provider::{
-    ShareHoldingPercentageJs, SharedDistributionParJs, SharedDistributionResJs,
-    SharesDistributionProvider,
+    SharedDistributionParJs, SharedDistributionResJs, SharesDistributionProvider,
 };
 use anyhow::{anyhow, Error, Result};
 use async_trait::async_trait;
 use base::queries::shares_distribution::ShareHoldingPercentage;
 use mbase::models::share_amount::ShareAmount;
-use mbase::util::decimal_util::DecimalExt;
 use rust_decimal::Decimal;
 
 pub struct SharesDistributionProviderMock {}
@@ -20,7 +18,8 @@ pub struct SharesDistributionProviderMock {}
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl SharesDistributionProvider for SharesDistributionProviderMock {
-    async fn get(&self, _: SharedDistributionParJs) -> Result<SharedDistributionResJs, FrError> {
+    async fn get(&self, pars: SharedDistributionParJs) -> Result<SharedDistributionResJs, FrError> {
+        let locale = locale();
         req_delay().await;
 
         let fake_supply = 10_000_000;
@@ -125,32 +124,36 @@ impl SharesDistributionProvider for SharesDistributionProviderMock {
             )));
         }
 
-        let mut holders_js = vec![];
-        for h in &holders {
-            holders_js.push(ShareHoldingPercentageJs {
-                address: h.address.to_string(),
-                label: shorten_address(&h.address)?,
-                address_browser_link: explorer_address_link_env(&h.address),
-                amount: h.amount.to_string(),
-                percentage_formatted: h.percentage.format_percentage(),
-                percentage_number: h.percentage.to_string(),
-                type_: "holder".to_owned(),
-            });
-        }
-
-        let not_owned = not_owned_shares_holdings(&holders, fake_supply)?;
+        let not_owned = not_owned_shares_holdings(&holders, fake_supply, &locale)?;
         log::info!(
             "mock data: not owned shares: amount: {}, percentage: {}",
             not_owned.amount,
             not_owned.percentage_formatted
         );
 
-        holders_js.push(not_owned);
+        // mock split: a third of the shares locked
+        let holders = holders
+            .into_iter()
+            .map(|h| {
+                let locked = h.amount.val() / 3;
+                HolderWithSplit {
+                    split: Some(SharesSplit {
+                        locked,
+                        free: h.amount.val() - locked,
+                    }),
+                    holding: h,
+                }
+            })
+            .collect();
+
+        let top_n = pars.top_n.map(|n| n.parse()).transpose()?;
 
-        Ok(SharedDistributionResJs {
-            holders: holders_js,
-            not_owned_shares: "555".to_owned(),
-        })
+        Ok(to_shares_distribution_js(
+            holders,
+            fake_supply,
+            top_n,
+            &locale,
+        )?)
     }
 }
 
@@ -1,17 +1,22 @@
+use crate::dependencies::algod;
 use crate::{
     error::FrError,
     provider::{
-        def::team_provider_def::{add_team_member_shared, edit_team_member_shared},
+        def::team_provider_def::{
+            add_team_member_shared, edit_team_member_shared, remove_team_member_shared,
+            reorder_team_members_shared,
+        },
         team_provider::{
             AddTeamMemberParsJs, AddTeamMemberResJs, EditTeamMemberParsJs, EditTeamMemberResJs,
-            GetTeamParsJs, GetTeamResJs, SetTeamParsJs, SetTeamResJs, SubmitSetTeamParJs,
-            TeamMemberJs, TeamProvider,
+            GetTeamParsJs, GetTeamResJs, RemoveTeamMemberParsJs, RemoveTeamMemberResJs,
+            ReorderTeamMembersParsJs, ReorderTeamMembersResJs, SetTeamParsJs, SetTeamResJs,
+            SubmitSetTeamParJs, TeamMemberJs, TeamProvider,
         },
     },
+    service::verified_content::ContentStatusJs,
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use mbase::dependencies::algod;
 
 use super::{mock_to_sign, req_delay};
 
@@ -63,6 +68,7 @@ impl TeamProvider for TeamProviderMock {
                 linkedin_url: None,
             }
             ],
+            status: ContentStatusJs::Verified,
         })
     }
 
@@ -80,6 +86,20 @@ impl TeamProvider for TeamProviderMock {
         edit_team_member_shared(pars).await
     }
 
+    async fn remove_team_member(
+        &self,
+        pars: RemoveTeamMemberParsJs,
+    ) -> Result<RemoveTeamMemberResJs, FrError> {
+        remove_team_member_shared(pars).await
+    }
+
+    async fn reorder_team_members(
+        &self,
+        pars: ReorderTeamMembersParsJs,
+    ) -> Result<ReorderTeamMembersResJs, FrError> {
+        reorder_team_members_shared(pars).await
+    }
+
     async fn set(&self, pars: SetTeamParsJs) -> Result<SetTeamResJs, FrError> {
         let algod = algod();
 
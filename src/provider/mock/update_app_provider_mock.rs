@@ -1,3 +1,4 @@
+use crate::dependencies::algod;
 use crate::error::FrError;
 use crate::provider::mock::req_delay;
 use crate::provider::update_app_provider::{
@@ -6,7 +7,6 @@ use crate::provider::update_app_provider::{
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use mbase::dependencies::algod;
 
 use super::mock_to_sign;
 
@@ -1,16 +1,19 @@
-use super::{mock_dao_for_users_view_data, mock_to_sign, req_delay};
+use super::{mock_dao_for_users_view_data, mock_tx, req_delay};
+use crate::dependencies::algod;
+use crate::js::to_sign_js::ToSignJs;
+use crate::service::passthrough::Passthrough;
 use crate::{
     error::FrError,
     provider::update_data_provider::{
         SubmitUpdateDataParJs, UpdatableDataParJs, UpdatableDataResJs, UpdateDataParJs,
         UpdateDataPassthroughJs, UpdateDataProvider, UpdateDataResJs,
     },
+    service::verified_content::ContentStatusJs,
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
 use base::{api::fetcher::Fetcher, dependencies::fetcher};
 use data_encoding::BASE64;
-use mbase::dependencies::algod;
 
 pub struct UpdateDataProviderMock {}
 
@@ -20,6 +23,8 @@ impl UpdateDataProvider for UpdateDataProviderMock {
     async fn get(&self, _: UpdatableDataParJs) -> Result<UpdatableDataResJs, FrError> {
         let fetcher = fetcher();
 
+        let txs = vec![mock_tx(&algod, &owner).await?];
+
         req_delay().await;
 
         // just a convienient source for our data
@@ -33,10 +38,13 @@ impl UpdateDataProvider for UpdateDataProviderMock {
         Ok(UpdatableDataResJs {
             project_name: mock_dao.name,
             project_desc: Some("My project description".to_owned()),
+            project_desc_status: Some(ContentStatusJs::Verified),
             share_price: "1_000".to_owned(),
             image_base64: Some(image_bytes_base64),
+            image_status: Some(ContentStatusJs::Unverified),
             social_media_url: "https://twitter.com/foobardoesntexist".to_owned(),
             prospectus: None,
+            prospectus_status: None,
             min_invest_amount: 3.to_string(),
             max_invest_amount: 10.to_string(),
         })
@@ -51,10 +59,12 @@ impl UpdateDataProvider for UpdateDataProviderMock {
         req_delay().await;
 
         Ok(UpdateDataResJs {
-            to_sign: mock_to_sign(&algod, &owner).await?,
+            to_sign: ToSignJs::new(txs.clone()).await?,
             pt: UpdateDataPassthroughJs {
                 dao_id: mock_dao.app_id,
-            },
+                mac: String::new(),
+            }
+            .signed(&txs)?,
         })
     }
 
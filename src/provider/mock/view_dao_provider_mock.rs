@@ -1,12 +1,17 @@
-use super::{mock_address, mock_dao_for_users_view_data, req_delay};
+use super::{
+    mock_address, mock_dao_for_users_view_data, mock_fiat_converter, mock_funds_asset_specs,
+    req_delay,
+};
 use crate::{
     error::FrError,
+    locale::locale,
     provider::view_dao_provider::{ViewDaoParJs, ViewDaoProvider, ViewDaoResJs},
     service::number_formats::format_u64_readable,
 };
 use algonaut::{core::MicroAlgos, transaction::url::LinkableTransactionBuilder};
 use anyhow::Result;
 use async_trait::async_trait;
+use mbase::models::funds::FundsAmount;
 
 pub struct ViewDaoProviderMock {}
 
@@ -14,14 +19,22 @@ pub struct ViewDaoProviderMock {}
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl ViewDaoProvider for ViewDaoProviderMock {
     async fn get(&self, _: ViewDaoParJs) -> Result<ViewDaoResJs, FrError> {
+        let locale = locale();
         req_delay().await;
 
         let dao = mock_dao_for_users_view_data()?;
+        let funds_asset_specs = mock_funds_asset_specs()?;
+        let fiat = mock_fiat_converter().await;
+        let to_fiat = |base_units| {
+            fiat.as_ref().and_then(|f| {
+                f.convert_or_log(FundsAmount::new(base_units), &funds_asset_specs, &locale)
+            })
+        };
         Ok(ViewDaoResJs {
             dao,
-            shares_available: format_u64_readable(10000000)?,
+            shares_available: format_u64_readable(10000000, &locale)?,
             investors_share: "40 %".to_owned(),
-            available_funds: format_u64_readable(20000)?,
+            available_funds: format_u64_readable(20000, &locale)?,
             customer_payment_deeplink: LinkableTransactionBuilder::payment(
                 mock_address()?,
                 MicroAlgos(0),
@@ -29,6 +42,8 @@ impl ViewDaoProvider for ViewDaoProviderMock {
             .build()
             .as_url()
             .to_string(),
+            available_funds_fiat: to_fiat(20_000_000_000),
+            share_price_fiat: to_fiat(100_000_000),
         })
     }
 }
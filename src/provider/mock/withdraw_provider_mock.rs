@@ -1,16 +1,18 @@
-use super::mock_tx_id;
-use crate::dependencies::funds_asset_specs;
+use super::{mock_funds_asset_specs, mock_tx_id};
+use crate::dependencies::algod;
 use crate::error::FrError;
+use crate::js::to_sign_js::ToSignJs;
+use crate::locale::locale;
 use crate::provider::def::withdraw_provider_def::withdrawal_view_data;
-use crate::provider::mock::{mock_to_sign, req_delay};
+use crate::provider::mock::{mock_tx, req_delay};
 use crate::provider::withdraw_provider::{
     validate_withdrawal_inputs, SubmitWithdrawParJs, SubmitWithdrawPassthroughParJs,
     SubmitWithdrawResJs, WithdrawInputsPassthroughJs, WithdrawParJs, WithdrawProvider,
     WithdrawResJs,
 };
+use crate::service::passthrough::Passthrough;
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use mbase::dependencies::algod;
 
 pub struct WithdrawProviderMock {}
 
@@ -31,18 +33,23 @@ impl WithdrawProvider for WithdrawProviderMock {
             description: pars.description.clone(),
         };
 
+        let txs = vec![mock_tx(&algod, &owner).await?];
+
         req_delay().await;
 
         Ok(WithdrawResJs {
-            to_sign: mock_to_sign(&algod, &owner).await?,
+            to_sign: ToSignJs::new(txs.clone()).await?,
             pt: SubmitWithdrawPassthroughParJs {
+                dao_id: pars.dao_id.clone(),
                 inputs: inputs_par.clone(),
-            },
+                mac: String::new(),
+            }
+            .signed(&txs)?,
         })
     }
 
     async fn submit(&self, pars: SubmitWithdrawParJs) -> Result<SubmitWithdrawResJs, FrError> {
-        let funds_asset_specs = funds_asset_specs()?;
+        let funds_asset_specs = mock_funds_asset_specs()?;
 
         // validate - for UI
         let withdrawal_inputs = validate_withdrawal_inputs(&pars.pt.inputs, &funds_asset_specs)?;
@@ -56,6 +63,7 @@ impl WithdrawProvider for WithdrawProviderMock {
                 withdrawal_inputs.description,
                 "Just now".to_owned(),
                 mock_tx_id().parse()?,
+                &locale(),
             ),
         })
     }
@@ -6,6 +6,8 @@ pub mod calculate_total_price;
 pub mod claim_provider;
 pub mod create_assets_provider;
 pub mod create_dao_provider;
+pub mod dao_page;
+pub mod dao_content_provider;
 pub mod dao_provider;
 pub mod def;
 pub mod description_provider;
@@ -16,6 +18,7 @@ pub mod funds_raising_provider;
 pub mod holders_count_provider;
 pub mod income_vs_spending_provider;
 pub mod investment_provider;
+pub mod investor_statement_provider;
 pub mod lock_provider;
 mod mock;
 pub mod my_daos_provider;
@@ -37,4 +40,4 @@ pub mod withdraw_provider;
 pub mod withdrawal_history_provider;
 pub mod wyre_provider;
 
-pub use providers::providers;
+pub use providers::{providers, swappable_provider_names};
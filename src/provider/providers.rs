@@ -7,6 +7,7 @@ use super::{
     claim_provider::ClaimProvider,
     create_assets_provider::CreateAssetsProvider,
     create_dao_provider::CreateDaoProvider,
+    dao_content_provider::DaoContentProvider,
     dao_provider::DaoProvider,
     def::{
         add_roadmap_item_provider_def::AddRoadmapItemProviderDef,
@@ -14,16 +15,18 @@ use super::{
         buy_shares_provider_def::BuySharesProviderDef,
         calculate_total_price_def::CalculateTotalPriceDef, claim_provider_def::ClaimProviderDef,
         create_assets_provider_def::CreateAssetsProviderDef,
-        create_dao_provider_def::CreateDaoProviderDef, dao_provider_def::DaoUserViewProviderDef,
+        create_dao_provider_def::CreateDaoProviderDef,
+        dao_content_provider_def::DaoContentProviderDef, dao_provider_def::DaoUserViewProviderDef,
         description_provider_def::DescriptionProviderDef, dev_provider_def::DevProviderDef,
         dividends_provider_def::DividendsProviderDef, drain_provider_def::DrainProviderDef,
         funds_activity_provider_def::FundsActivityProviderDef,
         funds_raising_provider_def::FundsRaisingProviderDef, hash_provider_def::HashProviderDef,
         holders_count_provider_def::HoldersCountProviderDef,
         income_vs_spending_provider_def::IncomeVsSpendingProviderDef,
-        investment_provider_def::InvestmentProviderDef, lock_provider_def::LockProviderDef,
-        metadata_provider_def::MetadataProviderDef, my_daos_provider_def::MyDaosProviderDef,
-        my_shares_provider_def::MySharesProviderDef,
+        investment_provider_def::InvestmentProviderDef,
+        investor_statement_provider_def::InvestorStatementProviderDef,
+        lock_provider_def::LockProviderDef, metadata_provider_def::MetadataProviderDef,
+        my_daos_provider_def::MyDaosProviderDef, my_shares_provider_def::MySharesProviderDef,
         optin_to_app_provider_def::OptinToAppProviderDef, pay_dao_provider_def::PayDaoProviderDef,
         reclaim_provider_def::ReclaimProviderDef, rekey_provider_def::RekeyProviderDef,
         roadmap_provider_def::RoadmapProviderDef,
@@ -44,6 +47,7 @@ use super::{
     holders_count_provider::HoldersCountProvider,
     income_vs_spending_provider::IncomeVsSpendingProvider,
     investment_provider::InvestmentProvider,
+    investor_statement_provider::InvestorStatementProvider,
     lock_provider::LockProvider,
     mock::{
         add_roadmap_item_provider_mock::AddRoadmapItemProviderMock,
@@ -54,6 +58,7 @@ use super::{
         claim_provider_mock::ClaimProviderMock,
         create_assets_provider_mock::CreateAssetsProviderMock,
         create_dao_provider_mock::CreateDaoProviderMock,
+        dao_content_provider_mock::DaoContentProviderMock,
         dao_provider_mock::DaoUserViewProviderMock,
         description_provider_mock::DescriptionProviderMock,
         dividends_provider_mock::DividendsProviderMock, drain_provider_mock::DrainProviderMock,
@@ -61,8 +66,10 @@ use super::{
         funds_raising_provider_mock::FundsRaisingProviderMock,
         holders_count_provider_mock::HoldersCountProviderMock,
         income_vs_spending_provider_mock::IncomeVsSpendingProviderMock,
-        investment_provider_mock::InvestmentProviderMock, lock_provider_mock::LockProviderMock,
-        my_daos_provider_mock::MyDaosProviderMock, my_shares_provider_mock::MySharesProviderMock,
+        investment_provider_mock::InvestmentProviderMock,
+        investor_statement_provider_mock::InvestorStatementProviderMock,
+        lock_provider_mock::LockProviderMock, my_daos_provider_mock::MyDaosProviderMock,
+        my_shares_provider_mock::MySharesProviderMock,
         optin_to_app_provider_mock::OptinToAppProviderMock,
         pay_dao_provider_mock::PayDaoProviderMock, reclaim_provider_mock::ReclaimProviderMock,
         rekey_provider_mock::RekeyProviderMock, roadmap_provider_mock::RoadmapProviderMock,
@@ -93,7 +100,10 @@ use super::{
     withdrawal_history_provider::WithdrawalHistoryProvider,
     wyre_provider::WyreProvider,
 };
-use crate::{dependencies::data_type, error::FrError};
+use crate::{
+    dependencies::{data_type, provider_data_types, set_provider_data_type},
+    error::FrError,
+};
 use mbase::dependencies::DataType;
 
 pub struct Providers<'a> {
@@ -135,6 +145,8 @@ pub struct Providers<'a> {
     pub metadata: MetadataProviderDef,
     pub dev_settings: DevProviderDef,
     pub team: &'a dyn TeamProvider,
+    pub investor_statement: &'a dyn InvestorStatementProvider,
+    pub dao_content: &'a dyn DaoContentProvider,
 }
 
 // we return JsValue for convenience, this is used only in the bridge (which returns JsValue)
@@ -142,12 +154,97 @@ pub fn providers<'a>() -> Result<Providers<'a>, FrError> {
     // note that we create data_type here instead of parametrizing, it's noise as all the bridge functions would have to pass it and no good reason for it.
     let data_type = data_type()?;
     log::info!("Data type config: {data_type:?}");
-    Ok(match data_type {
+    let mut providers = providers_for(&data_type);
+
+    let provider_data_types = provider_data_types()?;
+    if !provider_data_types.is_empty() {
+        log::info!("Provider data types config: {provider_data_types:?}");
+    }
+    for (name, data_type) in provider_data_types {
+        if !swap_provider(&mut providers, &name, &providers_for(&data_type)) {
+            // e.g. a provider that was renamed or removed since the data type was set: it shouldn't break the app
+            log::warn!("Unknown provider: {name} in provider data types, removing it");
+            if let Err(e) = set_provider_data_type(&name, None) {
+                log::error!("Couldn't remove unknown provider: {name} from data types: {e:?}");
+            }
+        }
+    }
+
+    Ok(providers)
+}
+
+fn providers_for<'a>(data_type: &DataType) -> Providers<'a> {
+    match data_type {
         DataType::Real => def_providers(),
         DataType::Mock => mock_providers(),
-    })
+    }
+}
+
+/// Generates the functions to select the data type for individual providers, by field name.
+/// Only the providers that have a mock are listed.
+macro_rules! swappable_providers {
+    ($($field:ident),* $(,)?) => {
+        /// Replaces the provider named `name` in `providers` with the one in `source`.
+        /// Returns false if there's no provider with this name.
+        fn swap_provider<'a>(
+            providers: &mut Providers<'a>,
+            name: &str,
+            source: &Providers<'a>,
+        ) -> bool {
+            match name {
+                $(stringify!($field) => providers.$field = source.$field,)*
+                _ => return false,
+            }
+            true
+        }
+
+        /// Names that can be used to set the data type of an individual provider
+        pub fn swappable_provider_names() -> Vec<&'static str> {
+            vec![$(stringify!($field)),*]
+        }
+    };
 }
 
+swappable_providers!(
+    funds_activity,
+    balance,
+    buy_shares,
+    shares_count,
+    dao,
+    app_optin,
+    claim,
+    investment,
+    lock,
+    pay_dao,
+    holders_count,
+    income_vs_spending,
+    my_daos,
+    my_shares,
+    shares_distribution,
+    add_roadmap_item,
+    roadmap,
+    unlock,
+    app_updates,
+    update_app,
+    update_data,
+    view_dao,
+    drain,
+    withdraw,
+    withdrawals_history,
+    create_dao,
+    create_assets,
+    calculate_total_price,
+    dividend,
+    reclaim,
+    description,
+    wyre,
+    rekey,
+    raised,
+    team,
+    investor_statement,
+    dao_content,
+);
+
 fn def_providers<'a>() -> Providers<'a> {
     Providers {
         funds_activity: &FundsActivityProviderDef {},
@@ -188,6 +285,8 @@ fn def_providers<'a>() -> Providers<'a> {
         metadata: MetadataProviderDef {},
         dev_settings: DevProviderDef {},
         team: &TeamProviderDef {},
+        investor_statement: &InvestorStatementProviderDef {},
+        dao_content: &DaoContentProviderDef {},
     }
 }
 
@@ -231,5 +330,7 @@ fn mock_providers<'a>() -> Providers<'a> {
         metadata: MetadataProviderDef {},
         dev_settings: DevProviderDef {},
         team: &TeamProviderMock {},
+        investor_statement: &InvestorStatementProviderMock {},
+        dao_content: &DaoContentProviderMock {},
     }
 }
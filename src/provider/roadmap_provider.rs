@@ -1,10 +1,15 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::{error::FrError, js::bridge::log_wrap_new};
+use crate::{
+    error::FrError,
+    js::bridge::log_wrap_new,
+    service::export::{to_ics, CalendarEvent, ExportResJs},
+};
 
 use super::providers;
 
@@ -12,6 +17,8 @@ use super::providers;
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 pub trait RoadmapProvider {
     async fn get(&self, pars: GetRoadmapParJs) -> Result<GetRoadmapResJs, FrError>;
+    /// iCalendar (.ics) file with the roadmap items as all-day events
+    async fn export_calendar(&self, pars: ExportRoadmapParJs) -> Result<ExportResJs, FrError>;
 }
 
 #[derive(Tsify, Debug, Clone, Deserialize)]
@@ -19,6 +26,16 @@ pub trait RoadmapProvider {
 pub struct GetRoadmapParJs {
     pub creator_address: String,
     pub dao_id: String,
+    /// Default: quarter
+    pub grouping: Option<RoadmapGroupingJs>,
+}
+
+#[derive(Tsify, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+pub enum RoadmapGroupingJs {
+    Month,
+    Quarter,
+    HalfYear,
+    Year,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
@@ -27,13 +44,98 @@ pub struct GetRoadmapResJs {
     pub items: Vec<RoadmapItemJs>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Tsify, Debug, Clone, Serialize)]
 pub struct RoadmapItemJs {
     pub item_type: String,       // "item" | "header"
     pub tx_id: Option<String>,   // set if type == "item"
     pub tx_link: Option<String>, // set if type == "item"
     pub date: Option<String>,    // set if type == "item"
     pub text: String,
+    pub description: Option<String>,
+    pub link: Option<String>,
+    pub parent_tx_id: Option<String>, // set if it's a sub-item
+    /// Sub-items (type "item"), sorted by date. Empty for headers.
+    /// They're shown under the parent's time range (header), regardless of their own date.
+    pub children: Vec<RoadmapItemJs>,
+    pub status: Option<String>, // set if type == "item": "planned" | "overdue" | "completed"
+    pub completed: bool,
+    pub completed_date: Option<String>,
+    pub edited: bool,
+    /// Chronological, starting with the addition. Empty for headers.
+    pub history: Vec<RoadmapItemChangeViewJs>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+pub struct RoadmapItemChangeViewJs {
+    pub op: String, // "add" | "edit" | "complete" | "reopen" | "remove"
+    pub tx_id: String,
+    pub tx_link: String,
+    pub text: String, // empty if op doesn't change the title
+    pub date: String,
+    pub saved_date: Option<String>, // not set for items saved before changes were supported
+}
+
+#[derive(Tsify, Debug, Clone, Deserialize)]
+#[tsify(from_wasm_abi)]
+pub struct ExportRoadmapParJs {
+    pub creator_address: String,
+    pub dao_id: String,
+    /// The DAO's page in the frontend, linked from the events
+    pub dao_url: String,
+}
+
+/// An item of the roadmap as calendar event. The item's tx id is the event's UID.
+pub fn roadmap_calendar_event(
+    tx_id: &str,
+    date: DateTime<Utc>,
+    title: &str,
+    description: Option<&str>,
+    link: Option<&str>,
+    completed: bool,
+    dao_url: &str,
+) -> CalendarEvent {
+    let mut description_parts = vec![];
+    if completed {
+        description_parts.push("Completed".to_owned());
+    }
+    if let Some(description) = description {
+        description_parts.push(description.to_owned());
+    }
+    if let Some(link) = link {
+        description_parts.push(link.to_owned());
+    }
+    description_parts.push(dao_url.to_owned());
+
+    CalendarEvent {
+        uid: format!("{tx_id}@capi"),
+        date: date.naive_utc().date(),
+        summary: title.to_owned(),
+        description: Some(description_parts.join("\n\n")),
+        url: Some(dao_url.to_owned()),
+    }
+}
+
+/// Shared by the providers, so the def and mock exports have the same format
+pub fn roadmap_calendar_file(
+    dao_id: &str,
+    dao_name: &str,
+    events: &[CalendarEvent],
+) -> Result<ExportResJs> {
+    Ok(ExportResJs::calendar(
+        &format!("roadmap_{dao_id}"),
+        to_ics(&format!("{dao_name} roadmap"), events, Utc::now())?,
+    ))
+}
+
+/// Http(s), without whitespace or control characters (the url is written unescaped to the calendar file)
+pub fn validate_dao_url(url: &str) -> Result<()> {
+    if (url.starts_with("http://") || url.starts_with("https://"))
+        && !url.chars().any(|c| c.is_whitespace() || c.is_control())
+    {
+        Ok(())
+    } else {
+        Err(anyhow!("Invalid DAO url: {url}"))
+    }
 }
 
 #[wasm_bindgen(js_name=loadRoadmap)]
@@ -43,3 +145,30 @@ pub async fn load_roadmap(pars: GetRoadmapParJs) -> Result<GetRoadmapResJs, FrEr
     })
     .await
 }
+
+#[wasm_bindgen(js_name=exportRoadmapCalendar)]
+pub async fn export_roadmap_calendar(pars: ExportRoadmapParJs) -> Result<ExportResJs, FrError> {
+    log_wrap_new("export_roadmap_calendar", pars, async move |pars| {
+        providers()?.roadmap.export_calendar(pars).await
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_dao_url_accepts_http_urls() {
+        assert!(validate_dao_url("https://app.capi.finance/dao/1").is_ok());
+        assert!(validate_dao_url("http://localhost:3000/dao/1").is_ok());
+    }
+
+    #[test]
+    fn validate_dao_url_rejects_other_schemes_and_line_breaks() {
+        assert!(validate_dao_url("javascript:alert(1)").is_err());
+        assert!(validate_dao_url("https://capi.finance\r\nATTACH:https://evil.com").is_err());
+        assert!(validate_dao_url("https://capi.finance\tx").is_err());
+        assert!(validate_dao_url("https://capi.finance x").is_err());
+    }
+}
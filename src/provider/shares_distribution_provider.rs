@@ -22,6 +22,9 @@ pub struct SharedDistributionParJs {
     pub share_supply: String,
 
     pub app_id: String,
+
+    /// If set, only the largest `top_n` holders are listed, the rest is grouped into "Others"
+    pub top_n: Option<String>,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
@@ -34,6 +37,10 @@ pub struct ShareHoldingPercentageJs {
     pub percentage_formatted: String,
     pub percentage_number: String,
     pub type_: String, // NOTE: don't change without updating react
+    /// Shares locked in the app (local state). None for "not owned", or if it couldn't be fetched.
+    pub locked: Option<String>,
+    /// Shares held by the account (asset holdings). None for "not owned", or if it couldn't be fetched.
+    pub free: Option<String>,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
@@ -41,6 +48,22 @@ pub struct ShareHoldingPercentageJs {
 pub struct SharedDistributionResJs {
     pub holders: Vec<ShareHoldingPercentageJs>,
     pub not_owned_shares: String,
+    /// Calculated with all the holders (not affected by `top_n`)
+    pub concentration: ConcentrationJs,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+pub struct ConcentrationJs {
+    pub holders_count: String,
+    /// Relative to the supply
+    pub largest_holder_percentage_formatted: String,
+    pub largest_holder_percentage_number: String,
+    /// Of the holders' amounts: 0 (everyone holds the same) to 1 (a single holder has everything)
+    pub gini_formatted: String,
+    pub gini_number: String,
+    /// Min. number of (largest) holders that together have more than half of the supply.
+    /// None if all the holders together don't have more than half.
+    pub holders_for_majority: Option<String>,
 }
 
 #[wasm_bindgen(js_name=sharesDistribution)]
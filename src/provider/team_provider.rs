@@ -1,6 +1,7 @@
 use crate::{
     error::FrError,
     js::{bridge::log_wrap_new, common::SignedTxFromJs, to_sign_js::ToSignJs},
+    service::verified_content::ContentStatusJs,
 };
 use anyhow::Result;
 use async_trait::async_trait;
@@ -25,6 +26,14 @@ pub trait TeamProvider {
         &self,
         pars: EditTeamMemberParsJs,
     ) -> Result<EditTeamMemberResJs, FrError>;
+    async fn remove_team_member(
+        &self,
+        pars: RemoveTeamMemberParsJs,
+    ) -> Result<RemoveTeamMemberResJs, FrError>;
+    async fn reorder_team_members(
+        &self,
+        pars: ReorderTeamMembersParsJs,
+    ) -> Result<ReorderTeamMembersResJs, FrError>;
 
     async fn set(&self, pars: SetTeamParsJs) -> Result<SetTeamResJs, FrError>;
     async fn submit(&self, pars: SubmitSetTeamParJs) -> Result<(), FrError>;
@@ -33,6 +42,7 @@ pub trait TeamProvider {
 #[derive(Tsify, Debug, Clone, Deserialize)]
 #[tsify(from_wasm_abi)]
 pub struct GetTeamParsJs {
+    /// The team url saved in the DAO. If it has a content hash (see [SetTeamParsJs]), the fetched team is verified with it.
     pub url: String,
 }
 
@@ -52,7 +62,9 @@ pub struct TeamMemberInputs {
 #[derive(Tsify, Debug, Clone, Serialize)]
 #[tsify(into_wasm_abi)]
 pub struct GetTeamResJs {
+    /// Empty if the team changed or is unreachable (see status)
     pub team: Vec<TeamMemberJs>,
+    pub status: ContentStatusJs,
 }
 
 #[derive(Tsify, Debug, Clone, Deserialize, Serialize)]
@@ -126,12 +138,43 @@ pub struct EditTeamMemberResJs {
     pub to_save: String,         // upload to IPFS
 }
 
+#[derive(Tsify, Debug, Clone, Deserialize)]
+#[tsify(from_wasm_abi)]
+pub struct RemoveTeamMemberParsJs {
+    pub uuid: String,
+    pub existing_members: Vec<TeamMemberJs>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct RemoveTeamMemberResJs {
+    pub team: Vec<TeamMemberJs>, // display
+    pub to_save: String,         // upload to IPFS
+}
+
+#[derive(Tsify, Debug, Clone, Deserialize)]
+#[tsify(from_wasm_abi)]
+pub struct ReorderTeamMembersParsJs {
+    /// The uuids of all the existing members, in the new order
+    pub uuids: Vec<String>,
+    pub existing_members: Vec<TeamMemberJs>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct ReorderTeamMembersResJs {
+    pub team: Vec<TeamMemberJs>, // display
+    pub to_save: String,         // upload to IPFS
+}
+
 #[derive(Tsify, Debug, Clone, Deserialize)]
 #[tsify(from_wasm_abi)]
 pub struct SetTeamParsJs {
     pub dao_id: String,
     pub owner_address: String,
     pub url: String,
+    /// What was uploaded to the url (`to_save`). Its hash is saved with the url, to verify the team when loading it.
+    pub team: String,
 }
 
 #[derive(Tsify, Debug, Clone, Serialize)]
@@ -170,6 +213,26 @@ pub async fn edit_team_member(pars: EditTeamMemberParsJs) -> Result<EditTeamMemb
     .await
 }
 
+#[wasm_bindgen(js_name=removeTeamMember)]
+pub async fn remove_team_member(
+    pars: RemoveTeamMemberParsJs,
+) -> Result<RemoveTeamMemberResJs, FrError> {
+    log_wrap_new("remove_team_member", pars, async move |pars| {
+        providers()?.team.remove_team_member(pars).await
+    })
+    .await
+}
+
+#[wasm_bindgen(js_name=reorderTeamMembers)]
+pub async fn reorder_team_members(
+    pars: ReorderTeamMembersParsJs,
+) -> Result<ReorderTeamMembersResJs, FrError> {
+    log_wrap_new("reorder_team_members", pars, async move |pars| {
+        providers()?.team.reorder_team_members(pars).await
+    })
+    .await
+}
+
 #[wasm_bindgen(js_name=setTeam)]
 pub async fn set_team(pars: SetTeamParsJs) -> Result<SetTeamResJs, FrError> {
     log_wrap_new("set_team", pars, async move |pars| {
@@ -8,6 +8,7 @@ use crate::{
     error::FrError,
     js::{bridge::log_wrap_new, common::SignedTxFromJs, to_sign_js::ToSignJs},
     model::ProspectusJs,
+    service::{passthrough::Passthrough, verified_content::ContentStatusJs},
 };
 
 use super::providers;
@@ -32,12 +33,18 @@ pub struct UpdatableDataParJs {
 pub struct UpdatableDataResJs {
     pub project_name: String,
     pub project_desc: Option<String>,
+    /// Set if the DAO has a description
+    pub project_desc_status: Option<ContentStatusJs>,
     pub share_price: String,
 
     pub image_base64: Option<String>, // js image cropper library expects base64
+    /// Set if the DAO has an image
+    pub image_status: Option<ContentStatusJs>,
     pub social_media_url: String,
 
     pub prospectus: Option<ProspectusJs>,
+    /// Set if the DAO has a prospectus
+    pub prospectus_status: Option<ContentStatusJs>,
     pub min_invest_amount: String,
     pub max_invest_amount: String,
 }
@@ -51,10 +58,12 @@ pub struct UpdateDataParJs {
     pub owner: String,
 
     pub project_name: String,
+    /// The hash of the description at this url is saved with it (see [crate::service::content_hash])
     pub project_desc_url: Option<String>,
     // TODO remove? not updatable currently
     pub share_price: String,
 
+    /// The hash of the image at this url is saved as the image NFT's metadata hash (see [crate::service::verified_content])
     pub image_url: Option<String>,
     pub social_media_url: String,
 
@@ -87,6 +96,16 @@ pub struct SubmitUpdateDataParJs {
 #[tsify(from_wasm_abi, into_wasm_abi)]
 pub struct UpdateDataPassthroughJs {
     pub dao_id: String,
+    /// See [crate::service::passthrough]
+    pub mac: String,
+}
+
+impl Passthrough for UpdateDataPassthroughJs {
+    const OPERATION: &'static str = "update_data";
+
+    fn mac_mut(&mut self) -> &mut String {
+        &mut self.mac
+    }
 }
 
 /// To pre fill the form to update data
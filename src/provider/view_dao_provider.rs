@@ -1,6 +1,7 @@
 use crate::error::FrError;
 use crate::js::bridge::log_wrap_new;
 use crate::model::dao_js::DaoJs;
+use crate::service::price_source::FiatAmountJs;
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::Deserialize;
@@ -31,6 +32,9 @@ pub struct ViewDaoResJs {
     pub investors_share: String,
     pub available_funds: String,
     pub customer_payment_deeplink: String,
+    /// Fiat equivalents in the user's selected currency, if any (current price)
+    pub available_funds_fiat: Option<FiatAmountJs>,
+    pub share_price_fiat: Option<FiatAmountJs>,
 }
 
 #[wasm_bindgen(js_name=viewDao)]
@@ -4,6 +4,7 @@ use crate::js::bridge::log_wrap_new;
 use crate::js::common::SignedTxFromJs;
 use crate::js::to_sign_js::ToSignJs;
 use crate::service::number_formats::validate_funds_amount_input;
+use crate::service::passthrough::Passthrough;
 use algonaut::core::Address;
 use anyhow::Error;
 use anyhow::Result;
@@ -51,7 +52,18 @@ pub struct SubmitWithdrawParJs {
 #[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
 #[tsify(from_wasm_abi, into_wasm_abi)]
 pub struct SubmitWithdrawPassthroughParJs {
+    pub dao_id: String,
     pub inputs: WithdrawInputsPassthroughJs,
+    /// See [crate::service::passthrough]
+    pub mac: String,
+}
+
+impl Passthrough for SubmitWithdrawPassthroughParJs {
+    const OPERATION: &'static str = "withdraw";
+
+    fn mac_mut(&mut self) -> &mut String {
+        &mut self.mac
+    }
 }
 
 #[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
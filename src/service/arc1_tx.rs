@@ -0,0 +1,57 @@
+use crate::wallet::{MultisigHint, WalletSettings};
+use algonaut::{core::Address, transaction::Transaction};
+use anyhow::Result;
+use data_encoding::BASE64;
+use serde::Serialize;
+use tsify::Tsify;
+
+use super::tx_message::tx_sender;
+
+// ARC-1 `WalletTransaction`, passed directly to `signTxns` in JS
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub struct Arc1Tx {
+    txn: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    /// Empty: the wallet must not sign this tx (it's signed by someone else)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auth_addr: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    msig: Option<MultisigHint>,
+}
+
+impl Arc1Tx {
+    /// wallet: if set, used to add the signing hints (signers, authAddr, msig)
+    pub fn new(tx: &Transaction, message: &str, wallet: Option<&WalletSettings>) -> Result<Arc1Tx> {
+        let mut arc1_tx = Arc1Tx {
+            txn: BASE64.encode(&rmp_serde::to_vec_named(tx)?),
+            message: Some(message.to_owned()).filter(|m| !m.is_empty()),
+            signers: None,
+            auth_addr: None,
+            msig: None,
+        };
+
+        let connected_address = match wallet.and_then(|w| w.address.as_ref()) {
+            Some(address) => Some(address.parse::<Address>().map_err(anyhow::Error::msg)?),
+            None => None,
+        };
+
+        if let (Some(wallet), Some(connected_address), Some(sender)) =
+            (wallet, connected_address, tx_sender(tx))
+        {
+            if sender == connected_address {
+                arc1_tx.auth_addr = wallet.auth_addr.clone();
+                arc1_tx.msig = wallet.msig.clone();
+            } else {
+                // not the connected account's tx
+                arc1_tx.signers = Some(vec![]);
+            }
+        }
+
+        Ok(arc1_tx)
+    }
+}
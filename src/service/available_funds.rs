@@ -9,20 +9,22 @@ use mbase::{
         capi_deps::CapiAssetDaoDeps,
         funds::{FundsAmount, FundsAssetId},
     },
-    state::dao_app_state::dao_global_state,
+    state::dao_app_state::CentralAppGlobalState,
 };
 
 // The "actual" funds of the app: available funds + not-yet-drained funds(deducting the fee).
 // Available/not available funds distinction is internal / low level.
 // This is the dao's balance users see on the UI.
+// `dao_state`: the dao's global state (callers usually fetched it already).
 pub async fn owned_funds(
     algod: &Algod,
     dao: &Dao,
+    dao_state: &CentralAppGlobalState,
     funds_asset_id: FundsAssetId,
     capi_deps: &CapiAssetDaoDeps,
 ) -> Result<FundsAmount> {
     let holdings = funds_holdings(algod, &dao.app_address(), funds_asset_id).await?;
-    let available_funds = dao_global_state(algod, dao.app_id).await?.available;
+    let available_funds = dao_state.available;
 
     let not_available_funds = FundsAmount::new(
         holdings
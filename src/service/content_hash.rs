@@ -0,0 +1,100 @@
+//! Hashes of content stored outside of the chain (e.g. the team JSON), saved on chain with the url:
+//! the url gets a `#h=<hash>` fragment. Fragments aren't sent to the host, so fetching the url isn't affected.
+//! Not used for the image: the NFT url is limited to 96 bytes and can have its own fragment (`#arc3`),
+//! so its hash is saved in the NFT's metadata hash instead (see [super::verified_content]).
+
+use super::verified_content::ContentStatusJs;
+use data_encoding::BASE64;
+use mbase::models::hashable::hash;
+
+const HASH_FRAGMENT_PREFIX: &str = "#h=";
+
+/// Base64 hash of the content, like [crate::provider::def::hash_provider_def::HashProviderDef]
+pub fn content_hash(bytes: &[u8]) -> String {
+    BASE64.encode(&hash(bytes).0)
+}
+
+/// `url` with the hash of `bytes` as fragment (replacing an existing fragment)
+pub fn url_with_content_hash(url: &str, bytes: &[u8]) -> String {
+    format!(
+        "{}{HASH_FRAGMENT_PREFIX}{}",
+        url_without_fragment(url),
+        content_hash(bytes)
+    )
+}
+
+pub fn url_without_fragment(url: &str) -> &str {
+    url.split('#').next().unwrap_or(url)
+}
+
+/// The hash saved in the url, if any (urls saved before hashes were supported don't have it)
+pub fn expected_content_hash(url: &str) -> Option<&str> {
+    url.find(HASH_FRAGMENT_PREFIX)
+        .map(|index| &url[index + HASH_FRAGMENT_PREFIX.len()..])
+        .filter(|hash| !hash.is_empty())
+}
+
+/// Verified if `bytes` match `expected_hash`, Changed if not, Unverified without hash
+pub fn content_status(expected_hash: Option<&str>, bytes: &[u8]) -> ContentStatusJs {
+    match expected_hash {
+        Some(expected) if content_hash(bytes) == expected => ContentStatusJs::Verified,
+        Some(_) => ContentStatusJs::Changed,
+        None => ContentStatusJs::Unverified,
+    }
+}
+
+/// [content_status] with the hash saved in the url
+pub fn check_content_hash(url: &str, bytes: &[u8]) -> ContentStatusJs {
+    content_status(expected_content_hash(url), bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://capi.finance/team.json";
+
+    #[test]
+    fn url_with_content_hash_appends_the_hash() {
+        let url = url_with_content_hash(URL, b"team");
+
+        assert_eq!(format!("{URL}#h={}", content_hash(b"team")), url);
+        assert_eq!(
+            Some(content_hash(b"team").as_str()),
+            expected_content_hash(&url)
+        );
+        assert_eq!(URL, url_without_fragment(&url));
+    }
+
+    #[test]
+    fn url_with_content_hash_replaces_an_existing_fragment() {
+        let url = url_with_content_hash(&format!("{URL}#section"), b"team");
+        assert_eq!(format!("{URL}#h={}", content_hash(b"team")), url);
+
+        // updated content: the old hash is replaced
+        let updated = url_with_content_hash(&url, b"new team");
+        assert_eq!(format!("{URL}#h={}", content_hash(b"new team")), updated);
+    }
+
+    #[test]
+    fn expected_content_hash_is_none_without_hash_fragment() {
+        assert_eq!(None, expected_content_hash(URL));
+        assert_eq!(None, expected_content_hash(&format!("{URL}#section")));
+        assert_eq!(None, expected_content_hash(&format!("{URL}#h=")));
+    }
+
+    #[test]
+    fn check_content_hash_compares_with_the_hash_in_the_url() {
+        let url = url_with_content_hash(URL, b"team");
+
+        assert_eq!(ContentStatusJs::Verified, check_content_hash(&url, b"team"));
+        assert_eq!(
+            ContentStatusJs::Changed,
+            check_content_hash(&url, b"changed")
+        );
+        assert_eq!(
+            ContentStatusJs::Unverified,
+            check_content_hash(URL, b"team")
+        );
+    }
+}
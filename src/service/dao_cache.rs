@@ -0,0 +1,389 @@
+//! Session cache for the DAO reads that almost every provider does ([load_dao], [dao_global_state], [dao_investor_state]).
+//!
+//! Entries are keyed by network and DAO (+ investor) and stamped with the round at which they were fetched.
+//! They expire after [TTL_SECS]. Submits call [invalidate_after_submit] with the submitted txs:
+//! this removes the entries of the DAOs and accounts the txs touched, and sets a min round for them,
+//! so responses to requests started before the submit (that may be older than the submitted txs) aren't cached.
+
+use super::tx_message::tx_sender;
+use crate::js::common::SignedTxFromJs;
+use crate::network_profile::{network_profile, NetworkProfileId};
+use algonaut::{
+    algod::v2::Algod,
+    core::Address,
+    transaction::{transaction::TransactionType, SignedTransaction},
+};
+use anyhow::Result;
+use base::flows::create_dao::model::Dao;
+use chrono::{DateTime, Duration, Utc};
+use mbase::{
+    models::{dao_app_id::DaoAppId, dao_id::DaoId},
+    state::{
+        app_state::ApplicationLocalStateError,
+        dao_app_state::{self, CentralAppGlobalState, CentralAppInvestorState},
+    },
+};
+use std::{cell::RefCell, collections::HashMap, hash::Hash, thread::LocalKey};
+
+/// Short enough that changes made outside of this session (other users, other tabs) show up quickly
+const TTL_SECS: i64 = 30;
+
+/// (network, DAO app id)
+type DaoKey = (NetworkProfileId, u64);
+/// (network, investor, DAO app id)
+type InvestorKey = (NetworkProfileId, Address, u64);
+
+struct Entry<V> {
+    value: V,
+    round: u64,
+    fetched_at: DateTime<Utc>,
+}
+
+thread_local! {
+    static DAOS: RefCell<HashMap<DaoKey, Entry<Dao>>> = RefCell::new(HashMap::new());
+    static GLOBAL_STATES: RefCell<HashMap<DaoKey, Entry<CentralAppGlobalState>>> = RefCell::new(HashMap::new());
+    static INVESTOR_STATES: RefCell<HashMap<InvestorKey, Entry<CentralAppInvestorState>>> = RefCell::new(HashMap::new());
+    /// Entries fetched before these rounds are outdated (set on invalidation)
+    static DAO_MIN_ROUNDS: RefCell<HashMap<DaoKey, u64>> = RefCell::new(HashMap::new());
+    static ACCOUNT_MIN_ROUNDS: RefCell<HashMap<(NetworkProfileId, Address), u64>> = RefCell::new(HashMap::new());
+}
+
+/// Cached [base::flows::create_dao::storage::load_dao::load_dao]
+pub async fn load_dao(algod: &Algod, dao_id: DaoId) -> Result<Dao> {
+    let key = (network_profile()?.id, dao_id.0 .0);
+    let min_round = dao_min_round(&key);
+
+    if let Some(dao) = cached(&DAOS, &key, min_round) {
+        return Ok(dao);
+    }
+
+    let round = current_round(algod).await;
+    let dao = base::flows::create_dao::storage::load_dao::load_dao(algod, dao_id).await?;
+    cache(&DAOS, key, &dao, round, min_round);
+
+    Ok(dao)
+}
+
+/// Cached [dao_app_state::dao_global_state]
+pub async fn dao_global_state(algod: &Algod, app_id: DaoAppId) -> Result<CentralAppGlobalState> {
+    let key = (network_profile()?.id, app_id.0);
+    let min_round = dao_min_round(&key);
+
+    if let Some(state) = cached(&GLOBAL_STATES, &key, min_round) {
+        return Ok(state);
+    }
+
+    let round = current_round(algod).await;
+    let state = dao_app_state::dao_global_state(algod, app_id).await?;
+    cache(&GLOBAL_STATES, key, &state, round, min_round);
+
+    Ok(state)
+}
+
+/// Cached [dao_app_state::dao_investor_state]. Only successful results are cached.
+pub async fn dao_investor_state(
+    algod: &Algod,
+    investor: &Address,
+    app_id: DaoAppId,
+) -> Result<CentralAppInvestorState, ApplicationLocalStateError<'static>> {
+    // the error type doesn't allow to return the network profile error: just don't use the cache
+    let network = match network_profile() {
+        Ok(profile) => profile.id,
+        Err(e) => {
+            log::error!("Couldn't get network profile, not using cache: {e:?}");
+            return dao_app_state::dao_investor_state(algod, investor, app_id).await;
+        }
+    };
+    let key = (network, *investor, app_id.0);
+    let min_round =
+        dao_min_round(&(network, app_id.0)).max(account_min_round(&(network, *investor)));
+
+    if let Some(state) = cached(&INVESTOR_STATES, &key, min_round) {
+        return Ok(state);
+    }
+
+    let round = current_round(algod).await;
+    let state = dao_app_state::dao_investor_state(algod, investor, app_id).await?;
+    cache(&INVESTOR_STATES, key, &state, round, min_round);
+
+    Ok(state)
+}
+
+/// To be called after the txs were submitted successfully. Never fails (at worst, entries stay until they expire).
+pub async fn invalidate_after_submit(algod: &Algod, txs: &[SignedTxFromJs]) {
+    let network = match network_profile() {
+        Ok(profile) => profile.id,
+        Err(e) => {
+            log::error!("Couldn't get network profile, not invalidating cache: {e:?}");
+            return;
+        }
+    };
+
+    let txs: Vec<SignedTransaction> = txs
+        .iter()
+        .filter_map(|tx| rmp_serde::from_slice(&tx.blob).ok())
+        .collect();
+
+    let mut app_ids = vec![];
+    let mut accounts = vec![];
+    for tx in &txs {
+        let tx = &tx.transaction;
+        accounts.extend(tx_sender(tx));
+        match &tx.txn_type {
+            TransactionType::Payment(p) => {
+                accounts.push(p.receiver);
+                accounts.extend(p.close_remainder_to);
+            }
+            TransactionType::AssetTransferTransaction(t) => {
+                accounts.push(t.receiver);
+                accounts.extend(t.close_to);
+            }
+            TransactionType::ApplicationCallTransaction(call) => {
+                app_ids.extend(call.app_id);
+                accounts.extend(call.accounts.iter().flatten().cloned());
+            }
+            _ => {}
+        }
+    }
+    // txs to / from a DAO's app account modify the DAO (e.g. its funds)
+    app_ids.extend(
+        cached_app_ids(network)
+            .into_iter()
+            .filter(|app_id| accounts.contains(&DaoAppId(*app_id).address())),
+    );
+
+    // fetched after the submit: entries from older rounds may not contain the changes
+    let min_round = current_round(algod).await;
+
+    log::debug!(
+        "Invalidating cache for apps: {app_ids:?}, accounts: {accounts:?}, min round: {min_round:?}"
+    );
+
+    for app_id in &app_ids {
+        let key = (network, *app_id);
+        DAOS.with(|c| c.borrow_mut().remove(&key));
+        GLOBAL_STATES.with(|c| c.borrow_mut().remove(&key));
+        if let Some(min_round) = min_round {
+            DAO_MIN_ROUNDS.with(|r| r.borrow_mut().insert(key, min_round));
+        }
+    }
+    if let Some(min_round) = min_round {
+        for account in &accounts {
+            ACCOUNT_MIN_ROUNDS.with(|r| r.borrow_mut().insert((network, *account), min_round));
+        }
+    }
+    INVESTOR_STATES.with(|c| {
+        c.borrow_mut().retain(|(n, investor, app_id), _| {
+            *n != network || !(app_ids.contains(app_id) || accounts.contains(investor))
+        })
+    });
+}
+
+/// The app ids of all the cached entries of the network
+fn cached_app_ids(network: NetworkProfileId) -> Vec<u64> {
+    let mut app_ids = vec![];
+    let mut add = |n: &NetworkProfileId, app_id: &u64| {
+        if *n == network && !app_ids.contains(app_id) {
+            app_ids.push(*app_id);
+        }
+    };
+    DAOS.with(|c| c.borrow().keys().for_each(|(n, app_id)| add(n, app_id)));
+    GLOBAL_STATES.with(|c| c.borrow().keys().for_each(|(n, app_id)| add(n, app_id)));
+    INVESTOR_STATES.with(|c| c.borrow().keys().for_each(|(n, _, app_id)| add(n, app_id)));
+    app_ids
+}
+
+fn cached<K: Eq + Hash, V: Clone>(
+    cache: &'static LocalKey<RefCell<HashMap<K, Entry<V>>>>,
+    key: &K,
+    min_round: u64,
+) -> Option<V> {
+    cache.with(|c| {
+        c.borrow()
+            .get(key)
+            .filter(|entry| {
+                entry.round >= min_round
+                    && Utc::now() - entry.fetched_at < Duration::seconds(TTL_SECS)
+            })
+            .map(|entry| entry.value.clone())
+    })
+}
+
+/// `round`: the round before fetching the value. If not known, the value isn't cached.
+fn cache<K: Eq + Hash, V: Clone>(
+    cache: &'static LocalKey<RefCell<HashMap<K, Entry<V>>>>,
+    key: K,
+    value: &V,
+    round: Option<u64>,
+    min_round: u64,
+) {
+    if let Some(round) = round.filter(|r| *r >= min_round) {
+        cache.with(|c| {
+            c.borrow_mut().insert(
+                key,
+                Entry {
+                    value: value.clone(),
+                    round,
+                    fetched_at: Utc::now(),
+                },
+            )
+        });
+    }
+}
+
+fn dao_min_round(key: &DaoKey) -> u64 {
+    DAO_MIN_ROUNDS.with(|r| r.borrow().get(key).cloned().unwrap_or(0))
+}
+
+fn account_min_round(key: &(NetworkProfileId, Address)) -> u64 {
+    ACCOUNT_MIN_ROUNDS.with(|r| r.borrow().get(key).cloned().unwrap_or(0))
+}
+
+async fn current_round(algod: &Algod) -> Option<u64> {
+    match algod.status().await {
+        Ok(status) => Some(status.last_round),
+        Err(e) => {
+            log::error!("Couldn't get current round: {e:?}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dependencies::algod,
+        fake_node::{
+            fixtures::{
+                app_address, dao_chain, payment_txs, set_investor_shares, signed_js_txs,
+                use_fixture_network_profile, CREATOR, DAO_APP_ID, INVESTOR, SHARES_ASSET_ID,
+            },
+            FakeNode,
+        },
+    };
+    use algonaut::transaction::{TransferAsset, TxnBuilder};
+
+    thread_local! {
+        static TEST_CACHE: RefCell<HashMap<u64, Entry<u64>>> = RefCell::new(HashMap::new());
+    }
+
+    fn address(address: &str) -> Address {
+        address.parse().unwrap()
+    }
+
+    /// Caches the global state and the investor's state of the fixture DAO
+    async fn fill_cache(algod: &Algod) -> (DaoKey, InvestorKey) {
+        let app_id = DaoAppId(DAO_APP_ID);
+        dao_global_state(algod, app_id).await.unwrap();
+        dao_investor_state(algod, &address(INVESTOR), app_id)
+            .await
+            .unwrap();
+
+        let network = network_profile().unwrap().id;
+        let dao_key = (network, DAO_APP_ID);
+        let investor_key = (network, address(INVESTOR), DAO_APP_ID);
+        assert!(GLOBAL_STATES.with(|c| c.borrow().contains_key(&dao_key)));
+        assert!(INVESTOR_STATES.with(|c| c.borrow().contains_key(&investor_key)));
+        (dao_key, investor_key)
+    }
+
+    #[test]
+    fn cached_entries_expire_after_the_ttl() {
+        cache(&TEST_CACHE, 1, &10, Some(5), 0);
+        assert_eq!(cached(&TEST_CACHE, &1, 0), Some(10));
+
+        TEST_CACHE.with(|c| {
+            c.borrow_mut().get_mut(&1).unwrap().fetched_at =
+                Utc::now() - Duration::seconds(TTL_SECS + 1)
+        });
+        assert_eq!(cached(&TEST_CACHE, &1, 0), None);
+    }
+
+    #[test]
+    fn entries_older_than_the_min_round_are_not_cached_or_returned() {
+        // fetched before the min round
+        cache(&TEST_CACHE, 1, &10, Some(4), 5);
+        assert_eq!(cached(&TEST_CACHE, &1, 0), None);
+
+        // round unknown
+        cache(&TEST_CACHE, 1, &10, None, 0);
+        assert_eq!(cached(&TEST_CACHE, &1, 0), None);
+
+        cache(&TEST_CACHE, 1, &10, Some(5), 5);
+        assert_eq!(cached(&TEST_CACHE, &1, 5), Some(10));
+        // the min round was raised after caching
+        assert_eq!(cached(&TEST_CACHE, &1, 6), None);
+    }
+
+    #[tokio::test]
+    async fn invalidate_after_submit_removes_the_dao_of_a_payment_to_its_app_account() {
+        let mut chain = dao_chain();
+        set_investor_shares(&mut chain, 10);
+        chain.set_round(100);
+        let _node = FakeNode::start(chain).unwrap();
+        use_fixture_network_profile();
+        let algod = algod();
+        // only the global and investor states are cached (not the DAO)
+        let (dao_key, investor_key) = fill_cache(&algod).await;
+
+        let txs = payment_txs(&algod, &[(CREATOR, app_address().as_str(), 1_000)]).await;
+        invalidate_after_submit(&algod, &signed_js_txs(&txs)).await;
+
+        assert!(!GLOBAL_STATES.with(|c| c.borrow().contains_key(&dao_key)));
+        assert!(!INVESTOR_STATES.with(|c| c.borrow().contains_key(&investor_key)));
+        assert_eq!(dao_min_round(&dao_key), 100);
+        assert_eq!(account_min_round(&(dao_key.0, address(CREATOR))), 100);
+    }
+
+    #[tokio::test]
+    async fn invalidate_after_submit_keeps_the_entries_of_untouched_daos_and_accounts() {
+        let mut chain = dao_chain();
+        set_investor_shares(&mut chain, 10);
+        let _node = FakeNode::start(chain).unwrap();
+        use_fixture_network_profile();
+        let algod = algod();
+        let (dao_key, investor_key) = fill_cache(&algod).await;
+
+        let txs = payment_txs(&algod, &[(CREATOR, CREATOR, 0)]).await;
+        invalidate_after_submit(&algod, &signed_js_txs(&txs)).await;
+
+        assert!(GLOBAL_STATES.with(|c| c.borrow().contains_key(&dao_key)));
+        assert!(INVESTOR_STATES.with(|c| c.borrow().contains_key(&investor_key)));
+    }
+
+    #[tokio::test]
+    async fn invalidate_after_submit_includes_the_close_to_accounts() {
+        let mut chain = dao_chain();
+        set_investor_shares(&mut chain, 10);
+        chain.set_round(100);
+        let _node = FakeNode::start(chain).unwrap();
+        use_fixture_network_profile();
+        let algod = algod();
+        let (dao_key, investor_key) = fill_cache(&algod).await;
+
+        // closes the remainder to the app account
+        let mut payment = payment_txs(&algod, &[(CREATOR, CREATOR, 0)])
+            .await
+            .remove(0);
+        if let TransactionType::Payment(p) = &mut payment.txn_type {
+            p.close_remainder_to = Some(address(&app_address()));
+        }
+        // closes the shares to the investor
+        let params = algod.suggested_transaction_params().await.unwrap();
+        let transfer = TxnBuilder::with(
+            &params,
+            TransferAsset::new(address(CREATOR), SHARES_ASSET_ID, 0, address(CREATOR))
+                .close_to(address(INVESTOR))
+                .build(),
+        )
+        .build()
+        .unwrap();
+
+        invalidate_after_submit(&algod, &signed_js_txs(&[payment, transfer])).await;
+
+        assert!(!GLOBAL_STATES.with(|c| c.borrow().contains_key(&dao_key)));
+        assert!(!INVESTOR_STATES.with(|c| c.borrow().contains_key(&investor_key)));
+        assert_eq!(account_min_round(&(dao_key.0, address(INVESTOR))), 100);
+    }
+}
@@ -1,7 +1,4 @@
-use crate::{
-    dependencies::funds_asset_specs,
-    js::common::{signed_js_tx_to_signed_tx1, SignedTxFromJs},
-};
+use crate::js::common::{signed_js_tx_to_signed_tx1, SignedTxFromJs};
 use algonaut::{algod::v2::Algod, core::Address};
 use anyhow::Result;
 use base::flows::{
@@ -15,6 +12,11 @@ use mbase::{
     util::network_util::wait_for_pending_transaction,
 };
 
+/// Wallet message for the drain tx (moves the DAO's income to its funds, see [drain_if_needed_tx])
+pub fn drain_message(dao: &Dao) -> String {
+    format!("Update the available funds of {}", dao.name)
+}
+
 /// Returns txs if needed to drain, None if not needed.
 pub async fn drain_if_needed_tx(
     algod: &Algod,
@@ -35,14 +37,8 @@ pub async fn drain_if_needed_tx(
         log::debug!("There's an amount to drain: {:?}", to_drain.dao);
 
         Ok(Some(
-            fetch_drain_amount_and_drain(
-                algod,
-                sender,
-                dao.app_id,
-                funds_asset_specs()?.id,
-                capi_deps,
-            )
-            .await?,
+            fetch_drain_amount_and_drain(algod, sender, dao.app_id, funds_asset_id, capi_deps)
+                .await?,
         ))
     } else {
         Ok(None)
@@ -0,0 +1,296 @@
+use algonaut::{
+    crypto::HashDigest,
+    transaction::{SignedTransaction, Transaction},
+};
+use anyhow::{Error, Result};
+use data_encoding::BASE64;
+use serde::Serialize;
+use std::{cell::RefCell, collections::VecDeque, fmt::Display};
+use tsify::Tsify;
+
+/// How many operations (txs handed out together) we remember. Older ones are discarded - they're likely expired anyway (last valid round).
+const MAX_PENDING_OPERATIONS: usize = 20;
+
+thread_local! {
+    /// Txs handed out for signing (txs step), per operation, in the order the operations were started
+    static PENDING_OPERATIONS: RefCell<VecDeque<ExpectedOperation>> = RefCell::new(VecDeque::new());
+}
+
+/// Txs handed out together for signing (a [crate::js::to_sign_js::ToSignJs]), in the order they were handed out.
+/// Identified by the id of its first tx.
+#[derive(Debug, Clone)]
+struct ExpectedOperation {
+    tx_ids: Vec<String>,
+    groups: Vec<String>,
+}
+
+/// The signed txs don't correspond to the txs handed out for signing
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub enum UnexpectedSignedTxError {
+    /// The tx belongs to a group handed out for signing, but its content was changed
+    Modified { tx_id: String, group: String },
+    /// Neither the tx nor its group were handed out for signing (or they were already submitted)
+    Unknown { tx_id: String },
+    /// The txs were handed out for signing, but not all of them came back, or more came back
+    Count { expected: usize, received: usize },
+    /// The txs were handed out for signing, but came back in a different order
+    Order { tx_id: String, index: usize },
+}
+
+impl Display for UnexpectedSignedTxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnexpectedSignedTxError::Modified { tx_id, group } => write!(
+                f,
+                "Signed tx: {tx_id} differs from the tx handed out for signing in group: {group}"
+            ),
+            UnexpectedSignedTxError::Unknown { tx_id } => {
+                write!(f, "Signed tx: {tx_id} wasn't handed out for signing")
+            }
+            UnexpectedSignedTxError::Count { expected, received } => {
+                write!(f, "Expected: {expected} signed txs, received: {received}")
+            }
+            UnexpectedSignedTxError::Order { tx_id, index } => write!(
+                f,
+                "Signed tx: {tx_id} at: {index} isn't in the order it was handed out for signing"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for UnexpectedSignedTxError {}
+
+/// Remembers txs handed out together for signing, so [consume_expected_txs] accepts them when they come back signed
+pub fn expect_txs(txs: &[Transaction]) -> Result<()> {
+    let mut tx_ids = vec![];
+    let mut groups = vec![];
+    for tx in txs {
+        tx_ids.push(tx.id().map_err(Error::msg)?);
+        if let Some(group) = &tx.group {
+            groups.push(group_str(group));
+        }
+    }
+    if tx_ids.is_empty() {
+        return Ok(());
+    }
+
+    PENDING_OPERATIONS.with(|operations| {
+        let mut operations = operations.borrow_mut();
+        operations.push_back(ExpectedOperation { tx_ids, groups });
+        while operations.len() > MAX_PENDING_OPERATIONS {
+            operations.pop_front();
+        }
+    });
+
+    Ok(())
+}
+
+/// Ensures that the signed txs are exactly the txs handed out together for signing (count, order and ids),
+/// and forgets them, so they can't be submitted again.
+/// Note that the tx id covers the group, so a matching id implies a matching group.
+pub fn consume_expected_txs(txs: &[SignedTransaction]) -> Result<()> {
+    let mut tx_ids = vec![];
+    for tx in txs {
+        tx_ids.push(tx.transaction.id().map_err(Error::msg)?);
+    }
+    let first_tx_id = match tx_ids.first() {
+        Some(tx_id) => tx_id,
+        None => return Ok(()),
+    };
+
+    PENDING_OPERATIONS.with(|operations| {
+        let mut operations = operations.borrow_mut();
+
+        let index = match operations
+            .iter()
+            .position(|operation| &operation.tx_ids[0] == first_tx_id)
+        {
+            Some(index) => index,
+            None => return Err(unexpected_tx_error(&operations, &txs[0], first_tx_id).into()),
+        };
+        let operation = &operations[index];
+
+        if operation.tx_ids.len() != tx_ids.len() {
+            return Err(UnexpectedSignedTxError::Count {
+                expected: operation.tx_ids.len(),
+                received: tx_ids.len(),
+            }
+            .into());
+        }
+        for (index, (tx, tx_id)) in txs.iter().zip(&tx_ids).enumerate() {
+            if &operation.tx_ids[index] == tx_id {
+                continue;
+            }
+            return Err(if operation.tx_ids.contains(tx_id) {
+                UnexpectedSignedTxError::Order {
+                    tx_id: tx_id.clone(),
+                    index,
+                }
+            } else {
+                unexpected_tx_error(&operations, tx, tx_id)
+            }
+            .into());
+        }
+
+        operations.remove(index);
+        Ok(())
+    })
+}
+
+fn unexpected_tx_error(
+    operations: &VecDeque<ExpectedOperation>,
+    tx: &SignedTransaction,
+    tx_id: &str,
+) -> UnexpectedSignedTxError {
+    let group = tx.transaction.group.as_ref().map(group_str);
+    match group {
+        Some(group) if operations.iter().any(|o| o.groups.contains(&group)) => {
+            UnexpectedSignedTxError::Modified {
+                tx_id: tx_id.to_owned(),
+                group,
+            }
+        }
+        _ => UnexpectedSignedTxError::Unknown {
+            tx_id: tx_id.to_owned(),
+        },
+    }
+}
+
+fn group_str(group: &HashDigest) -> String {
+    BASE64.encode(&group.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dependencies::algod,
+        fake_node::{
+            fixtures::{payment_txs, signed, CREATOR, INVESTOR},
+            FakeChain, FakeNode,
+        },
+    };
+
+    /// A group of `count` payments, distinct from other groups with a different `seed`
+    async fn group(seed: u64, count: u64) -> Vec<Transaction> {
+        let payments: Vec<(&str, &str, u64)> = (0..count)
+            .map(|index| (CREATOR, INVESTOR, seed * 100 + index))
+            .collect();
+        payment_txs(&algod(), &payments).await
+    }
+
+    fn consume(txs: &[Transaction]) -> Result<()> {
+        consume_expected_txs(&txs.iter().map(signed).collect::<Vec<_>>())
+    }
+
+    fn unexpected_error(res: Result<()>) -> UnexpectedSignedTxError {
+        res.expect_err("Expected an error")
+            .downcast::<UnexpectedSignedTxError>()
+            .expect("Expected UnexpectedSignedTxError")
+    }
+
+    #[tokio::test]
+    async fn consume_accepts_the_handed_out_txs_once() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let txs = group(1, 2).await;
+        expect_txs(&txs).unwrap();
+
+        assert!(consume(&txs).is_ok());
+
+        assert!(matches!(
+            unexpected_error(consume(&txs)),
+            UnexpectedSignedTxError::Unknown { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn consume_fails_with_count_if_txs_are_missing() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let txs = group(1, 3).await;
+        expect_txs(&txs).unwrap();
+
+        let res = consume(&txs[..2]);
+
+        assert!(matches!(
+            unexpected_error(res),
+            UnexpectedSignedTxError::Count {
+                expected: 3,
+                received: 2
+            }
+        ));
+        // failed submits don't consume the operation
+        assert!(consume(&txs).is_ok());
+    }
+
+    #[tokio::test]
+    async fn consume_fails_with_order_if_txs_are_reordered() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let txs = group(1, 3).await;
+        expect_txs(&txs).unwrap();
+
+        let reordered = vec![txs[0].clone(), txs[2].clone(), txs[1].clone()];
+
+        assert!(matches!(
+            unexpected_error(consume(&reordered)),
+            UnexpectedSignedTxError::Order { index: 1, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn consume_fails_with_modified_if_a_tx_of_the_group_was_changed() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let txs = group(1, 2).await;
+        expect_txs(&txs).unwrap();
+
+        let mut modified = group(2, 1).await;
+        modified[0].group = txs[0].group;
+
+        assert!(matches!(
+            unexpected_error(consume(&modified)),
+            UnexpectedSignedTxError::Modified { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn consume_fails_with_unknown_for_txs_that_werent_handed_out() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        expect_txs(&group(1, 2).await).unwrap();
+
+        assert!(matches!(
+            unexpected_error(consume(&group(2, 2).await)),
+            UnexpectedSignedTxError::Unknown { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn consume_consumes_only_the_submitted_operation() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let first = group(1, 2).await;
+        let second = group(2, 1).await;
+        expect_txs(&first).unwrap();
+        expect_txs(&second).unwrap();
+
+        assert!(consume(&second).is_ok());
+
+        assert!(consume(&first).is_ok());
+    }
+
+    #[tokio::test]
+    async fn expect_txs_forgets_the_oldest_operations() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let mut operations = vec![];
+        for seed in 0..(MAX_PENDING_OPERATIONS as u64 + 1) {
+            let txs = group(seed + 1, 1).await;
+            expect_txs(&txs).unwrap();
+            operations.push(txs);
+        }
+
+        assert!(matches!(
+            unexpected_error(consume(&operations[0])),
+            UnexpectedSignedTxError::Unknown { .. }
+        ));
+        assert!(consume(&operations[1]).is_ok());
+        assert!(consume(&operations[MAX_PENDING_OPERATIONS]).is_ok());
+    }
+}
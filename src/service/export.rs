@@ -0,0 +1,302 @@
+//! Shared helpers to export data (e.g. for accounting) as files generated by the frontend.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+/// RFC 5545: lines shouldn't be longer than 75 octets (excluding the line break)
+const ICS_MAX_LINE_OCTETS: usize = 75;
+
+#[derive(Tsify, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Json => "application/json",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// A file to be downloaded by the frontend
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct ExportResJs {
+    pub content: String,
+    pub mime_type: String,
+    pub file_name: String,
+}
+
+impl ExportResJs {
+    /// `file_name` without extension
+    pub fn new(format: ExportFormat, file_name: &str, content: String) -> ExportResJs {
+        ExportResJs {
+            content,
+            mime_type: format.mime_type().to_owned(),
+            file_name: format!("{file_name}.{}", format.extension()),
+        }
+    }
+
+    /// iCalendar file ([to_ics]). `file_name` without extension.
+    pub fn calendar(file_name: &str, content: String) -> ExportResJs {
+        ExportResJs {
+            content,
+            mime_type: "text/calendar".to_owned(),
+            file_name: format!("{file_name}.ics"),
+        }
+    }
+}
+
+/// RFC 4180 CSV (with CRLF line breaks)
+pub fn to_csv(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut lines = vec![headers
+        .iter()
+        .map(|h| escape_csv_field(h))
+        .collect::<Vec<_>>()
+        .join(",")];
+    for row in rows {
+        lines.push(
+            row.iter()
+                .map(|f| escape_csv_field(f))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+    lines.join("\r\n") + "\r\n"
+}
+
+/// Fields starting with a formula character are prefixed with `'`, so spreadsheets don't evaluate them
+/// (the fields contain user controlled text, e.g. notes or asset names). Negative numbers are kept as they are.
+fn escape_csv_field(field: &str) -> String {
+    let is_formula = field.starts_with(|c| matches!(c, '=' | '+' | '-' | '@' | '\t' | '\r'))
+        && field.parse::<f64>().is_err();
+    let field = if is_formula {
+        format!("'{field}")
+    } else {
+        field.to_owned()
+    };
+    if field.contains(|c| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// An all-day calendar event
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    /// Globally unique, stable across exports (so calendar apps update the event instead of duplicating it)
+    pub uid: String,
+    pub date: NaiveDate,
+    pub summary: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+}
+
+/// RFC 5545 iCalendar document (with CRLF line breaks)
+pub fn to_ics(
+    calendar_name: &str,
+    events: &[CalendarEvent],
+    timestamp: DateTime<Utc>,
+) -> Result<String> {
+    let dtstamp = timestamp.format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//Capi//Roadmap//EN".to_owned(),
+        "CALSCALE:GREGORIAN".to_owned(),
+        "METHOD:PUBLISH".to_owned(),
+        format!("X-WR-CALNAME:{}", escape_ics_text(calendar_name)),
+    ];
+    for event in events {
+        let end = event
+            .date
+            .succ_opt()
+            .ok_or_else(|| anyhow!("Invalid event date: {}", event.date))?;
+
+        lines.push("BEGIN:VEVENT".to_owned());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{dtstamp}"));
+        // all-day: the end date is exclusive
+        lines.push(format!(
+            "DTSTART;VALUE=DATE:{}",
+            event.date.format("%Y%m%d")
+        ));
+        lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+        lines.push(format!("SUMMARY:{}", escape_ics_text(&event.summary)));
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape_ics_text(description)));
+        }
+        if let Some(url) = &event.url {
+            // a URI value isn't escaped: line breaks would inject properties
+            if url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+                return Err(anyhow!("Invalid event url: {url:?}"));
+            }
+            lines.push(format!("URL:{url}"));
+        }
+        lines.push("END:VEVENT".to_owned());
+    }
+    lines.push("END:VCALENDAR".to_owned());
+
+    Ok(lines
+        .iter()
+        .map(|l| fold_ics_line(l))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n")
+}
+
+fn escape_ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+        .replace('\r', "\\n")
+}
+
+/// Splits long lines into a line and continuation lines (starting with a space), without splitting characters
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut line_octets = 0;
+    for c in line.chars() {
+        if line_octets + c.len_utf8() > ICS_MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            line_octets = 1;
+        }
+        folded.push(c);
+        line_octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn to_csv_escapes_separators_quotes_and_line_breaks() {
+        let csv = to_csv(
+            &["a", "b"],
+            &[vec!["1,5".to_owned(), "say \"hi\"\nbye".to_owned()]],
+        );
+
+        assert_eq!("a,b\r\n\"1,5\",\"say \"\"hi\"\"\nbye\"\r\n", csv);
+    }
+
+    #[test]
+    fn to_csv_neutralizes_formulas_but_keeps_negative_numbers() {
+        let csv = to_csv(
+            &["a", "b", "c"],
+            &[vec![
+                "=HYPERLINK(\"x\")".to_owned(),
+                "-12.5".to_owned(),
+                "@SUM(A1)".to_owned(),
+            ]],
+        );
+
+        assert_eq!(
+            "a,b,c\r\n\"'=HYPERLINK(\"\"x\"\")\",-12.5,'@SUM(A1)\r\n",
+            csv
+        );
+    }
+
+    #[test]
+    fn fold_ics_line_keeps_short_lines() {
+        let line = "SUMMARY:Short";
+        assert_eq!(line, fold_ics_line(line));
+    }
+
+    #[test]
+    fn fold_ics_line_folds_at_75_octets_without_splitting_chars() {
+        // 74 ascii + a 2 octets char: the char doesn't fit in the first line
+        let line = format!("{}é{}", "a".repeat(74), "b".repeat(10));
+
+        let folded = fold_ics_line(&line);
+
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(2, lines.len());
+        assert_eq!("a".repeat(74), lines[0]);
+        assert_eq!(format!(" é{}", "b".repeat(10)), lines[1]);
+        assert!(lines.iter().all(|l| l.len() <= ICS_MAX_LINE_OCTETS));
+    }
+
+    #[test]
+    fn to_ics_writes_all_day_events_with_exclusive_end() {
+        let ics = to_ics(
+            "Roadmap",
+            &[CalendarEvent {
+                uid: "item-1@capi".to_owned(),
+                date: NaiveDate::from_ymd(2022, 12, 31),
+                summary: "Launch; v1, finally".to_owned(),
+                description: Some("Line 1\nLine 2".to_owned()),
+                url: None,
+            }],
+            Utc.ymd(2022, 1, 2).and_hms(3, 4, 5),
+        )
+        .unwrap();
+
+        let lines: Vec<&str> = ics.split("\r\n").collect();
+        assert!(lines.contains(&"UID:item-1@capi"));
+        assert!(lines.contains(&"DTSTAMP:20220102T030405Z"));
+        assert!(lines.contains(&"DTSTART;VALUE=DATE:20221231"));
+        assert!(lines.contains(&"DTEND;VALUE=DATE:20230101"));
+        assert!(lines.contains(&"SUMMARY:Launch\\; v1\\, finally"));
+        assert!(lines.contains(&"DESCRIPTION:Line 1\\nLine 2"));
+        assert!(!ics.contains("URL:"));
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn to_ics_writes_the_url() {
+        let ics = to_ics(
+            "Roadmap",
+            &[event(Some("https://app.capi.finance/dao/1"))],
+            now(),
+        )
+        .unwrap();
+
+        assert!(ics.contains("\r\nURL:https://app.capi.finance/dao/1\r\n"));
+    }
+
+    #[test]
+    fn to_ics_rejects_urls_with_line_breaks_or_whitespace() {
+        for url in [
+            "https://capi.finance\r\nATTACH:https://evil.com",
+            "https://capi.finance\nX-INJECTED:1",
+            "https://capi.finance x",
+        ] {
+            assert!(to_ics("Roadmap", &[event(Some(url))], now()).is_err());
+        }
+    }
+
+    fn event(url: Option<&str>) -> CalendarEvent {
+        CalendarEvent {
+            uid: "item-1@capi".to_owned(),
+            date: NaiveDate::from_ymd(2022, 12, 31),
+            summary: "Launch".to_owned(),
+            description: None,
+            url: url.map(|u| u.to_owned()),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.ymd(2022, 1, 2).and_hms(3, 4, 5)
+    }
+}
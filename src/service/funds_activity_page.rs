@@ -0,0 +1,196 @@
+//! Paginated funds activity of a DAO, queried directly from the indexer.
+//!
+//! Unlike [base::queries::funds_activity::funds_activity], which loads the whole history,
+//! this fetches one indexer page at a time and returns the indexer's next token as cursor.
+//! The date range is passed to the indexer, type, counterparty and min amount are filtered locally,
+//! so a page can have less entries than requested (only a missing cursor means that there are no more entries).
+//! The min amount can't be passed to the indexer: its amount filter applies only to the root tx,
+//! which would drop the withdrawals (done in inner txs of the app call).
+//!
+//! The export goes through all the pages ([funds_activity_all]), so both use the same classification ([to_entries]).
+
+use super::indexer_history::{tx_date, PAGE_SIZE};
+use algonaut::{
+    core::Address,
+    indexer::v2::Indexer,
+    model::indexer::v2::{QueryAccountTransaction, Transaction},
+};
+use anyhow::{Error, Result};
+use base::{
+    flows::{create_dao::model::Dao, drain::drain::calculate_dao_and_capi_escrow_xfer_amounts},
+    queries::funds_activity::FundsActivityEntryType,
+};
+use chrono::{DateTime, Utc};
+use data_encoding::BASE64;
+use mbase::models::{capi_deps::CapiAssetDaoDeps, funds::FundsAmount, tx_id::TxId};
+
+#[derive(Debug, Clone, Default)]
+pub struct FundsActivityFilter {
+    pub type_: Option<FundsActivityEntryType>,
+    /// Inclusive
+    pub after: Option<DateTime<Utc>>,
+    /// Exclusive
+    pub before: Option<DateTime<Utc>>,
+    /// The sender of income / receiver of spending
+    pub counterparty: Option<Address>,
+    pub min_amount: Option<FundsAmount>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FundsActivityPageEntry {
+    pub type_: FundsActivityEntryType,
+    pub amount: FundsAmount,
+    /// Capi fee (income only)
+    pub fee: FundsAmount,
+    pub description: String,
+    pub date: DateTime<Utc>,
+    pub tx_id: TxId,
+    pub address: Address,
+}
+
+#[derive(Debug, Clone)]
+pub struct FundsActivityPage {
+    /// Most recent first
+    pub entries: Vec<FundsActivityPageEntry>,
+    /// Pass this to get the next page. None if this is the last page.
+    pub next: Option<String>,
+}
+
+pub async fn funds_activity_page(
+    indexer: &Indexer,
+    dao: &Dao,
+    capi_deps: &CapiAssetDaoDeps,
+    filter: &FundsActivityFilter,
+    limit: u64,
+    cursor: Option<String>,
+) -> Result<FundsActivityPage> {
+    let app_address = dao.app_address();
+
+    // the account's transactions are returned most recent first
+    let res = indexer
+        .account_transactions(
+            &app_address,
+            &QueryAccountTransaction {
+                asset_id: Some(dao.funds_asset_id.0),
+                after_time: filter.after.map(|d| d.to_rfc3339()),
+                before_time: filter.before.map(|d| d.to_rfc3339()),
+                limit: Some(limit),
+                next: cursor,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+    let mut entries = vec![];
+    for tx in &res.transactions {
+        for entry in to_entries(tx, &app_address, dao, capi_deps)? {
+            if matches_filter(&entry, filter) {
+                entries.push(entry);
+            }
+        }
+    }
+
+    // the indexer returns a next token with every non-empty page: a page that isn't full is the last one
+    let is_last_page = (res.transactions.len() as u64) < limit;
+
+    Ok(FundsActivityPage {
+        entries,
+        next: if is_last_page { None } else { res.next_token },
+    })
+}
+
+/// The complete funds activity of the DAO, most recent first
+pub async fn funds_activity_all(
+    indexer: &Indexer,
+    dao: &Dao,
+    capi_deps: &CapiAssetDaoDeps,
+) -> Result<Vec<FundsActivityPageEntry>> {
+    let filter = FundsActivityFilter::default();
+    let mut entries = vec![];
+    let mut cursor = None;
+    loop {
+        let page = funds_activity_page(indexer, dao, capi_deps, &filter, PAGE_SIZE, cursor).await?;
+        entries.extend(page.entries);
+        match page.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    Ok(entries)
+}
+
+/// Funds asset transfers to (income) or from (spending) the app, in the tx or its inner txs.
+/// Transfers to Capi (drains) aren't DAO spending, so they're skipped.
+fn to_entries(
+    tx: &Transaction,
+    app_address: &Address,
+    dao: &Dao,
+    capi_deps: &CapiAssetDaoDeps,
+) -> Result<Vec<FundsActivityPageEntry>> {
+    let date = tx_date(tx)?;
+    // withdrawals store the description in the note of the app call
+    let description = tx
+        .note
+        .as_ref()
+        .and_then(|note| BASE64.decode(note.as_bytes()).ok())
+        .map(|note| String::from_utf8_lossy(&note).into_owned())
+        .unwrap_or_default();
+
+    let mut entries = vec![];
+    for t in std::iter::once(tx).chain(tx.inner_txns.iter().flatten()) {
+        let xfer = match &t.asset_transfer_transaction {
+            Some(xfer) if xfer.asset_id == dao.funds_asset_id.0 => xfer,
+            _ => continue,
+        };
+        let sender = t.sender.to_string();
+        let receiver = xfer.receiver.to_string();
+        let amount = FundsAmount::new(xfer.amount);
+
+        if receiver == app_address.to_string() {
+            entries.push(FundsActivityPageEntry {
+                type_: FundsActivityEntryType::Income,
+                amount,
+                fee: calculate_dao_and_capi_escrow_xfer_amounts(
+                    amount,
+                    capi_deps.escrow_percentage,
+                )?
+                .capi,
+                description: "".to_owned(),
+                date,
+                tx_id: tx.id.parse()?,
+                address: sender.parse().map_err(Error::msg)?,
+            });
+        } else if sender == app_address.to_string() && receiver != capi_deps.address.0.to_string() {
+            entries.push(FundsActivityPageEntry {
+                type_: FundsActivityEntryType::Spending,
+                amount,
+                fee: FundsAmount::new(0),
+                description: description.clone(),
+                date,
+                tx_id: tx.id.parse()?,
+                address: receiver.parse().map_err(Error::msg)?,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+fn matches_filter(entry: &FundsActivityPageEntry, filter: &FundsActivityFilter) -> bool {
+    filter.type_.as_ref().map_or(true, |t| {
+        matches!(
+            (t, &entry.type_),
+            (
+                FundsActivityEntryType::Income,
+                FundsActivityEntryType::Income
+            ) | (
+                FundsActivityEntryType::Spending,
+                FundsActivityEntryType::Spending
+            )
+        )
+    }) && filter
+        .counterparty
+        .map_or(true, |address| address == entry.address)
+        && filter
+            .min_amount
+            .map_or(true, |min| entry.amount.val() >= min.val())
+}
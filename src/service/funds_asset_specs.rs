@@ -0,0 +1,56 @@
+use crate::{
+    dependencies::{funds_asset_id, FundsAssetSpecs},
+    network_profile::{network_profile, NetworkProfileId},
+};
+use algonaut::algod::v2::Algod;
+use anyhow::{Error, Result};
+use mbase::models::funds::FundsAssetId;
+use std::{cell::RefCell, collections::HashMap, convert::TryInto};
+
+thread_local! {
+    /// Asset params are immutable (decimals, name), so the cache doesn't expire.
+    /// Keyed by network too, as the same asset id can refer to different assets on different networks.
+    static SPECS_CACHE: RefCell<HashMap<(NetworkProfileId, u64), FundsAssetSpecs>> = RefCell::new(HashMap::new());
+}
+
+/// Specs of the default funds asset (the one new DAOs are created with, if they don't specify one)
+pub async fn default_funds_asset_specs(algod: &Algod) -> Result<FundsAssetSpecs> {
+    funds_asset_specs_for(algod, funds_asset_id()?).await
+}
+
+/// Specs of a funds asset, retrieved from its on-chain params.
+/// Use this with the DAO's funds asset id to format its amounts.
+pub async fn funds_asset_specs_for(algod: &Algod, id: FundsAssetId) -> Result<FundsAssetSpecs> {
+    let key = (network_profile()?.id, id.0);
+
+    if let Some(specs) = SPECS_CACHE.with(|c| c.borrow().get(&key).cloned()) {
+        return Ok(specs);
+    }
+
+    let asset = algod.asset_information(id.0).await?;
+
+    let specs = FundsAssetSpecs {
+        id,
+        decimals: asset.params.decimals.try_into()?,
+        unit_name: asset.params.unit_name.unwrap_or_default(),
+        name: asset.params.name.unwrap_or_default(),
+    };
+    log::debug!("Fetched funds asset specs: {specs:?}");
+
+    SPECS_CACHE.with(|c| c.borrow_mut().insert(key, specs.clone()));
+
+    Ok(specs)
+}
+
+/// Specs for an optional funds asset id entered / passed by the frontend, falling back to the default funds asset
+pub async fn funds_asset_specs_for_input(
+    algod: &Algod,
+    id: Option<&str>,
+) -> Result<FundsAssetSpecs> {
+    match id {
+        Some(id) => {
+            funds_asset_specs_for(algod, FundsAssetId(id.parse().map_err(Error::msg)?)).await
+        }
+        None => default_funds_asset_specs(algod).await,
+    }
+}
@@ -0,0 +1,525 @@
+//! Shares holders over time, reconstructed from the DAO's transaction history.
+//!
+//! Bought shares are locked in the app directly, without a shares transfer, so the holdings are tracked per account
+//! as free (held by the account) + locked (in the app), see [apply_group]. Accounts with free + locked > 0 are holders.
+//! The app itself isn't a holder.
+//!
+//! The replayed history is cached per DAO until a settled date (see [SETTLED_DELAY_MINS]),
+//! so each call only fetches the transactions after it.
+
+use super::indexer_history::{account_txs_in_range, asset_txs_in_range, group_txs, tx_date};
+use crate::network_profile::{network_profile, NetworkProfileId};
+use algonaut::{indexer::v2::Indexer, model::indexer::v2::Transaction};
+use anyhow::Result;
+use base::flows::create_dao::model::Dao;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use data_encoding::BASE64;
+use futures::try_join;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    iter,
+};
+
+/// Transactions confirmed in the last minutes may not be in the indexer yet, so they aren't cached but fetched on each call
+const SETTLED_DELAY_MINS: i64 = 5;
+
+thread_local! {
+    /// (network, DAO app id) -> history replayed until its settled date
+    static SETTLED: RefCell<HashMap<(NetworkProfileId, u64), Replay>> = RefCell::new(HashMap::new());
+}
+
+/// Holders count after each of the DAO's transaction groups
+pub struct HoldersHistory {
+    /// (group date, holders count after the group), chronological
+    counts: Vec<(DateTime<Utc>, usize)>,
+}
+
+impl HoldersHistory {
+    /// Holders count before `date` (groups at `date` aren't included)
+    pub fn count_before(&self, date: DateTime<Utc>) -> usize {
+        match self.counts.partition_point(|(d, _)| *d < date) {
+            0 => 0,
+            index => self.counts[index - 1].1,
+        }
+    }
+
+    /// Current holders count
+    pub fn count(&self) -> usize {
+        self.counts.last().map_or(0, |(_, count)| *count)
+    }
+}
+
+/// The DAO's complete history (app txs and shares transfers) until `until`.
+/// Starts from the cached settled history, if there's one, fetching only the transactions after it.
+pub async fn holders_history(
+    indexer: &Indexer,
+    dao: &Dao,
+    until: DateTime<Utc>,
+) -> Result<HoldersHistory> {
+    let key = (network_profile()?.id, dao.app_id.0);
+    let mut replay = SETTLED
+        .with(|settled| settled.borrow().get(&key).cloned())
+        .unwrap_or_else(Replay::new);
+
+    if until > replay.until {
+        let start = replay.until;
+        let settled = until.min(Utc::now() - Duration::minutes(SETTLED_DELAY_MINS));
+        let app_address = dao.app_address().to_string();
+
+        let mut to_cache = None;
+        for group in fetch_groups(indexer, dao, start, until).await? {
+            let date = tx_date(&group[0])?;
+            if date >= settled && to_cache.is_none() {
+                to_cache = Some(replay.settled_at(settled));
+            }
+            apply_group(&group, dao, &app_address, &mut replay.holdings);
+            replay.counts.push((date, replay.holdings.holders));
+        }
+
+        let to_cache = to_cache.unwrap_or_else(|| replay.settled_at(settled));
+        if to_cache.until > start {
+            SETTLED.with(|cached| cached.borrow_mut().insert(key, to_cache));
+        }
+    }
+
+    let mut counts = replay.counts;
+    // the cached history can be past `until`
+    counts.retain(|(date, _)| *date < until);
+    Ok(HoldersHistory { counts })
+}
+
+/// The DAO's groups in a time range (`after` inclusive, `before` exclusive), chronological
+async fn fetch_groups(
+    indexer: &Indexer,
+    dao: &Dao,
+    after: DateTime<Utc>,
+    before: DateTime<Utc>,
+) -> Result<Vec<Vec<Transaction>>> {
+    let (app_txs, shares_txs) = try_join!(
+        account_txs_in_range(indexer, &dao.app_address(), after, before),
+        asset_txs_in_range(indexer, dao.shares_asset_id, after, before),
+    )?;
+
+    // shares transfers from / to the app are in both
+    let mut ids = HashSet::new();
+    let mut txs: Vec<Transaction> = app_txs
+        .into_iter()
+        .chain(shares_txs)
+        .filter(|tx| ids.insert(tx.id.clone()))
+        .collect();
+    // most recent first, as returned by the indexer
+    txs.sort_by(|tx1, tx2| tx2.round_time.cmp(&tx1.round_time));
+
+    Ok(group_txs(txs))
+}
+
+/// Holdings and counts after replaying the history until `until` (exclusive)
+#[derive(Clone)]
+struct Replay {
+    holdings: Holdings,
+    counts: Vec<(DateTime<Utc>, usize)>,
+    until: DateTime<Utc>,
+}
+
+impl Replay {
+    fn new() -> Replay {
+        Replay {
+            holdings: Holdings::default(),
+            counts: vec![],
+            // the DAO's assets are created before the app, so we can't start at the setup date
+            until: Utc.timestamp(0, 0),
+        }
+    }
+
+    fn settled_at(&self, until: DateTime<Utc>) -> Replay {
+        Replay {
+            until,
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+struct Holdings {
+    /// account -> (free, locked). Can be negative for the creator, who gets the supply without a transfer.
+    by_account: HashMap<String, (i128, i128)>,
+    holders: usize,
+}
+
+impl Holdings {
+    fn add(&mut self, account: &str, free: i128, locked: i128) {
+        let entry = self.by_account.entry(account.to_owned()).or_default();
+        let was_holder = entry.0 + entry.1 > 0;
+        entry.0 += free;
+        entry.1 += locked;
+        let is_holder = entry.0 + entry.1 > 0;
+
+        match (was_holder, is_holder) {
+            (false, true) => self.holders += 1,
+            (true, false) => self.holders -= 1,
+            _ => {}
+        }
+    }
+
+    fn free(&self, account: &str) -> i128 {
+        self.by_account.get(account).map_or(0, |(free, _)| *free)
+    }
+}
+
+/// Updates the holdings with the shares movements of a group:
+/// - investor -> app: lock, or reclaim if the app pays the investor in the same group
+/// - app -> investor: unlock
+/// - account -> account: transfer (with close-to, the account's remaining shares go to the close-to account)
+/// - investor calls the app to invest, without moving shares: purchase (locked directly), see [group_purchases]
+fn apply_group(group: &[Transaction], dao: &Dao, app_address: &str, holdings: &mut Holdings) {
+    let txs: Vec<&Transaction> = group_txs_with_inner(group).collect();
+
+    let mut paid_by_app: HashSet<String> = HashSet::new();
+    for tx in &txs {
+        if let Some(xfer) = &tx.asset_transfer_transaction {
+            if xfer.asset_id == dao.funds_asset_id.0 && tx.sender.to_string() == app_address {
+                paid_by_app.insert(xfer.receiver.to_string());
+            }
+        }
+    }
+
+    let mut moved_shares: HashSet<String> = HashSet::new();
+    for tx in &txs {
+        let xfer = match &tx.asset_transfer_transaction {
+            Some(xfer) if xfer.asset_id == dao.shares_asset_id => xfer,
+            _ => continue,
+        };
+        let sender = tx.sender.to_string();
+        let receiver = xfer.receiver.to_string();
+        let amount = i128::from(xfer.amount);
+
+        if receiver == app_address {
+            if paid_by_app.contains(&sender) {
+                holdings.add(&sender, -amount, 0);
+            } else {
+                holdings.add(&sender, -amount, amount);
+            }
+        } else if sender == app_address {
+            holdings.add(&receiver, amount, -amount);
+        } else {
+            holdings.add(&sender, -amount, 0);
+            holdings.add(&receiver, amount, 0);
+            if let Some(close_to) = &xfer.close_to {
+                let remaining = holdings.free(&sender);
+                holdings.add(&sender, -remaining, 0);
+                holdings.add(&close_to.to_string(), remaining, 0);
+            }
+        }
+        moved_shares.insert(sender);
+        moved_shares.insert(receiver);
+    }
+
+    for purchase in group_purchases(group, dao) {
+        if !moved_shares.contains(&purchase.investor) {
+            holdings.add(&purchase.investor, 0, i128::from(purchase.shares));
+        }
+    }
+}
+
+/// First argument of the DAO app's invest call, followed by the bought shares (big endian u64)
+const INVEST_ARG: &[u8] = b"invest";
+
+/// Shares bought by an investor in a group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Purchase {
+    pub investor: String,
+    pub shares: u64,
+    /// Funds paid by the investor to the app in the group
+    pub paid: u64,
+}
+
+/// The group's invest calls to the DAO's app.
+/// The shares are taken from the call's arguments: they're locked directly, without a shares transfer.
+pub fn group_purchases(group: &[Transaction], dao: &Dao) -> Vec<Purchase> {
+    let app_address = dao.app_address().to_string();
+
+    let mut purchases = vec![];
+    for tx in group {
+        let call = match &tx.application_transaction {
+            Some(call) if call.application_id == dao.app_id.0 => call,
+            _ => continue,
+        };
+        let args: Vec<Vec<u8>> = call
+            .application_args
+            .iter()
+            .flatten()
+            .filter_map(|arg| BASE64.decode(arg.as_bytes()).ok())
+            .collect();
+        let shares = match args.as_slice() {
+            [first, shares, ..] if first == INVEST_ARG => match shares.as_slice().try_into() {
+                Ok(bytes) => u64::from_be_bytes(bytes),
+                Err(_) => continue,
+            },
+            _ => continue,
+        };
+
+        let investor = tx.sender.to_string();
+        let paid = group_txs_with_inner(group)
+            .filter_map(|tx| {
+                let xfer = tx.asset_transfer_transaction.as_ref()?;
+                (xfer.asset_id == dao.funds_asset_id.0
+                    && tx.sender.to_string() == investor
+                    && xfer.receiver.to_string() == app_address)
+                    .then(|| xfer.amount)
+            })
+            .sum();
+
+        purchases.push(Purchase {
+            investor,
+            shares,
+            paid,
+        });
+    }
+    purchases
+}
+
+fn group_txs_with_inner(group: &[Transaction]) -> impl Iterator<Item = &Transaction> {
+    group
+        .iter()
+        .flat_map(|tx| iter::once(tx).chain(tx.inner_txns.iter().flatten()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dependencies::algod,
+        fake_node::{
+            fixtures::{
+                app_address, dao_chain, use_fixture_network_profile, CAPI_ADDRESS, CREATOR,
+                DAO_APP_ID, FUNDS_ASSET_ID, INVESTOR, SHARES_ASSET_ID, SHARE_PRICE,
+            },
+            FakeNode, FakeTx,
+        },
+        service::dao_cache::load_dao,
+    };
+    use mbase::models::{dao_app_id::DaoAppId, dao_id::DaoId};
+    use serde_json::json;
+
+    #[test]
+    fn holdings_add_counts_accounts_with_free_or_locked_shares() {
+        let mut holdings = Holdings::default();
+
+        holdings.add("a", 10, 0);
+        holdings.add("b", 0, 5);
+        assert_eq!(2, holdings.holders);
+
+        // lock: still a holder
+        holdings.add("a", -10, 10);
+        assert_eq!(2, holdings.holders);
+        assert_eq!(0, holdings.free("a"));
+
+        // reclaim: the shares go back to the app
+        holdings.add("b", 0, -5);
+        assert_eq!(1, holdings.holders);
+    }
+
+    #[test]
+    fn holdings_add_ignores_negative_holdings() {
+        let mut holdings = Holdings::default();
+
+        // the creator gets the supply without a transfer, so sending it makes them negative
+        holdings.add("creator", -100, 0);
+        assert_eq!(0, holdings.holders);
+
+        holdings.add("creator", 100, 0);
+        assert_eq!(0, holdings.holders);
+
+        holdings.add("creator", 1, 0);
+        assert_eq!(1, holdings.holders);
+    }
+
+    #[test]
+    fn count_before_excludes_groups_at_the_date() {
+        let date = |secs| Utc.timestamp(secs, 0);
+        let history = HoldersHistory {
+            counts: vec![(date(10), 1), (date(20), 2), (date(30), 1)],
+        };
+
+        assert_eq!(0, history.count_before(date(10)));
+        assert_eq!(1, history.count_before(date(20)));
+        assert_eq!(2, history.count_before(date(25)));
+        assert_eq!(1, history.count());
+    }
+
+    const TIME: i64 = 1_650_000_000;
+
+    async fn fixture_dao() -> Dao {
+        let _node = FakeNode::start(dao_chain()).unwrap();
+        use_fixture_network_profile();
+        load_dao(&algod(), DaoId(DaoAppId(DAO_APP_ID)))
+            .await
+            .unwrap()
+    }
+
+    fn tx(fake: FakeTx) -> Transaction {
+        serde_json::from_value(fake.0).unwrap()
+    }
+
+    fn shares_transfer(sender: &str, receiver: &str, amount: u64) -> FakeTx {
+        FakeTx::asset_transfer("", sender, receiver, SHARES_ASSET_ID, amount, TIME)
+    }
+
+    fn app_call(arg: &[u8]) -> FakeTx {
+        FakeTx::app_call("", INVESTOR, DAO_APP_ID, &[arg], TIME)
+    }
+
+    fn invest_call(shares: u64) -> FakeTx {
+        FakeTx::app_call(
+            "",
+            INVESTOR,
+            DAO_APP_ID,
+            &[INVEST_ARG, &shares.to_be_bytes()],
+            TIME,
+        )
+    }
+
+    fn apply(dao: &Dao, group: Vec<FakeTx>, holdings: &mut Holdings) {
+        let group: Vec<Transaction> = group.into_iter().map(tx).collect();
+        apply_group(&group, dao, &app_address(), holdings);
+    }
+
+    fn split(holdings: &Holdings, account: &str) -> (i128, i128) {
+        holdings
+            .by_account
+            .get(account)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn apply_group_lock_moves_free_shares_to_locked() {
+        let dao = fixture_dao().await;
+        let mut holdings = Holdings::default();
+        holdings.add(INVESTOR, 3, 0);
+
+        apply(
+            &dao,
+            vec![
+                app_call(b"lock"),
+                shares_transfer(INVESTOR, &app_address(), 3),
+            ],
+            &mut holdings,
+        );
+
+        assert_eq!((0, 3), split(&holdings, INVESTOR));
+        assert_eq!(1, holdings.holders);
+    }
+
+    #[tokio::test]
+    async fn apply_group_unlock_moves_locked_shares_to_free() {
+        let dao = fixture_dao().await;
+        let mut holdings = Holdings::default();
+        holdings.add(INVESTOR, 0, 5);
+
+        apply(
+            &dao,
+            vec![app_call(b"unlock").with(
+                "inner-txns",
+                json!([shares_transfer(&app_address(), INVESTOR, 5).0]),
+            )],
+            &mut holdings,
+        );
+
+        assert_eq!((5, 0), split(&holdings, INVESTOR));
+        assert_eq!(1, holdings.holders);
+    }
+
+    #[tokio::test]
+    async fn apply_group_reclaim_removes_the_shares() {
+        let dao = fixture_dao().await;
+        let mut holdings = Holdings::default();
+        holdings.add(INVESTOR, 2, 0);
+
+        let funds_from_app = FakeTx::asset_transfer(
+            "",
+            &app_address(),
+            INVESTOR,
+            FUNDS_ASSET_ID,
+            2 * SHARE_PRICE,
+            TIME,
+        );
+        apply(
+            &dao,
+            vec![
+                app_call(b"reclaim").with("inner-txns", json!([funds_from_app.0])),
+                shares_transfer(INVESTOR, &app_address(), 2),
+            ],
+            &mut holdings,
+        );
+
+        // not locked: the shares went back to the app
+        assert_eq!((0, 0), split(&holdings, INVESTOR));
+        assert_eq!(0, holdings.holders);
+    }
+
+    #[tokio::test]
+    async fn apply_group_transfer_with_close_to_moves_the_remaining_shares() {
+        let dao = fixture_dao().await;
+        let mut holdings = Holdings::default();
+        holdings.add(CREATOR, 10, 0);
+
+        let mut transfer = shares_transfer(CREATOR, INVESTOR, 1);
+        transfer.0["asset-transfer-transaction"]["close-to"] = json!(CAPI_ADDRESS);
+        apply(&dao, vec![transfer], &mut holdings);
+
+        assert_eq!((0, 0), split(&holdings, CREATOR));
+        assert_eq!((1, 0), split(&holdings, INVESTOR));
+        assert_eq!((9, 0), split(&holdings, CAPI_ADDRESS));
+        assert_eq!(2, holdings.holders);
+    }
+
+    #[tokio::test]
+    async fn apply_group_invest_locks_the_bought_shares_without_transfer() {
+        let dao = fixture_dao().await;
+        let mut holdings = Holdings::default();
+
+        let payment = FakeTx::asset_transfer(
+            "",
+            INVESTOR,
+            &app_address(),
+            FUNDS_ASSET_ID,
+            5 * SHARE_PRICE,
+            TIME,
+        );
+        let group: Vec<Transaction> = vec![tx(invest_call(5)), tx(payment)];
+
+        assert_eq!(
+            vec![Purchase {
+                investor: INVESTOR.to_owned(),
+                shares: 5,
+                paid: 5 * SHARE_PRICE,
+            }],
+            group_purchases(&group, &dao)
+        );
+
+        apply_group(&group, &dao, &app_address(), &mut holdings);
+        assert_eq!((0, 5), split(&holdings, INVESTOR));
+        assert_eq!(1, holdings.holders);
+    }
+
+    #[tokio::test]
+    async fn group_purchases_ignores_other_calls_and_apps() {
+        let dao = fixture_dao().await;
+
+        let group: Vec<Transaction> = vec![
+            tx(app_call(b"claim")),
+            tx(FakeTx::app_call(
+                "",
+                INVESTOR,
+                DAO_APP_ID + 1,
+                &[INVEST_ARG, &5u64.to_be_bytes()],
+                TIME,
+            )),
+        ];
+
+        assert!(group_purchases(&group, &dao).is_empty());
+    }
+}
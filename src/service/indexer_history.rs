@@ -0,0 +1,106 @@
+use algonaut::{
+    core::Address,
+    indexer::v2::Indexer,
+    model::indexer::v2::{QueryAccountTransaction, QueryTransaction, Transaction},
+};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::HashMap;
+
+/// Max page size allowed by the indexer
+pub const PAGE_SIZE: u64 = 1000;
+
+/// All the transactions of an account in a time range (`after` inclusive, `before` exclusive), most recent first.
+/// Goes through all the indexer's pages, so use only with bounded ranges.
+pub async fn account_txs_in_range(
+    indexer: &Indexer,
+    address: &Address,
+    after: DateTime<Utc>,
+    before: DateTime<Utc>,
+) -> Result<Vec<Transaction>> {
+    let mut txs = vec![];
+    let mut next = None;
+    loop {
+        let res = indexer
+            .account_transactions(
+                address,
+                &QueryAccountTransaction {
+                    after_time: Some(after.to_rfc3339()),
+                    before_time: Some(before.to_rfc3339()),
+                    limit: Some(PAGE_SIZE),
+                    next,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        let is_last_page = (res.transactions.len() as u64) < PAGE_SIZE;
+        txs.extend(res.transactions);
+
+        if is_last_page || res.next_token.is_none() {
+            break;
+        }
+        next = res.next_token;
+    }
+    Ok(txs)
+}
+
+/// All the transactions that transfer an asset in a time range (`after` inclusive, `before` exclusive), most recent first.
+/// Transfers done by apps are returned as the root app call, with the transfer in the inner txs.
+/// Goes through all the indexer's pages, so use only with bounded ranges.
+pub async fn asset_txs_in_range(
+    indexer: &Indexer,
+    asset_id: u64,
+    after: DateTime<Utc>,
+    before: DateTime<Utc>,
+) -> Result<Vec<Transaction>> {
+    let mut txs = vec![];
+    let mut next = None;
+    loop {
+        let res = indexer
+            .transactions(&QueryTransaction {
+                asset_id: Some(asset_id),
+                after_time: Some(after.to_rfc3339()),
+                before_time: Some(before.to_rfc3339()),
+                limit: Some(PAGE_SIZE),
+                next,
+                ..Default::default()
+            })
+            .await?;
+
+        let is_last_page = (res.transactions.len() as u64) < PAGE_SIZE;
+        txs.extend(res.transactions);
+
+        if is_last_page || res.next_token.is_none() {
+            break;
+        }
+        next = res.next_token;
+    }
+    Ok(txs)
+}
+
+/// Groups transactions by atomic group (or by id, if not in a group), chronological.
+/// `txs` are expected to be most recent first, as returned by the indexer.
+pub fn group_txs(txs: Vec<Transaction>) -> Vec<Vec<Transaction>> {
+    let mut groups: Vec<Vec<Transaction>> = vec![];
+    // group key -> index in groups
+    let mut indices: HashMap<String, usize> = HashMap::new();
+    for tx in txs.into_iter().rev() {
+        let key = tx.group.clone().unwrap_or_else(|| tx.id.clone());
+        match indices.get(&key) {
+            Some(index) => groups[*index].push(tx),
+            None => {
+                indices.insert(key, groups.len());
+                groups.push(vec![tx]);
+            }
+        }
+    }
+    groups
+}
+
+pub fn tx_date(tx: &Transaction) -> Result<DateTime<Utc>> {
+    let round_time = tx
+        .round_time
+        .ok_or_else(|| anyhow!("Indexer tx without round time: {}", tx.id))?;
+    Ok(Utc.timestamp(round_time as i64, 0))
+}
@@ -1,4 +1,4 @@
-use crate::js::common::{signed_js_tx_to_signed_tx1, SignedTxFromJs};
+use crate::js::common::{signed_js_tx_to_signed_tx1, verify_signed_js_txs, SignedTxFromJs};
 use algonaut::algod::v2::Algod;
 use anyhow::{anyhow, Result};
 use mbase::util::network_util::wait_for_pending_transaction;
@@ -7,6 +7,7 @@ pub async fn submit_apps_optins_from_js(algod: &Algod, optins: &[SignedTxFromJs]
     if optins.len() != 1 {
         return Err(anyhow!("Invalid app optins count: {}", optins.len()));
     }
+    verify_signed_js_txs(optins)?;
     let central_optin = &optins[0];
     submit_apps_optins(algod, central_optin).await
 }
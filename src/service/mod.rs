@@ -1,7 +1,24 @@
+pub mod arc1_tx;
 pub mod available_funds;
 pub mod constants;
+pub mod content_hash;
+pub mod dao_cache;
 pub mod drain_if_needed;
+pub mod expected_txs;
+pub mod export;
+pub mod funds_activity_page;
+pub mod funds_asset_specs;
+pub mod holders_history;
+pub mod indexer_history;
 pub mod invest_or_lock;
+pub mod node_error;
 pub mod number_formats;
+pub mod passthrough;
+pub mod price_source;
+pub mod roadmap;
+pub mod simulate;
 pub mod storage;
+pub mod tx_costs;
+pub mod tx_message;
+pub mod verified_content;
 pub mod wallet_connect_tx;
@@ -0,0 +1,150 @@
+//! Maps the errors returned by algod / indexer to typed [FrError]s.
+//!
+//! The node reports most tx failures only as text, so this is the one place where we parse the messages,
+//! instead of each provider checking for substrings.
+
+use crate::error::FrError;
+use algonaut::error::{RequestError, RequestErrorDetails, ServiceError};
+
+pub fn service_error_to_fr_error(e: &ServiceError) -> FrError {
+    match e {
+        ServiceError::Request(RequestError { details, .. }) => match details {
+            RequestErrorDetails::Http { status, message } => node_message_to_fr_error(message)
+                .unwrap_or_else(|| FrError::Node {
+                    status: Some(*status),
+                    message: message.clone(),
+                }),
+            RequestErrorDetails::Timeout => FrError::Network {
+                message: e.to_string(),
+            },
+            RequestErrorDetails::Client { description } => FrError::Network {
+                message: description.clone(),
+            },
+        },
+        _ => FrError::Internal(e.to_string()),
+    }
+}
+
+/// Returns None if the message doesn't match a known node error
+fn node_message_to_fr_error(message: &str) -> Option<FrError> {
+    // e.g. "overspend (account ADDRESS, data {...}, tried to spend {1000})"
+    if message.contains("overspend") {
+        return Some(FrError::NotEnoughAlgos);
+    }
+
+    // e.g. "account ADDRESS balance 100000 below min 200000 (1 assets)"
+    if let Some(min_balance) = word_after(message, "below min ") {
+        return Some(FrError::BelowMinBalance {
+            address: word_after(message, "account ").unwrap_or_default(),
+            balance: word_after(message, " balance ").unwrap_or_default(),
+            min_balance,
+        });
+    }
+
+    // e.g. "underflow on subtracting 100 from sender amount 50"
+    if let Some(amount) = word_after(message, "underflow on subtracting ") {
+        return Some(FrError::NotEnoughAsset {
+            amount,
+            holdings: word_after(message, "sender amount ").unwrap_or_default(),
+        });
+    }
+
+    // e.g. "asset 123 missing from ADDRESS"
+    if message.contains(" missing from ") {
+        if let Some(asset_id) = word_after(message, "asset ") {
+            return Some(FrError::AssetNotOptedIn {
+                asset_id,
+                address: word_after(message, " missing from ").unwrap_or_default(),
+            });
+        }
+    }
+
+    // e.g. "logic eval error: assert failed pc=505. Details: ..." or "rejected by ApprovalProgram"
+    if message.contains("logic eval error") || message.contains("rejected by ApprovalProgram") {
+        return Some(FrError::AppRejected {
+            reason: match message.split_once("logic eval error: ") {
+                Some((_, reason)) => reason
+                    .split(". Details")
+                    .next()
+                    .unwrap_or(reason)
+                    .to_owned(),
+                None => "rejected by ApprovalProgram".to_owned(),
+            },
+            pc: word_after(message, "pc="),
+        });
+    }
+
+    if message.contains("has not opted in to application") {
+        return Some(FrError::NotOptedInToApp);
+    }
+
+    None
+}
+
+/// The word following `prefix`, without trailing punctuation
+fn word_after(message: &str, prefix: &str) -> Option<String> {
+    let (_, rest) = message.split_once(prefix)?;
+    let word = rest
+        .split(|c: char| c.is_whitespace())
+        .next()?
+        .trim_end_matches(|c: char| matches!(c, ',' | '.' | ')' | ':' | '}'))
+        .trim_start_matches('{');
+    if word.is_empty() {
+        None
+    } else {
+        Some(word.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ADDRESS: &str = "DN7MBMCL5JQ3PFUQS7TMX5AH4EEKOBJVDUF4TCV6WERATKFLQF4MQUPZTA";
+
+    #[test]
+    fn node_message_to_fr_error_maps_the_algod_messages() {
+        let cases = [
+            (
+                format!("TransactionPool.Remember: transaction TXID: overspend (account {ADDRESS}, data {{_struct:{{}} Status:Offline MicroAlgos:{{Raw:100000}}}}, tried to spend {{1000000}})"),
+                json!("notEnoughAlgos"),
+            ),
+            (
+                format!("TransactionPool.Remember: transaction TXID: account {ADDRESS} balance 100000 below min 200000 (1 assets)"),
+                json!({ "belowMinBalance": { "address": ADDRESS, "balance": "100000", "min_balance": "200000" } }),
+            ),
+            (
+                "TransactionPool.Remember: transaction TXID: underflow on subtracting 100 from sender amount 50".to_owned(),
+                json!({ "notEnoughAsset": { "amount": "100", "holdings": "50" } }),
+            ),
+            (
+                format!("TransactionPool.Remember: transaction TXID: asset 123 missing from {ADDRESS}"),
+                json!({ "assetNotOptedIn": { "asset_id": "123", "address": ADDRESS } }),
+            ),
+            (
+                format!("TransactionPool.Remember: transaction TXID: address {ADDRESS} has not opted in to application 123"),
+                json!("notOptedInToApp"),
+            ),
+            (
+                "TransactionPool.Remember: transaction TXID: logic eval error: assert failed pc=505. Details: pc=505, opcodes=int 1\n==\nassert".to_owned(),
+                json!({ "appRejected": { "reason": "assert failed pc=505", "pc": "505" } }),
+            ),
+            (
+                "TransactionPool.Remember: transaction TXID: rejected by ApprovalProgram".to_owned(),
+                json!({ "appRejected": { "reason": "rejected by ApprovalProgram", "pc": null } }),
+            ),
+        ];
+
+        for (message, expected) in cases {
+            let error = node_message_to_fr_error(&message)
+                .unwrap_or_else(|| panic!("Not mapped: {message}"));
+            assert_eq!(serde_json::to_value(&error).unwrap(), expected, "{message}");
+        }
+    }
+
+    #[test]
+    fn node_message_to_fr_error_returns_none_for_unknown_messages() {
+        assert!(node_message_to_fr_error("fee too small").is_none());
+    }
+}
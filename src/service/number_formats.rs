@@ -1,11 +1,13 @@
 use crate::{
-    dependencies::FundsAssetSpecs, inputs_validation::ValidationError,
+    dependencies::FundsAssetSpecs,
+    inputs_validation::ValidationError,
+    locale::{PercentageStyle, UserLocale},
     provider::buy_shares::ValidateSharesInputError,
 };
 use algonaut::core::MicroAlgos;
 use anyhow::{anyhow, Result};
 use mbase::models::{funds::FundsAmount, share_amount::ShareAmount};
-use num_format::{Locale, ToFormattedString};
+use num_format::ToFormattedString;
 use rust_decimal::{prelude::ToPrimitive, Decimal};
 
 #[allow(dead_code)] // we might use Algo inputs in the future e.g. for fees
@@ -52,8 +54,8 @@ pub fn validate_share_amount_positive_or_0(
 }
 
 #[allow(dead_code)] // we might use Algo inputs in the future e.g. for fees
-pub fn microalgos_to_algos_str(micro_algos: MicroAlgos) -> String {
-    format_display_units(microalgos_to_algos(micro_algos))
+pub fn microalgos_to_algos_str(micro_algos: MicroAlgos, locale: &UserLocale) -> String {
+    format_display_units(microalgos_to_algos(micro_algos), locale)
 }
 
 pub fn microalgos_to_algos(micro_algos: MicroAlgos) -> Decimal {
@@ -63,53 +65,111 @@ pub fn microalgos_to_algos(micro_algos: MicroAlgos) -> Decimal {
 pub fn base_units_to_display_units_str(
     funds: FundsAmount,
     asset_specs: &FundsAssetSpecs,
+    locale: &UserLocale,
 ) -> String {
-    format_display_units(base_units_to_display_units(funds, asset_specs))
+    format_display_units(base_units_to_display_units(funds, asset_specs), locale)
 }
 pub fn base_units_to_display_units_readable(
     funds: FundsAmount,
     asset_specs: &FundsAssetSpecs,
+    locale: &UserLocale,
 ) -> Result<String> {
-    format_decimal_readable(base_units_to_display_units(funds, asset_specs))
+    format_decimal_readable(base_units_to_display_units(funds, asset_specs), locale)
 }
 
-pub fn format_display_units(display_units: Decimal) -> String {
-    format!("{:.2}", display_units)
+/// Not localized: for values that are parsed again (by JS or as inputs)
+pub fn base_units_to_display_units_number_str(
+    funds: FundsAmount,
+    asset_specs: &FundsAssetSpecs,
+) -> String {
+    format!("{:.2}", base_units_to_display_units(funds, asset_specs))
 }
 
-pub fn format_u64_readable(number: u64) -> Result<String> {
-    format_decimal_readable(number.into())
+/// With 2 fractionals, e.g. 1,000.50
+pub fn format_display_units(display_units: Decimal, locale: &UserLocale) -> String {
+    format_grouped(display_units, 2, locale).unwrap_or_else(|e| {
+        log::error!("Couldn't format: {display_units}: {e:?}");
+        format!("{:.2}", display_units)
+    })
+}
+
+pub fn format_u64_readable(number: u64, locale: &UserLocale) -> Result<String> {
+    format_decimal_readable(number.into(), locale)
 }
 
 /// Format number in a readable format (basically with readability separators e.g. 1,000,000 instead of 1000000),
 /// and with 1 fractional, or no fractionals if it's 0
-pub fn format_decimal_readable(decimal: Decimal) -> Result<String> {
-    // num_format doesn't support fractionals, so we use it to format only the whole part and append the decimals manually
-    // this is hacky and probably has a better solution
-    // note that it's also potentially problematic if we were to make the locale dynamic,
-    // as the "." we append for the decimals might conflict with the locale-specific separator.
+pub fn format_decimal_readable(decimal: Decimal, locale: &UserLocale) -> Result<String> {
     let dec_formatted = one_fractional_skip_zeros(decimal)?;
-    let whole = dec_formatted.trunc();
-    let fractionals: Decimal = dec_formatted.fract();
-
-    // fractionals is 0,... - we need then as an integer to append to the whole part
-    let base = 10u32;
-    let multiplier = base
-        .checked_pow(dec_formatted.scale())
-        .ok_or_else(|| anyhow!("Failed pow: {} pow {}", base, dec_formatted.scale()))?;
-    let fractionals_as_whole: Decimal = (fractionals * Decimal::from(multiplier)).normalize();
-    // log::debug!("display_units: {}, dec_formatted: {}, whole: {}, fractionals: {}, fractionals_as_whole: {}", display_units, dec_formatted, whole, fractionals, fractionals_as_whole);
-
-    let str = whole
+    format_grouped(dec_formatted, dec_formatted.scale(), locale)
+}
+
+/// Like [format_decimal_readable], but always with the currency's fractionals (e.g. 1,000.50 USD, 1,000 JPY)
+pub fn format_fiat_readable(
+    decimal: Decimal,
+    currency: &str,
+    locale: &UserLocale,
+) -> Result<String> {
+    format_grouped(decimal, currency_fractionals(currency), locale)
+}
+
+/// The number formatting shared by the formatters: the whole part with the locale's grouping (e.g. 1,000 / 1'000 / 1 000),
+/// then the locale's decimal separator and exactly `fractionals` digits (none if 0). Rounds to `fractionals`.
+fn format_grouped(decimal: Decimal, fractionals: u32, locale: &UserLocale) -> Result<String> {
+    let rounded = decimal.round_dp(fractionals);
+    let sign = if rounded.is_sign_negative() && !rounded.is_zero() {
+        "-"
+    } else {
+        ""
+    };
+    let abs = rounded.abs();
+
+    // num_format doesn't support fractionals, so we use it to format only the whole part and append the fractionals manually
+    let whole = abs
+        .trunc()
         .to_u128()
         .ok_or(anyhow!("Invalid state: couldn't convert decimal to u128"))?
-        // for now always english format
-        .to_formatted_string(&Locale::en);
+        .to_formatted_string(&locale.number);
+    if fractionals == 0 {
+        return Ok(format!("{sign}{whole}"));
+    }
 
-    Ok(if fractionals.is_zero() {
-        str
-    } else {
-        format!("{}.{}", str, fractionals_as_whole)
+    let multiplier = 10u64
+        .checked_pow(fractionals)
+        .ok_or_else(|| anyhow!("Failed pow: 10 pow {fractionals}"))?;
+    let fract = (abs.fract() * Decimal::from(multiplier))
+        .to_u64()
+        .ok_or(anyhow!("Invalid state: couldn't convert decimal to u64"))?;
+    Ok(format!(
+        "{sign}{whole}{}{:0width$}",
+        locale.decimal_separator(),
+        fract,
+        width = fractionals as usize
+    ))
+}
+
+/// ISO 4217 minor units of the currency. Most currencies have 2.
+fn currency_fractionals(currency: &str) -> u32 {
+    match currency {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+/// `percentage` is a fraction, e.g. 0.4 -> "40%" ("40 %" in e.g. German)
+pub fn format_percentage(percentage: Decimal, locale: &UserLocale) -> Result<String> {
+    let hundred: Decimal = 100.into();
+    let value = percentage
+        .checked_mul(hundred)
+        .ok_or_else(|| anyhow!("Error multiplying: {} * {}", percentage, hundred))?
+        .round_dp(2)
+        .normalize();
+    let value = format_grouped(value, value.scale(), locale)?;
+    Ok(match locale.percentage_style {
+        PercentageStyle::Attached => format!("{value}%"),
+        PercentageStyle::Spaced => format!("{value}\u{a0}%"),
     })
 }
 
@@ -174,30 +234,34 @@ fn to_base_units(decimal: Decimal, base_10_exp: u32) -> Result<u64, ValidationEr
     })
 }
 
-pub fn format_short(d: Decimal) -> Result<String> {
+pub fn format_short(d: Decimal, locale: &UserLocale) -> Result<String> {
     let thousand = 1_000.into();
     let million = 1_000_000.into();
     let billion = 1_000_000_000.into();
     let trillion = 1_000_000_000_000u64.into();
 
     if d < thousand {
-        format_one_fractional_with_suffix(d, "")
+        format_one_fractional_with_suffix(d, "", locale)
     } else if d >= thousand && d < million {
         let r = d.checked_div(thousand).unwrap();
-        format_one_fractional_with_suffix(r, "K")
+        format_one_fractional_with_suffix(r, "K", locale)
     } else if d >= million && d < billion {
         let r = d.checked_div(million).unwrap();
-        format_one_fractional_with_suffix(r, "M")
+        format_one_fractional_with_suffix(r, "M", locale)
     } else if d >= billion && d < trillion {
         let r = d.checked_div(billion).unwrap();
-        format_one_fractional_with_suffix(r, "B")
+        format_one_fractional_with_suffix(r, "B", locale)
     } else {
         let r = d.checked_div(trillion).unwrap();
-        format_one_fractional_with_suffix(r, "T")
+        format_one_fractional_with_suffix(r, "T", locale)
     }
 }
 
-fn format_one_fractional_with_suffix(d: Decimal, suffix: &str) -> Result<String> {
+fn format_one_fractional_with_suffix(
+    d: Decimal,
+    suffix: &str,
+    locale: &UserLocale,
+) -> Result<String> {
     // we want to format amount with x decimals and *skipping trailing zeros*
     // rust currently doesn't have a built in format to skip trailing zeros ({:.N} doesn't)
     // see also https://stackoverflow.com/questions/59506403/how-to-format-a-float-without-trailing-zeros-in-rust
@@ -205,7 +269,11 @@ fn format_one_fractional_with_suffix(d: Decimal, suffix: &str) -> Result<String>
     // and since we're using Decimal we've to call normalize() to remove the trailing zeros
 
     let new_d = one_fractional_skip_zeros(d)?;
-    Ok(format!("{}{}", new_d, suffix))
+    Ok(format!(
+        "{}{}",
+        format_grouped(new_d, new_d.scale(), locale)?,
+        suffix
+    ))
 }
 
 fn one_fractional_skip_zeros(d: Decimal) -> Result<Decimal> {
@@ -222,3 +290,95 @@ fn one_fractional_skip_zeros(d: Decimal) -> Result<Decimal> {
 
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::to_user_locale;
+    use std::str::FromStr;
+
+    fn dec(str: &str) -> Decimal {
+        Decimal::from_str(str).unwrap()
+    }
+
+    #[test]
+    fn format_percentage_by_locale() {
+        let en = to_user_locale("en").unwrap();
+        let de_ch = to_user_locale("de-CH").unwrap();
+        let fr = to_user_locale("fr").unwrap();
+
+        assert_eq!("40%", format_percentage(dec("0.4"), &en).unwrap());
+        assert_eq!("12.5%", format_percentage(dec("0.125"), &en).unwrap());
+        assert_eq!("40\u{a0}%", format_percentage(dec("0.4"), &de_ch).unwrap());
+        assert_eq!("12,5\u{a0}%", format_percentage(dec("0.125"), &fr).unwrap());
+        assert_eq!("33.33%", format_percentage(dec("0.333333"), &en).unwrap());
+    }
+
+    #[test]
+    fn format_display_units_groups_like_format_decimal_readable() {
+        let en = to_user_locale("en").unwrap();
+        let de_ch = to_user_locale("de-CH").unwrap();
+        let sep = de_ch.number.separator();
+
+        assert_eq!(
+            "1,234,567.89",
+            format_display_units(dec("1234567.891"), &en)
+        );
+        assert_eq!("0.50", format_display_units(dec("0.5"), &en));
+        assert_eq!(
+            format!("1{sep}234{sep}567.89"),
+            format_display_units(dec("1234567.891"), &de_ch)
+        );
+
+        assert_eq!(
+            "1,234,567.3",
+            format_decimal_readable(dec("1234567.26"), &en).unwrap()
+        );
+        assert_eq!(
+            format!("1{sep}234{sep}567"),
+            format_decimal_readable(dec("1234567"), &de_ch).unwrap()
+        );
+    }
+
+    #[test]
+    fn format_display_units_localizes_the_separators() {
+        let fr = to_user_locale("fr").unwrap();
+        let sep = fr.number.separator();
+
+        assert_eq!(
+            format!("1{sep}000,50"),
+            format_display_units(dec("1000.5"), &fr)
+        );
+    }
+
+    #[test]
+    fn format_fiat_readable_uses_the_currency_fractionals() {
+        let en = to_user_locale("en").unwrap();
+        let de_ch = to_user_locale("de-CH").unwrap();
+        let sep = de_ch.number.separator();
+
+        // 2
+        assert_eq!(
+            "1,234.50",
+            format_fiat_readable(dec("1234.5"), "USD", &en).unwrap()
+        );
+        assert_eq!(
+            format!("1{sep}234.57"),
+            format_fiat_readable(dec("1234.567"), "CHF", &de_ch).unwrap()
+        );
+        // 0
+        assert_eq!(
+            "1,235",
+            format_fiat_readable(dec("1234.56"), "JPY", &en).unwrap()
+        );
+        // 3
+        assert_eq!(
+            "1,234.568",
+            format_fiat_readable(dec("1234.5678"), "KWD", &en).unwrap()
+        );
+        assert_eq!(
+            "0.050",
+            format_fiat_readable(dec("0.05"), "KWD", &en).unwrap()
+        );
+    }
+}
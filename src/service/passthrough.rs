@@ -0,0 +1,238 @@
+//! Authentication of the passthrough ("pt") data, i.e. data that txs() hands to JS and JS hands back to submit() unchanged.
+//!
+//! submit() trusts this data (e.g. the dao in buy shares), so we attach a MAC to it, keyed with a random per-session key.
+//! The key lives only in wasm memory: a passthrough is valid only in the session (page load) that created it.
+//! The MAC also covers the operation and the ids of the txs handed out with the passthrough,
+//! so a passthrough can't be submitted with another operation or with other signed txs.
+
+use crate::js::common::{signed_js_tx_to_signed_tx1, SignedTxFromJs};
+use algonaut::transaction::Transaction;
+use anyhow::{Error, Result};
+use data_encoding::BASE64;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::{cell::RefCell, fmt::Display};
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+thread_local! {
+    static SESSION_KEY: RefCell<Option<Vec<u8>>> = RefCell::new(None);
+}
+
+/// The passthrough data was modified, created in a different session, or belongs to other txs
+#[derive(Debug, Clone)]
+pub struct InvalidPassthroughError;
+
+impl Display for InvalidPassthroughError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Passthrough data was modified, created in a different session or belongs to other txs"
+        )
+    }
+}
+
+impl std::error::Error for InvalidPassthroughError {}
+
+/// Implemented by the passthrough structs. The MAC is calculated over the struct, serialized with an empty mac field.
+pub trait Passthrough: Serialize + Clone {
+    /// The operation that hands out the passthrough, e.g. "withdraw"
+    const OPERATION: &'static str;
+
+    fn mac_mut(&mut self) -> &mut String;
+
+    /// To be called by txs(), with the txs handed out for signing, before handing the passthrough to JS
+    fn signed(mut self, txs: &[Transaction]) -> Result<Self> {
+        let mut tx_ids = vec![];
+        for tx in txs {
+            tx_ids.push(tx.id().map_err(Error::msg)?);
+        }
+
+        *self.mac_mut() = String::new();
+        let mac = mac(Self::OPERATION, &tx_ids, &self)?
+            .finalize()
+            .into_bytes();
+        *self.mac_mut() = BASE64.encode(&mac);
+        Ok(self)
+    }
+
+    /// To be called first thing by the step receiving the passthrough, with the signed txs of the operation that handed it out
+    fn verify(&self, txs: &[SignedTxFromJs]) -> Result<()> {
+        let mut tx_ids = vec![];
+        for tx in txs {
+            tx_ids.push(
+                signed_js_tx_to_signed_tx1(tx)?
+                    .transaction
+                    .id()
+                    .map_err(Error::msg)?,
+            );
+        }
+
+        let mut unsigned = self.clone();
+        let received_mac = BASE64
+            .decode(std::mem::take(unsigned.mac_mut()).as_bytes())
+            .map_err(|_| InvalidPassthroughError)?;
+
+        // constant time comparison
+        mac(Self::OPERATION, &tx_ids, &unsigned)?
+            .verify_slice(&received_mac)
+            .map_err(|_| InvalidPassthroughError.into())
+    }
+}
+
+fn mac<T: Serialize>(operation: &str, tx_ids: &[String], data: &T) -> Result<HmacSha256> {
+    let mut mac = HmacSha256::new_from_slice(&session_key()).map_err(Error::msg)?;
+    // length prefixed, so the parts can't be shifted into each other
+    let mut update = |bytes: &[u8]| {
+        mac.update(&(bytes.len() as u64).to_be_bytes());
+        mac.update(bytes);
+    };
+    update(operation.as_bytes());
+    for tx_id in tx_ids {
+        update(tx_id.as_bytes());
+    }
+    update(&rmp_serde::to_vec_named(data)?);
+    Ok(mac)
+}
+
+fn session_key() -> Vec<u8> {
+    SESSION_KEY.with(|key| {
+        key.borrow_mut()
+            .get_or_insert_with(|| {
+                // 2 v4 uuids: 244 random bits
+                let mut key = Uuid::new_v4().as_bytes().to_vec();
+                key.extend_from_slice(Uuid::new_v4().as_bytes());
+                key
+            })
+            .clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dependencies::algod,
+        fake_node::{
+            fixtures::{payment_txs, signed_js_txs, CREATOR, INVESTOR},
+            FakeChain, FakeNode,
+        },
+    };
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct TestPassthrough {
+        dao_id: String,
+        mac: String,
+    }
+
+    impl Passthrough for TestPassthrough {
+        const OPERATION: &'static str = "test";
+
+        fn mac_mut(&mut self) -> &mut String {
+            &mut self.mac
+        }
+    }
+
+    /// Same fields, other operation
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct OtherPassthrough {
+        dao_id: String,
+        mac: String,
+    }
+
+    impl Passthrough for OtherPassthrough {
+        const OPERATION: &'static str = "other";
+
+        fn mac_mut(&mut self) -> &mut String {
+            &mut self.mac
+        }
+    }
+
+    fn passthrough() -> TestPassthrough {
+        TestPassthrough {
+            dao_id: "123".to_owned(),
+            mac: String::new(),
+        }
+    }
+
+    async fn txs(amount: u64) -> Vec<Transaction> {
+        payment_txs(&algod(), &[(CREATOR, INVESTOR, amount)]).await
+    }
+
+    fn assert_invalid(res: Result<()>) {
+        assert!(res.unwrap_err().is::<InvalidPassthroughError>());
+    }
+
+    #[tokio::test]
+    async fn verify_accepts_the_signed_passthrough_with_its_txs() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let txs = txs(1).await;
+
+        let pt = passthrough().signed(&txs).unwrap();
+
+        assert!(!pt.mac.is_empty());
+        assert!(pt.verify(&signed_js_txs(&txs)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_a_tampered_field() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let txs = txs(1).await;
+        let mut pt = passthrough().signed(&txs).unwrap();
+
+        pt.dao_id = "124".to_owned();
+
+        assert_invalid(pt.verify(&signed_js_txs(&txs)));
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_a_tampered_or_missing_mac() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let txs = txs(1).await;
+        let pt = passthrough().signed(&txs).unwrap();
+
+        let mut tampered = pt.clone();
+        tampered.mac = BASE64.encode(&[0; 32]);
+        assert_invalid(tampered.verify(&signed_js_txs(&txs)));
+
+        assert_invalid(passthrough().verify(&signed_js_txs(&txs)));
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_other_txs() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let pt = passthrough().signed(&txs(1).await).unwrap();
+
+        assert_invalid(pt.verify(&signed_js_txs(&txs(2).await)));
+        assert_invalid(pt.verify(&[]));
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_a_passthrough_of_another_operation() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let txs = txs(1).await;
+        let pt = passthrough().signed(&txs).unwrap();
+
+        let other = OtherPassthrough {
+            dao_id: pt.dao_id,
+            mac: pt.mac,
+        };
+
+        assert_invalid(other.verify(&signed_js_txs(&txs)));
+    }
+
+    #[tokio::test]
+    async fn verify_rejects_a_passthrough_signed_with_another_session_key() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let txs = txs(1).await;
+        let pt = passthrough().signed(&txs).unwrap();
+
+        // new session (page reload)
+        SESSION_KEY.with(|key| *key.borrow_mut() = None);
+
+        assert_invalid(pt.verify(&signed_js_txs(&txs)));
+    }
+}
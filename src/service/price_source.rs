@@ -0,0 +1,386 @@
+//! Prices of the funds asset in fiat currencies, to show fiat equivalents alongside the funds asset amounts.
+//!
+//! Fiat values are informative: if no currency is selected or the price can't be fetched,
+//! [fiat_converter] returns None and the amounts are shown only in the funds asset.
+
+use super::number_formats::{base_units_to_display_units, format_fiat_readable};
+use crate::{
+    dependencies::FundsAssetSpecs,
+    fiat::{fiat_settings, price_source},
+    locale::UserLocale,
+};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use base::reqwest_ext::ResponseExt;
+use chrono::{DateTime, Duration, Utc};
+use mbase::models::funds::FundsAmount;
+use reqwest::Client;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, collections::HashMap};
+use tsify::Tsify;
+
+/// Prices change continuously, but a session doesn't need them more often than this
+const PRICE_TTL_SECS: i64 = 60;
+
+/// (source id, funds asset id, currency)
+type PriceKey = (String, u64, String);
+
+thread_local! {
+    static PRICES: RefCell<HashMap<PriceKey, (Decimal, DateTime<Utc>)>> = RefCell::new(HashMap::new());
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait PriceSource {
+    /// Identifies the source in the price cache
+    fn id(&self) -> String;
+
+    /// Price of 1 display unit of the funds asset (e.g. 1 USDC, not 1 micro USDC) in `currency`
+    async fn price(&self, asset_specs: &FundsAssetSpecs, currency: &str) -> Result<Decimal>;
+}
+
+/// Fixed rates, for development / QA.
+/// The rates are the ones of USD, so they're only returned for USD stablecoins (by unit name, e.g. "USDC").
+/// For other funds assets this fails, i.e. amounts are shown only in the funds asset.
+pub struct MockPriceSource {}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl PriceSource for MockPriceSource {
+    fn id(&self) -> String {
+        "mock".to_owned()
+    }
+
+    async fn price(&self, asset_specs: &FundsAssetSpecs, currency: &str) -> Result<Decimal> {
+        if !matches!(
+            asset_specs.unit_name.to_uppercase().as_str(),
+            "USDC" | "USDT"
+        ) {
+            return Err(anyhow!(
+                "Mock price source: no prices for: {} (only USD stablecoins)",
+                asset_specs.unit_name
+            ));
+        }
+        let price = match currency {
+            "USD" => "1",
+            "EUR" => "0.93",
+            "CHF" => "0.9",
+            "GBP" => "0.8",
+            "JPY" => "140",
+            _ => return Err(anyhow!("Mock price source: no price for: {currency}")),
+        };
+        Ok(price.parse()?)
+    }
+}
+
+/// Price API. Expected endpoint:
+/// `GET {url}/v1/price?asset_id={funds asset id}&unit={funds asset unit name}&currency={currency}`
+/// returning `{"price": "<price of 1 display unit of the asset, as decimal string>"}`.
+/// In tests, it's served by the fake node - see [crate::fake_node::FakeChain::set_price].
+pub struct HttpPriceSource {
+    url: String,
+    client: Client,
+}
+
+impl HttpPriceSource {
+    pub fn new(url: &str) -> HttpPriceSource {
+        HttpPriceSource {
+            url: url.trim_end_matches('/').to_owned(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PriceRes {
+    price: String,
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl PriceSource for HttpPriceSource {
+    fn id(&self) -> String {
+        self.url.clone()
+    }
+
+    async fn price(&self, asset_specs: &FundsAssetSpecs, currency: &str) -> Result<Decimal> {
+        let res: PriceRes = self
+            .client
+            .get(format!("{}/v1/price", self.url))
+            .header("Accept", "application/json")
+            .query(&[
+                ("asset_id", asset_specs.id.0.to_string()),
+                ("unit", asset_specs.unit_name.clone()),
+                ("currency", currency.to_owned()),
+            ])
+            .send()
+            .await?
+            .to_error_if_http_error()
+            .await?
+            .json()
+            .await?;
+
+        let price: Decimal = res.price.parse()?;
+        if price.is_sign_negative() {
+            return Err(anyhow!("Invalid price: {price} for: {currency}"));
+        }
+        Ok(price)
+    }
+}
+
+/// A fiat amount, alongside the funds asset amount it was converted from
+#[derive(Tsify, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FiatAmountJs {
+    /// ISO 4217 code, e.g. "EUR"
+    pub currency: String,
+    /// Formatted with the user's locale and the currency's fractionals (e.g. 2 for EUR, 0 for JPY)
+    pub amount: String,
+    /// For calculations / charts
+    pub amount_number: f64,
+}
+
+/// Converts funds asset amounts with a fixed price (fetched once per response)
+#[derive(Debug, Clone)]
+pub struct FiatConverter {
+    currency: String,
+    price: Decimal,
+}
+
+impl FiatConverter {
+    pub fn new(currency: &str, price: Decimal) -> FiatConverter {
+        FiatConverter {
+            currency: currency.to_owned(),
+            price,
+        }
+    }
+
+    pub fn convert(
+        &self,
+        funds: FundsAmount,
+        asset_specs: &FundsAssetSpecs,
+        locale: &UserLocale,
+    ) -> Result<FiatAmountJs> {
+        self.convert_display_units(base_units_to_display_units(funds, asset_specs), locale)
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    /// Unformatted fiat amount, e.g. for charts
+    pub fn to_fiat(&self, display_units: Decimal) -> Result<Decimal> {
+        display_units
+            .checked_mul(self.price)
+            .ok_or_else(|| anyhow!("Error multiplying: {} * {}", display_units, self.price))
+    }
+
+    pub fn convert_display_units(
+        &self,
+        display_units: Decimal,
+        locale: &UserLocale,
+    ) -> Result<FiatAmountJs> {
+        let amount = self.to_fiat(display_units)?;
+        Ok(FiatAmountJs {
+            currency: self.currency.clone(),
+            amount: format_fiat_readable(amount, &self.currency, locale)?,
+            amount_number: amount
+                .to_f64()
+                .ok_or_else(|| anyhow!("Couldn't convert: {amount} to f64"))?,
+        })
+    }
+
+    /// Like [FiatConverter::convert], but logs errors and returns None: fiat values are informative
+    pub fn convert_or_log(
+        &self,
+        funds: FundsAmount,
+        asset_specs: &FundsAssetSpecs,
+        locale: &UserLocale,
+    ) -> Option<FiatAmountJs> {
+        self.convert(funds, asset_specs, locale)
+            .map_err(|e| log::error!("Couldn't convert: {funds:?} to fiat: {e:?}"))
+            .ok()
+    }
+}
+
+/// Converter for the user's selected currency. None if no currency is selected or the price isn't available.
+pub async fn fiat_converter(asset_specs: &FundsAssetSpecs) -> Option<FiatConverter> {
+    match fiat_converter_or_err(asset_specs).await {
+        Ok(converter) => converter,
+        Err(e) => {
+            log::error!("Couldn't get fiat price, showing only funds asset amounts: {e:?}");
+            None
+        }
+    }
+}
+
+async fn fiat_converter_or_err(asset_specs: &FundsAssetSpecs) -> Result<Option<FiatConverter>> {
+    let settings = fiat_settings()?;
+    let currency = match &settings.currency {
+        Some(currency) => currency,
+        None => return Ok(None),
+    };
+    let source = price_source(&settings)?;
+    let key = (source.id(), asset_specs.id.0, currency.clone());
+
+    let cached = PRICES.with(|prices| {
+        prices
+            .borrow()
+            .get(&key)
+            .filter(|(_, fetched_at)| Utc::now() - *fetched_at < Duration::seconds(PRICE_TTL_SECS))
+            .map(|(price, _)| *price)
+    });
+    let price = match cached {
+        Some(price) => price,
+        None => {
+            let price = source.price(asset_specs, currency).await?;
+            PRICES.with(|prices| prices.borrow_mut().insert(key, (price, Utc::now())));
+            price
+        }
+    };
+
+    Ok(Some(FiatConverter::new(currency, price)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fake_node::{fixtures::FUNDS_ASSET_ID, price_source_url_override, FakeChain, FakeNode},
+        fiat::{set_fiat_settings, FiatSettings, PriceSourceSettings},
+        locale::to_user_locale,
+    };
+    use mbase::models::funds::FundsAssetId;
+
+    fn specs(unit_name: &str) -> FundsAssetSpecs {
+        FundsAssetSpecs {
+            id: FundsAssetId(FUNDS_ASSET_ID),
+            decimals: 6,
+            unit_name: unit_name.to_owned(),
+            name: unit_name.to_owned(),
+        }
+    }
+
+    fn dec(str: &str) -> Decimal {
+        str.parse().unwrap()
+    }
+
+    fn http_source() -> HttpPriceSource {
+        HttpPriceSource::new(&price_source_url_override().unwrap())
+    }
+
+    fn select_currency(currency: &str) {
+        set_fiat_settings(&FiatSettings {
+            currency: Some(currency.to_owned()),
+            // in tests, the fake node's price API is used
+            price_source: PriceSourceSettings::Http {
+                url: "http://localhost".to_owned(),
+            },
+        })
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn http_price_source_returns_the_price_of_the_asset_in_the_currency() {
+        let mut chain = FakeChain::new();
+        chain.set_price(FUNDS_ASSET_ID, "EUR", "0.93");
+        let _node = FakeNode::start(chain).unwrap();
+
+        let price = http_source().price(&specs("USDC"), "EUR").await.unwrap();
+
+        assert_eq!(dec("0.93"), price);
+    }
+
+    #[tokio::test]
+    async fn http_price_source_fails_without_price_or_with_negative_price() {
+        let mut chain = FakeChain::new();
+        chain.set_price(FUNDS_ASSET_ID, "CHF", "-1");
+        let _node = FakeNode::start(chain).unwrap();
+
+        assert!(http_source().price(&specs("USDC"), "EUR").await.is_err());
+        assert!(http_source().price(&specs("USDC"), "CHF").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn fiat_converter_caches_the_price_until_the_ttl() {
+        let mut chain = FakeChain::new();
+        chain.set_price(FUNDS_ASSET_ID, "EUR", "0.93");
+        let node = FakeNode::start(chain).unwrap();
+        select_currency("EUR");
+        let specs = specs("USDC");
+
+        let to_eur =
+            |converter: Option<FiatConverter>| converter.unwrap().to_fiat(1.into()).unwrap();
+
+        assert_eq!(dec("0.93"), to_eur(fiat_converter(&specs).await));
+
+        node.chain()
+            .lock()
+            .unwrap()
+            .set_price(FUNDS_ASSET_ID, "EUR", "0.95");
+        // cached
+        assert_eq!(dec("0.93"), to_eur(fiat_converter(&specs).await));
+
+        PRICES.with(|prices| {
+            for (_, fetched_at) in prices.borrow_mut().values_mut() {
+                *fetched_at = Utc::now() - Duration::seconds(PRICE_TTL_SECS + 1);
+            }
+        });
+        assert_eq!(dec("0.95"), to_eur(fiat_converter(&specs).await));
+    }
+
+    #[tokio::test]
+    async fn fiat_converter_is_none_without_currency_or_price() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let specs = specs("USDC");
+
+        // no currency selected
+        assert!(fiat_converter(&specs).await.is_none());
+
+        // no price
+        select_currency("EUR");
+        assert!(fiat_converter(&specs).await.is_none());
+    }
+
+    #[test]
+    fn fiat_converter_converts_and_formats_with_the_currency_fractionals() {
+        let en = to_user_locale("en").unwrap();
+        let specs = specs("USDC");
+
+        let eur = FiatConverter::new("EUR", dec("0.9"))
+            .convert(FundsAmount::new(1_500_000), &specs, &en)
+            .unwrap();
+        assert_eq!(
+            FiatAmountJs {
+                currency: "EUR".to_owned(),
+                amount: "1.35".to_owned(),
+                amount_number: 1.35,
+            },
+            eur
+        );
+
+        let jpy = FiatConverter::new("JPY", dec("140"))
+            .convert(FundsAmount::new(10_000_000_000), &specs, &en)
+            .unwrap();
+        assert_eq!("1,400,000", jpy.amount);
+    }
+
+    #[tokio::test]
+    async fn mock_price_source_prices_only_usd_stablecoins() {
+        assert_eq!(
+            dec("0.93"),
+            MockPriceSource {}
+                .price(&specs("USDC"), "EUR")
+                .await
+                .unwrap()
+        );
+        assert!(MockPriceSource {}
+            .price(&specs("USDC"), "XYZ")
+            .await
+            .is_err());
+        assert!(MockPriceSource {}
+            .price(&specs("ALGO"), "EUR")
+            .await
+            .is_err());
+    }
+}
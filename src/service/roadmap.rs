@@ -0,0 +1,344 @@
+//! Roadmap item changes on top of [base::roadmap], which only saves items (title, date, parent hash).
+//!
+//! Every saved item is an operation ([RoadmapOp]), encoded as JSON in the title ([RoadmapItemPayload]).
+//! Edits, completions and removals point with `parent` to the tx id of the item they supersede,
+//! and are applied in the order they were confirmed ([resolve_roadmap]).
+//! Items saved as plain titles (before operations existed) are additions.
+
+use algonaut::crypto::HashDigest;
+use anyhow::{anyhow, Result};
+use base::roadmap::get_roadmap::SavedRoadmapItem;
+use chrono::{DateTime, TimeZone, Utc};
+use data_encoding::BASE32_NOPAD;
+use mbase::models::tx_id::TxId;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, convert::TryInto};
+
+/// Current payload format version
+const PAYLOAD_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoadmapOp {
+    Add,
+    /// New title and date (the saved item's date)
+    Edit,
+    /// Completion date: the saved item's date
+    Complete,
+    /// Undoes complete
+    Reopen,
+    Remove,
+}
+
+impl ToString for RoadmapOp {
+    fn to_string(&self) -> String {
+        match self {
+            RoadmapOp::Add => "add",
+            RoadmapOp::Edit => "edit",
+            RoadmapOp::Complete => "complete",
+            RoadmapOp::Reopen => "reopen",
+            RoadmapOp::Remove => "remove",
+        }
+        .to_owned()
+    }
+}
+
+/// What's saved as title of the base roadmap item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoadmapItemPayload {
+    pub v: u8,
+    pub op: RoadmapOp,
+    /// Empty for operations that don't change the title
+    pub title: String,
+    /// Unix seconds, as reported by the client: informative only (operations are applied in confirmation order)
+    pub saved_at: i64,
+    /// Add / edit (an edit replaces it, also with None)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Add / edit (an edit replaces it, also with None)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+impl RoadmapItemPayload {
+    pub fn new(op: RoadmapOp, title: String) -> RoadmapItemPayload {
+        RoadmapItemPayload {
+            v: PAYLOAD_VERSION,
+            op,
+            title,
+            saved_at: Utc::now().timestamp(),
+            description: None,
+            link: None,
+        }
+    }
+
+    pub fn with_details(
+        mut self,
+        description: Option<String>,
+        link: Option<String>,
+    ) -> RoadmapItemPayload {
+        self.description = description.filter(|d| !d.trim().is_empty());
+        self.link = link.filter(|l| !l.trim().is_empty());
+        self
+    }
+
+    pub fn to_title(&self) -> Result<String> {
+        if matches!(self.op, RoadmapOp::Add | RoadmapOp::Edit) && self.title.trim().is_empty() {
+            return Err(anyhow!("Roadmap item title is empty"));
+        }
+        if let Some(link) = &self.link {
+            if !(link.starts_with("http://") || link.starts_with("https://")) {
+                return Err(anyhow!("Invalid roadmap item link: {link}"));
+            }
+        }
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Plain titles (legacy items, or not saved by us) are additions
+    pub fn from_title(title: &str) -> RoadmapItemPayload {
+        match serde_json::from_str::<RoadmapItemPayload>(title) {
+            Ok(payload) if payload.v == PAYLOAD_VERSION => payload,
+            _ => RoadmapItemPayload {
+                v: PAYLOAD_VERSION,
+                op: RoadmapOp::Add,
+                title: title.to_owned(),
+                saved_at: 0,
+                description: None,
+                link: None,
+            },
+        }
+    }
+}
+
+/// Tx ids are base32 encoded hashes, so they fit in the `parent` hash
+pub fn tx_id_to_hash(tx_id: &TxId) -> Result<HashDigest> {
+    let tx_id_str = tx_id.to_string();
+    let bytes = BASE32_NOPAD.decode(tx_id_str.as_bytes())?;
+    Ok(HashDigest(bytes.try_into().map_err(|bytes: Vec<u8>| {
+        anyhow!(
+            "Tx id: {tx_id_str} isn't a hash: unexpected length: {}",
+            bytes.len()
+        )
+    })?))
+}
+
+/// A roadmap item with all its operations applied
+#[derive(Debug, Clone)]
+pub struct RoadmapEntry {
+    /// Tx id of the addition: identifies the item
+    pub tx_id: TxId,
+    /// Parent of the addition (not of the operations): the tx id hash of the parent item (e.g. an epic)
+    pub parent: Option<HashDigest>,
+    pub title: String,
+    pub date: DateTime<Utc>,
+    pub description: Option<String>,
+    pub link: Option<String>,
+    pub completed_date: Option<DateTime<Utc>>,
+    /// Chronological, starting with the addition
+    pub history: Vec<RoadmapChange>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RoadmapChange {
+    pub op: RoadmapOp,
+    pub tx_id: TxId,
+    pub title: String,
+    pub date: DateTime<Utc>,
+    pub description: Option<String>,
+    pub link: Option<String>,
+    /// None for legacy items
+    pub saved_at: Option<DateTime<Utc>>,
+}
+
+impl RoadmapEntry {
+    pub fn completed(&self) -> bool {
+        self.completed_date.is_some()
+    }
+
+    pub fn edited(&self) -> bool {
+        self.history.iter().any(|c| c.op == RoadmapOp::Edit)
+    }
+}
+
+/// Applies the operations to the added items, in confirmation order. Removed items aren't returned.
+/// Operations pointing to unknown or removed items are ignored.
+///
+/// `items` are expected in indexer order (most recent first, like the other account tx queries).
+/// The client's `saved_at` isn't used for ordering, as it can't be trusted (clock skew, or set arbitrarily).
+pub fn resolve_roadmap(items: Vec<SavedRoadmapItem>) -> Result<Vec<RoadmapEntry>> {
+    let mut entries: Vec<Option<RoadmapEntry>> = vec![];
+    // tx id hash -> index in entries
+    let mut indices: HashMap<[u8; 32], usize> = HashMap::new();
+    let mut ops = vec![];
+
+    // chronological: operations are collected in confirmation order
+    for item in items.into_iter().rev() {
+        let payload = RoadmapItemPayload::from_title(&item.title);
+        let change = RoadmapChange {
+            op: payload.op,
+            tx_id: item.tx_id.clone(),
+            title: payload.title,
+            date: item.date,
+            description: payload.description,
+            link: payload.link,
+            saved_at: match payload.saved_at {
+                0 => None,
+                secs => Some(Utc.timestamp(secs, 0)),
+            },
+        };
+
+        if payload.op == RoadmapOp::Add {
+            indices.insert(tx_id_to_hash(&item.tx_id)?.0, entries.len());
+            entries.push(Some(RoadmapEntry {
+                tx_id: item.tx_id,
+                parent: *item.parent,
+                title: change.title.clone(),
+                date: item.date,
+                description: change.description.clone(),
+                link: change.link.clone(),
+                completed_date: None,
+                history: vec![change],
+            }));
+        } else {
+            match *item.parent {
+                Some(target) => ops.push((target, change)),
+                None => log::warn!("Roadmap operation without target: {change:?}"),
+            }
+        }
+    }
+
+    for (target, change) in ops {
+        let index = indices.get(&target.0).copied();
+        let entry = match index.and_then(|i| entries[i].as_mut()) {
+            Some(entry) => entry,
+            None => {
+                log::warn!("Roadmap operation for unknown or removed item: {change:?}");
+                continue;
+            }
+        };
+        match change.op {
+            RoadmapOp::Add => {}
+            RoadmapOp::Edit => {
+                entry.title = change.title.clone();
+                entry.date = change.date;
+                entry.description = change.description.clone();
+                entry.link = change.link.clone();
+            }
+            RoadmapOp::Complete => entry.completed_date = Some(change.date),
+            RoadmapOp::Reopen => entry.completed_date = None,
+            RoadmapOp::Remove => {
+                if let Some(i) = index {
+                    entries[i] = None;
+                }
+                continue;
+            }
+        }
+        entry.history.push(change);
+    }
+
+    Ok(entries.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx_id(seed: u8) -> TxId {
+        BASE32_NOPAD.encode(&[seed; 32]).parse().unwrap()
+    }
+
+    fn date(day: u32) -> DateTime<Utc> {
+        Utc.ymd(2022, 6, day).and_hms(0, 0, 0)
+    }
+
+    fn saved(
+        seed: u8,
+        payload: &RoadmapItemPayload,
+        day: u32,
+        parent: Option<u8>,
+    ) -> SavedRoadmapItem {
+        SavedRoadmapItem {
+            tx_id: tx_id(seed),
+            title: payload.to_title().unwrap(),
+            date: date(day),
+            parent: Box::new(parent.map(|p| tx_id_to_hash(&tx_id(p)).unwrap())),
+        }
+    }
+
+    fn payload(op: RoadmapOp, title: &str) -> RoadmapItemPayload {
+        RoadmapItemPayload::new(op, title.to_owned())
+    }
+
+    #[test]
+    fn resolve_roadmap_applies_edit_and_complete() {
+        // most recent first
+        let items = vec![
+            saved(3, &payload(RoadmapOp::Complete, ""), 20, Some(1)),
+            saved(2, &payload(RoadmapOp::Edit, "Launch v2"), 10, Some(1)),
+            saved(1, &payload(RoadmapOp::Add, "Launch"), 5, None),
+        ];
+
+        let entries = resolve_roadmap(items).unwrap();
+
+        assert_eq!(1, entries.len());
+        let entry = &entries[0];
+        assert_eq!(tx_id(1).to_string(), entry.tx_id.to_string());
+        assert_eq!("Launch v2", entry.title);
+        assert_eq!(date(10), entry.date);
+        assert_eq!(Some(date(20)), entry.completed_date);
+        assert!(entry.edited());
+        let ops: Vec<RoadmapOp> = entry.history.iter().map(|c| c.op).collect();
+        assert_eq!(
+            vec![RoadmapOp::Add, RoadmapOp::Edit, RoadmapOp::Complete],
+            ops
+        );
+    }
+
+    #[test]
+    fn resolve_roadmap_applies_operations_in_confirmation_order_not_saved_at() {
+        // the first confirmed edit claims to be saved later
+        let mut first_edit = payload(RoadmapOp::Edit, "First");
+        first_edit.saved_at = Utc::now().timestamp() + 1000;
+        let items = vec![
+            saved(3, &payload(RoadmapOp::Edit, "Second"), 5, Some(1)),
+            saved(2, &first_edit, 5, Some(1)),
+            saved(1, &payload(RoadmapOp::Add, "Initial"), 5, None),
+        ];
+
+        let entries = resolve_roadmap(items).unwrap();
+
+        assert_eq!("Second", entries[0].title);
+    }
+
+    #[test]
+    fn resolve_roadmap_drops_removed_items_and_ignores_later_operations() {
+        let items = vec![
+            saved(4, &payload(RoadmapOp::Complete, ""), 9, Some(1)),
+            saved(3, &payload(RoadmapOp::Remove, ""), 8, Some(1)),
+            saved(2, &payload(RoadmapOp::Add, "Kept"), 7, None),
+            saved(1, &payload(RoadmapOp::Add, "Removed"), 6, None),
+        ];
+
+        let entries = resolve_roadmap(items).unwrap();
+
+        let titles: Vec<&str> = entries.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(vec!["Kept"], titles);
+        assert!(!entries[0].completed());
+    }
+
+    #[test]
+    fn resolve_roadmap_treats_plain_titles_as_additions() {
+        let items = vec![SavedRoadmapItem {
+            tx_id: tx_id(1),
+            title: "Legacy item".to_owned(),
+            date: date(1),
+            parent: Box::new(None),
+        }];
+
+        let entries = resolve_roadmap(items).unwrap();
+
+        assert_eq!(1, entries.len());
+        assert_eq!("Legacy item", entries[0].title);
+        assert_eq!(None, entries[0].completed_date);
+    }
+}
@@ -0,0 +1,407 @@
+//! Optional simulation of the txs handed out for signing, so the UI can prevent the user from signing txs that will fail.
+//!
+//! Two steps: a local balance check (algos and assets, incl. fees and min balance increases), which catches e.g. "overspend",
+//! and an algod dryrun of the app calls, which catches app logic failures (e.g. "underflow on subtracting").
+
+use super::{
+    number_formats::microalgos_to_algos_str,
+    storage::{storage_get, storage_set},
+    tx_costs::{min_balance_increases, MinBalanceIncrease},
+    tx_message::tx_sender,
+};
+use crate::locale::locale;
+use algonaut::{
+    algod::v2::Algod,
+    core::{Address, MicroAlgos},
+    crypto::Signature,
+    model::algod::v2::{Account, Application, DryrunRequest, DryrunResponse},
+    transaction::{
+        transaction::{ApplicationCallTransaction, TransactionSignature, TransactionType},
+        SignedTransaction, Transaction,
+    },
+};
+use anyhow::{Error, Result};
+use chrono::Utc;
+use mbase::models::dao_app_id::DaoAppId;
+use serde::Serialize;
+use std::collections::HashMap;
+use tsify::Tsify;
+
+/// Local storage key for the simulation setting
+const SIMULATE_TXS_KEY: &str = "SIMULATE_TXS";
+
+/// Whether txs are simulated before being handed out for signing (off by default: it needs additional requests)
+pub fn simulate_txs_enabled() -> Result<bool> {
+    Ok(storage_get(SIMULATE_TXS_KEY)?.unwrap_or(false))
+}
+
+pub fn set_simulate_txs(enabled: bool) -> Result<()> {
+    storage_set(SIMULATE_TXS_KEY, &enabled)
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct SimulationJs {
+    pub status: SimulationStatus,
+    /// Set if status is failure
+    pub failure: Option<SimulationFailureJs>,
+    pub total_fee: String,
+    pub total_fee_number: String,
+}
+
+#[derive(Tsify, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SimulationStatus {
+    Success,
+    Failure,
+    /// The simulation couldn't be run (e.g. node error). The txs may or may not succeed.
+    Unavailable,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+pub struct SimulationFailureJs {
+    pub kind: SimulationFailureKind,
+    /// Index of the failing tx in the group
+    pub tx_index: usize,
+    /// Set if an app call failed
+    pub app_id: Option<String>,
+    /// For developers: dryrun / check messages
+    pub messages: Vec<String>,
+}
+
+#[derive(Tsify, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SimulationFailureKind {
+    /// The sender doesn't have enough algos for the payments and fees, or would go below the min balance
+    Overspend,
+    /// The sender doesn't have enough of an asset
+    NotEnoughAsset,
+    /// The app logic rejected the tx
+    AppRejected,
+}
+
+/// Never fails: if the simulation can't be run, returns [SimulationStatus::Unavailable]
+pub async fn simulate(algod: &Algod, txs: &[Transaction]) -> SimulationJs {
+    let locale = locale();
+    let total_fee = MicroAlgos(txs.iter().map(|tx| tx.fee.0).sum());
+
+    let (status, failure) = match simulate_failure(algod, txs).await {
+        Ok(None) => (SimulationStatus::Success, None),
+        Ok(Some(failure)) => (SimulationStatus::Failure, Some(failure)),
+        Err(e) => {
+            log::error!("Couldn't simulate txs: {e:?}");
+            (SimulationStatus::Unavailable, None)
+        }
+    };
+
+    SimulationJs {
+        status,
+        failure,
+        total_fee: microalgos_to_algos_str(total_fee, &locale),
+        total_fee_number: total_fee.0.to_string(),
+    }
+}
+
+async fn simulate_failure(
+    algod: &Algod,
+    txs: &[Transaction],
+) -> Result<Option<SimulationFailureJs>> {
+    let accounts = fetch_accounts(algod, txs).await?;
+    let min_balance_increases = min_balance_increases(algod, txs).await?;
+
+    if let Some(failure) = check_balances(txs, &accounts, &min_balance_increases) {
+        return Ok(Some(failure));
+    }
+
+    if txs
+        .iter()
+        .any(|tx| matches!(tx.txn_type, TransactionType::ApplicationCallTransaction(_)))
+    {
+        return dryrun(algod, txs, accounts.into_values().collect()).await;
+    }
+
+    Ok(None)
+}
+
+/// Accounts of the senders, receivers and accounts referenced by the app calls (incl. the app accounts)
+async fn fetch_accounts(algod: &Algod, txs: &[Transaction]) -> Result<HashMap<Address, Account>> {
+    let mut addresses = vec![];
+    for tx in txs {
+        addresses.extend(tx_sender(tx));
+        match &tx.txn_type {
+            TransactionType::Payment(p) => addresses.push(p.receiver),
+            TransactionType::AssetTransferTransaction(t) => addresses.push(t.receiver),
+            TransactionType::ApplicationCallTransaction(call) => {
+                addresses.extend(call.accounts.iter().flatten().cloned());
+                addresses.extend(app_ids(call).into_iter().map(|id| DaoAppId(id).address()));
+            }
+            _ => {}
+        }
+    }
+
+    let mut accounts = HashMap::new();
+    for address in addresses {
+        if !accounts.contains_key(&address) {
+            accounts.insert(address, algod.account_information(&address).await?);
+        }
+    }
+    Ok(accounts)
+}
+
+fn check_balances(
+    txs: &[Transaction],
+    accounts: &HashMap<Address, Account>,
+    min_balance_increases: &[MinBalanceIncrease],
+) -> Option<SimulationFailureJs> {
+    // running balances: algos and (address, asset id) -> amount
+    let mut algos: HashMap<Address, i128> = accounts
+        .iter()
+        .map(|(address, account)| (*address, account.amount.0 as i128))
+        .collect();
+    let mut assets: HashMap<(Address, u64), i128> = accounts
+        .iter()
+        .flat_map(|(address, account)| {
+            account
+                .assets
+                .iter()
+                .map(move |holding| ((*address, holding.asset_id), holding.amount as i128))
+        })
+        .collect();
+    // running min balances: they increase with the opt-ins and creations in the group
+    let mut min_balances: HashMap<Address, i128> = accounts
+        .iter()
+        .map(|(address, account)| (*address, account.min_balance.0 as i128))
+        .collect();
+
+    for (index, tx) in txs.iter().enumerate() {
+        let sender = match tx_sender(tx) {
+            Some(sender) => sender,
+            // tx types we don't use
+            None => continue,
+        };
+        *algos.entry(sender).or_default() -= tx.fee.0 as i128;
+        *min_balances.entry(sender).or_default() += min_balance_increases
+            .iter()
+            .filter(|increase| increase.tx_index == index)
+            .map(|increase| increase.amount as i128)
+            .sum::<i128>();
+
+        match &tx.txn_type {
+            TransactionType::Payment(p) => {
+                *algos.entry(sender).or_default() -= p.amount.0 as i128;
+                *algos.entry(p.receiver).or_default() += p.amount.0 as i128;
+            }
+            TransactionType::AssetTransferTransaction(t) => {
+                let sender_holding = assets.entry((sender, t.xfer)).or_default();
+                *sender_holding -= t.amount as i128;
+                if *sender_holding < 0 {
+                    return Some(balance_failure(
+                        SimulationFailureKind::NotEnoughAsset,
+                        index,
+                        format!(
+                            "{sender} doesn't have enough of asset {}: missing {}",
+                            t.xfer, -*sender_holding
+                        ),
+                    ));
+                }
+                *assets.entry((t.receiver, t.xfer)).or_default() += t.amount as i128;
+            }
+            _ => {}
+        }
+
+        let min_balance = min_balances.get(&sender).cloned().unwrap_or(0);
+        let balance = algos.get(&sender).cloned().unwrap_or(0);
+        if balance < min_balance {
+            return Some(balance_failure(
+                SimulationFailureKind::Overspend,
+                index,
+                format!("{sender} would have {balance} microalgos, min balance: {min_balance}"),
+            ));
+        }
+    }
+
+    None
+}
+
+fn balance_failure(
+    kind: SimulationFailureKind,
+    tx_index: usize,
+    message: String,
+) -> SimulationFailureJs {
+    SimulationFailureJs {
+        kind,
+        tx_index,
+        app_id: None,
+        messages: vec![message],
+    }
+}
+
+async fn dryrun(
+    algod: &Algod,
+    txs: &[Transaction],
+    accounts: Vec<Account>,
+) -> Result<Option<SimulationFailureJs>> {
+    let mut apps: Vec<Application> = vec![];
+    for app_id in txs.iter().flat_map(|tx| match &tx.txn_type {
+        TransactionType::ApplicationCallTransaction(call) => app_ids(call),
+        _ => vec![],
+    }) {
+        if !apps.iter().any(|app| app.id == app_id) {
+            apps.push(algod.application_information(app_id).await?);
+        }
+    }
+
+    let request = DryrunRequest {
+        accounts,
+        apps,
+        latest_timestamp: Utc::now().timestamp() as u64,
+        protocol_version: "".to_owned(),
+        round: algod.status().await?.last_round,
+        sources: vec![],
+        // dryrun doesn't check the signatures
+        txns: txs.iter().map(unsigned).collect::<Result<_>>()?,
+    };
+
+    let response = algod.dryrun_teal(&request).await?;
+    log::debug!("Dryrun response: {response:?}");
+
+    Ok(dryrun_failure(txs, &response))
+}
+
+fn dryrun_failure(txs: &[Transaction], response: &DryrunResponse) -> Option<SimulationFailureJs> {
+    for (index, res) in response.txns.iter().enumerate() {
+        let messages = res.app_call_messages.clone().unwrap_or_default();
+        if messages.iter().any(|m| m == "REJECT") {
+            let app_id = txs.get(index).and_then(|tx| match &tx.txn_type {
+                TransactionType::ApplicationCallTransaction(call) => call.app_id,
+                _ => None,
+            });
+            return Some(SimulationFailureJs {
+                kind: SimulationFailureKind::AppRejected,
+                tx_index: index,
+                app_id: app_id.map(|id| id.to_string()),
+                messages,
+            });
+        }
+    }
+    if !response.error.is_empty() {
+        return Some(SimulationFailureJs {
+            kind: SimulationFailureKind::AppRejected,
+            tx_index: 0,
+            app_id: None,
+            messages: vec![response.error.clone()],
+        });
+    }
+    None
+}
+
+/// Called app and foreign apps
+fn app_ids(call: &ApplicationCallTransaction) -> Vec<u64> {
+    call.app_id
+        .into_iter()
+        .chain(call.foreign_apps.iter().flatten().cloned())
+        .collect()
+}
+
+fn unsigned(tx: &Transaction) -> Result<SignedTransaction> {
+    Ok(SignedTransaction {
+        transaction: tx.clone(),
+        transaction_id: tx.id().map_err(Error::msg)?,
+        sig: TransactionSignature::Single(Signature([0; 64])),
+        auth_address: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dependencies::algod,
+        fake_node::{
+            fixtures::{payment_txs, CREATOR, INVESTOR},
+            FakeChain, FakeNode,
+        },
+        service::tx_costs::MinBalanceReason,
+    };
+
+    /// Min balance of an account without assets and apps
+    const MIN_BALANCE: u64 = 100_000;
+
+    #[tokio::test]
+    async fn simulate_succeeds_if_the_balances_are_sufficient() {
+        let mut chain = FakeChain::new();
+        chain.set_algos(INVESTOR, 10_000_000);
+        let _node = FakeNode::start(chain).unwrap();
+        let algod = algod();
+        let txs = payment_txs(&algod, &[(INVESTOR, CREATOR, 1_000_000)]).await;
+
+        let simulation = simulate(&algod, &txs).await;
+
+        assert_eq!(SimulationStatus::Success, simulation.status);
+        assert!(simulation.failure.is_none());
+        assert_eq!(txs[0].fee.0.to_string(), simulation.total_fee_number);
+    }
+
+    #[tokio::test]
+    async fn check_balances_includes_min_balance_increases() {
+        let node = FakeNode::start(FakeChain::new()).unwrap();
+        let algod = algod();
+        let txs = payment_txs(&algod, &[(INVESTOR, CREATOR, 1_000_000)]).await;
+        // after paying, 50_000 above the min balance
+        node.chain()
+            .lock()
+            .unwrap()
+            .set_algos(INVESTOR, MIN_BALANCE + 1_000_000 + txs[0].fee.0 + 50_000);
+        let accounts = fetch_accounts(&algod, &txs).await.unwrap();
+
+        assert!(check_balances(&txs, &accounts, &[]).is_none());
+
+        let increases = [MinBalanceIncrease {
+            tx_index: 0,
+            reason: MinBalanceReason::AssetOptIn,
+            amount: 100_000,
+        }];
+        let failure = check_balances(&txs, &accounts, &increases).unwrap();
+        assert_eq!(SimulationFailureKind::Overspend, failure.kind);
+        assert_eq!(0, failure.tx_index);
+    }
+
+    #[tokio::test]
+    async fn check_balances_fails_at_the_overspending_tx() {
+        let node = FakeNode::start(FakeChain::new()).unwrap();
+        let algod = algod();
+        // the investor can pay only the first one
+        let txs = payment_txs(
+            &algod,
+            &[
+                (INVESTOR, CREATOR, 1_000_000),
+                (INVESTOR, CREATOR, 1_000_001),
+            ],
+        )
+        .await;
+        node.chain()
+            .lock()
+            .unwrap()
+            .set_algos(INVESTOR, MIN_BALANCE + 1_500_000);
+        let accounts = fetch_accounts(&algod, &txs).await.unwrap();
+
+        let failure = check_balances(&txs, &accounts, &[]).unwrap();
+
+        assert_eq!(SimulationFailureKind::Overspend, failure.kind);
+        assert_eq!(1, failure.tx_index);
+    }
+
+    #[tokio::test]
+    async fn simulate_is_unavailable_if_the_node_fails() {
+        let node = FakeNode::start(FakeChain::new()).unwrap();
+        let algod = algod();
+        let txs = payment_txs(&algod, &[(INVESTOR, CREATOR, 1_000_000)]).await;
+        // the client still points to the stopped node
+        drop(node);
+
+        let simulation = simulate(&algod, &txs).await;
+
+        assert_eq!(SimulationStatus::Unavailable, simulation.status);
+        assert!(simulation.failure.is_none());
+    }
+}
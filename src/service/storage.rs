@@ -1,7 +1,10 @@
-use anyhow::{anyhow, Error, Result};
+use anyhow::Result;
 use serde::{de::DeserializeOwned, Serialize};
-use wasm_bindgen::JsValue;
-use web_sys::Storage;
+
+#[cfg(target_arch = "wasm32")]
+use self::local_storage::{storage, to_anyhow};
+#[cfg(not(target_arch = "wasm32"))]
+use self::memory_storage::{storage, to_anyhow};
 
 pub fn storage_get_str(key: &str) -> Result<Option<String>> {
     log::debug!("Will retrieve local storage key: {key}");
@@ -32,20 +35,74 @@ where
     })
 }
 
+pub fn storage_remove(key: &str) -> Result<()> {
+    storage()?.remove_item(key).map_err(to_anyhow)
+}
+
 #[allow(dead_code)] // can be used only for debugging
 pub fn storage_clear_all() -> Result<()> {
     log::debug!("Will clear all local storage..");
     storage()?.clear().map_err(to_anyhow)
 }
 
-fn storage() -> Result<Storage> {
-    web_sys::window()
-        .ok_or(anyhow!("Unexpected: no window"))?
-        .local_storage()
-        .map_err(to_anyhow)?
-        .ok_or(anyhow!("Unexpected: no storage"))
+#[cfg(target_arch = "wasm32")]
+mod local_storage {
+    use anyhow::{anyhow, Error, Result};
+    use wasm_bindgen::JsValue;
+    use web_sys::Storage;
+
+    pub fn storage() -> Result<Storage> {
+        web_sys::window()
+            .ok_or(anyhow!("Unexpected: no window"))?
+            .local_storage()
+            .map_err(to_anyhow)?
+            .ok_or(anyhow!("Unexpected: no storage"))
+    }
+
+    pub fn to_anyhow(value: JsValue) -> anyhow::Error {
+        Error::msg(format!("{value:?}"))
+    }
 }
 
-fn to_anyhow(value: JsValue) -> anyhow::Error {
-    Error::msg(format!("{value:?}"))
+/// Natively (tests) there's no local storage - we use an in-memory map with the same interface
+#[cfg(not(target_arch = "wasm32"))]
+mod memory_storage {
+    use anyhow::Result;
+    use std::{cell::RefCell, collections::HashMap, convert::Infallible};
+
+    thread_local! {
+        static STORAGE: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    }
+
+    pub struct MemoryStorage {}
+
+    pub fn storage() -> Result<MemoryStorage> {
+        Ok(MemoryStorage {})
+    }
+
+    pub fn to_anyhow(e: Infallible) -> anyhow::Error {
+        match e {}
+    }
+
+    impl MemoryStorage {
+        pub fn get_item(&self, key: &str) -> Result<Option<String>, Infallible> {
+            Ok(STORAGE.with(|s| s.borrow().get(key).cloned()))
+        }
+
+        pub fn set_item(&self, key: &str, value: &str) -> Result<(), Infallible> {
+            STORAGE.with(|s| s.borrow_mut().insert(key.to_owned(), value.to_owned()));
+            Ok(())
+        }
+
+        pub fn remove_item(&self, key: &str) -> Result<(), Infallible> {
+            STORAGE.with(|s| s.borrow_mut().remove(key));
+            Ok(())
+        }
+
+        #[allow(dead_code)] // see storage_clear_all
+        pub fn clear(&self) -> Result<(), Infallible> {
+            STORAGE.with(|s| s.borrow_mut().clear());
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,412 @@
+use super::{number_formats::microalgos_to_algos_str, tx_message::tx_sender};
+use crate::locale::locale;
+use algonaut::{
+    algod::v2::Algod,
+    core::{Address, MicroAlgos},
+    transaction::{
+        transaction::{OnCompletion, TransactionType},
+        Transaction,
+    },
+};
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt::Display;
+use tsify::Tsify;
+
+// https://developer.algorand.org/docs/get-details/parameter_tables/#minimum-balance
+const MIN_BALANCE_ASSET: u64 = 100_000;
+const MIN_BALANCE_APP: u64 = 100_000;
+const MIN_BALANCE_APP_EXTRA_PAGE: u64 = 100_000;
+const MIN_BALANCE_UINT: u64 = 28_500;
+const MIN_BALANCE_BYTE_SLICE: u64 = 50_000;
+
+/// What signing the txs costs, in algos
+#[derive(Tsify, Debug, Clone, Serialize)]
+pub struct CostBreakdownJs {
+    /// Fee of each tx (same order as the txs)
+    pub fees: Vec<String>,
+    /// The costs of each sender in the group, in order of their first tx.
+    /// Usually only the connected account, but e.g. groups can contain txs of the app account.
+    pub senders: Vec<SenderCostsJs>,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+pub struct SenderCostsJs {
+    pub address: String,
+    pub total_fees: String,
+    /// Algos locked by opt-ins, created apps and assets (the account's min balance increases)
+    pub min_balance_increases: Vec<MinBalanceIncreaseJs>,
+    pub total_min_balance_increase: String,
+    /// Algos sent with payments
+    pub payments: String,
+    /// Algos received with payments from other txs of the group
+    pub received: String,
+    /// fees + min balance increases + payments - received (0 if the sender receives more than it spends)
+    pub total: String,
+    pub total_number: String,
+}
+
+#[derive(Tsify, Debug, Clone, Serialize)]
+pub struct MinBalanceIncreaseJs {
+    pub tx_index: usize,
+    pub reason: MinBalanceReason,
+    pub amount: String,
+}
+
+#[derive(Tsify, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MinBalanceReason {
+    AssetOptIn,
+    AssetCreation,
+    AppOptIn,
+    AppCreation,
+}
+
+/// A sender's available algos (balance - current min balance) don't cover its costs of the txs
+#[derive(Debug, Clone)]
+pub struct NotEnoughAlgosError {
+    pub address: Address,
+    pub available: MicroAlgos,
+    pub cost: MicroAlgos,
+}
+
+impl Display for NotEnoughAlgosError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Not enough algos to sign the txs. Account: {}, available: {}, cost: {} (microalgos)",
+            self.address, self.available.0, self.cost.0
+        )
+    }
+}
+
+impl std::error::Error for NotEnoughAlgosError {}
+
+/// The algos a sender of a group spends and receives (microalgos)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SenderCosts {
+    pub address: Address,
+    pub fees: u64,
+    pub min_balance_increases: Vec<MinBalanceIncrease>,
+    pub payments: u64,
+    pub received: u64,
+}
+
+impl SenderCosts {
+    fn new(address: Address) -> SenderCosts {
+        SenderCosts {
+            address,
+            fees: 0,
+            min_balance_increases: vec![],
+            payments: 0,
+            received: 0,
+        }
+    }
+
+    pub fn total_min_balance_increase(&self) -> u64 {
+        self.min_balance_increases.iter().map(|i| i.amount).sum()
+    }
+
+    /// What the sender needs to have available to sign the txs
+    pub fn total(&self) -> MicroAlgos {
+        MicroAlgos(
+            (self.fees + self.total_min_balance_increase() + self.payments)
+                .saturating_sub(self.received),
+        )
+    }
+}
+
+/// Min balance increase caused by a tx (for its sender)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinBalanceIncrease {
+    pub tx_index: usize,
+    pub reason: MinBalanceReason,
+    pub amount: u64,
+}
+
+/// The min balance increases caused by the txs: opt-ins, created apps and assets.
+/// Shared by the cost breakdown and the simulation.
+pub async fn min_balance_increases(
+    algod: &Algod,
+    txs: &[Transaction],
+) -> Result<Vec<MinBalanceIncrease>> {
+    let mut increases = vec![];
+
+    for (tx_index, tx) in txs.iter().enumerate() {
+        let mut increase = |reason, amount| {
+            increases.push(MinBalanceIncrease {
+                tx_index,
+                reason,
+                amount,
+            });
+        };
+        match &tx.txn_type {
+            TransactionType::AssetAcceptTransaction(_) => {
+                increase(MinBalanceReason::AssetOptIn, MIN_BALANCE_ASSET)
+            }
+            TransactionType::AssetConfigurationTransaction(config)
+                if config.config_asset.is_none() =>
+            {
+                increase(MinBalanceReason::AssetCreation, MIN_BALANCE_ASSET)
+            }
+            TransactionType::ApplicationCallTransaction(call) => match call.app_id {
+                None => {
+                    let schema = call.global_state_schema.as_ref();
+                    increase(
+                        MinBalanceReason::AppCreation,
+                        MIN_BALANCE_APP
+                            + MIN_BALANCE_APP_EXTRA_PAGE * call.extra_pages as u64
+                            + schema_cost(
+                                schema.map(|s| s.number_ints).unwrap_or(0),
+                                schema.map(|s| s.number_byteslices).unwrap_or(0),
+                            ),
+                    )
+                }
+                Some(app_id) if matches!(call.on_complete, OnCompletion::OptIn) => {
+                    let app = algod.application_information(app_id).await?;
+                    let schema = app.params.local_state_schema.as_ref();
+                    increase(
+                        MinBalanceReason::AppOptIn,
+                        MIN_BALANCE_APP
+                            + schema_cost(
+                                schema.map(|s| s.num_uint).unwrap_or(0),
+                                schema.map(|s| s.num_byte_slice).unwrap_or(0),
+                            ),
+                    )
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    Ok(increases)
+}
+
+/// The costs of the txs grouped by sender (see [sender_costs])
+pub async fn txs_sender_costs(algod: &Algod, txs: &[Transaction]) -> Result<Vec<SenderCosts>> {
+    let min_balance_increases = min_balance_increases(algod, txs).await?;
+    Ok(sender_costs(txs, &min_balance_increases))
+}
+
+/// Groups the fees, min balance increases and payments of the txs by sender, in order of the senders' first tx.
+/// Payments to a sender of the group are counted as received by it.
+pub fn sender_costs(
+    txs: &[Transaction],
+    min_balance_increases: &[MinBalanceIncrease],
+) -> Vec<SenderCosts> {
+    let mut costs: Vec<SenderCosts> = vec![];
+
+    for (tx_index, tx) in txs.iter().enumerate() {
+        let sender = match tx_sender(tx) {
+            Some(sender) => sender,
+            // tx types we don't use
+            None => continue,
+        };
+        let sender_index = match costs.iter().position(|c| c.address == sender) {
+            Some(index) => index,
+            None => {
+                costs.push(SenderCosts::new(sender));
+                costs.len() - 1
+            }
+        };
+        let sender_costs = &mut costs[sender_index];
+        sender_costs.fees += tx.fee.0;
+        sender_costs.min_balance_increases.extend(
+            min_balance_increases
+                .iter()
+                .filter(|increase| increase.tx_index == tx_index)
+                .copied(),
+        );
+        if let TransactionType::Payment(payment) = &tx.txn_type {
+            sender_costs.payments += payment.amount.0;
+        }
+    }
+
+    // only the group's senders: receivers outside of it don't sign anything
+    for tx in txs {
+        if let TransactionType::Payment(payment) = &tx.txn_type {
+            if let Some(receiver) = costs.iter_mut().find(|c| c.address == payment.receiver) {
+                receiver.received += payment.amount.0;
+            }
+        }
+    }
+
+    costs
+}
+
+pub fn cost_breakdown(txs: &[Transaction], sender_costs: &[SenderCosts]) -> CostBreakdownJs {
+    let locale = locale();
+    let algos = |micro_algos: u64| microalgos_to_algos_str(MicroAlgos(micro_algos), &locale);
+
+    CostBreakdownJs {
+        fees: txs.iter().map(|tx| algos(tx.fee.0)).collect(),
+        senders: sender_costs
+            .iter()
+            .map(|costs| SenderCostsJs {
+                address: costs.address.to_string(),
+                total_fees: algos(costs.fees),
+                min_balance_increases: costs
+                    .min_balance_increases
+                    .iter()
+                    .map(|increase| MinBalanceIncreaseJs {
+                        tx_index: increase.tx_index,
+                        reason: increase.reason,
+                        amount: algos(increase.amount),
+                    })
+                    .collect(),
+                total_min_balance_increase: algos(costs.total_min_balance_increase()),
+                payments: algos(costs.payments),
+                received: algos(costs.received),
+                total: algos(costs.total().0),
+                total_number: costs.total().0.to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// Fails with [NotEnoughAlgosError] if a sender can't pay its costs.
+pub async fn preflight_check(algod: &Algod, sender_costs: &[SenderCosts]) -> Result<()> {
+    for costs in sender_costs {
+        let cost = costs.total();
+        if cost.0 == 0 {
+            continue;
+        }
+        let account = algod.account_information(&costs.address).await?;
+        let available = MicroAlgos(account.amount.0.saturating_sub(account.min_balance.0));
+        if available.0 < cost.0 {
+            return Err(NotEnoughAlgosError {
+                address: costs.address,
+                available,
+                cost,
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+fn schema_cost(uints: u64, byte_slices: u64) -> u64 {
+    MIN_BALANCE_UINT * uints + MIN_BALANCE_BYTE_SLICE * byte_slices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dependencies::algod,
+        fake_node::{
+            fixtures::{app_address, payment_txs, CREATOR, INVESTOR},
+            FakeChain, FakeNode,
+        },
+    };
+
+    const FEE: u64 = 1_000;
+
+    fn address(address: &str) -> Address {
+        address.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn sender_costs_groups_the_costs_by_sender() {
+        let _node = FakeNode::start(FakeChain::new()).unwrap();
+        let app = app_address();
+        let app = app.as_str();
+        let txs = payment_txs(
+            &algod(),
+            &[
+                (CREATOR, app, 300_000),
+                (app, INVESTOR, 50_000),
+                (CREATOR, INVESTOR, 1_000),
+            ],
+        )
+        .await;
+        let opt_in = MinBalanceIncrease {
+            tx_index: 1,
+            reason: MinBalanceReason::AssetOptIn,
+            amount: MIN_BALANCE_ASSET,
+        };
+
+        let costs = sender_costs(&txs, &[opt_in]);
+
+        assert_eq!(
+            costs,
+            vec![
+                SenderCosts {
+                    address: address(CREATOR),
+                    fees: 2 * FEE,
+                    min_balance_increases: vec![],
+                    payments: 301_000,
+                    received: 0,
+                },
+                SenderCosts {
+                    address: address(app),
+                    fees: FEE,
+                    min_balance_increases: vec![opt_in],
+                    payments: 50_000,
+                    received: 300_000,
+                },
+            ]
+        );
+        assert_eq!(costs[0].total(), MicroAlgos(2 * FEE + 301_000));
+        // receives more than it spends
+        assert_eq!(costs[1].total(), MicroAlgos(0));
+    }
+
+    #[test]
+    fn sender_costs_total_includes_the_min_balance_increases() {
+        let costs = SenderCosts {
+            address: address(CREATOR),
+            fees: FEE,
+            min_balance_increases: vec![
+                MinBalanceIncrease {
+                    tx_index: 0,
+                    reason: MinBalanceReason::AppCreation,
+                    amount: MIN_BALANCE_APP + schema_cost(2, 1),
+                },
+                MinBalanceIncrease {
+                    tx_index: 1,
+                    reason: MinBalanceReason::AssetOptIn,
+                    amount: MIN_BALANCE_ASSET,
+                },
+            ],
+            payments: 10,
+            received: 5,
+        };
+
+        assert_eq!(costs.total_min_balance_increase(), 307_000);
+        assert_eq!(costs.total(), MicroAlgos(FEE + 307_000 + 10 - 5));
+    }
+
+    #[tokio::test]
+    async fn preflight_check_checks_each_sender() {
+        let app = app_address();
+        let app = app.as_str();
+        let mut chain = FakeChain::new();
+        chain.set_algos(CREATOR, 10_000_000);
+        // only the min balance
+        chain.set_algos(app, 100_000);
+        let _node = FakeNode::start(chain).unwrap();
+        let algod = algod();
+
+        let txs = payment_txs(
+            &algod,
+            &[(CREATOR, INVESTOR, 1_000), (app, INVESTOR, 500_000)],
+        )
+        .await;
+        let error = preflight_check(&algod, &sender_costs(&txs, &[]))
+            .await
+            .unwrap_err()
+            .downcast::<NotEnoughAlgosError>()
+            .unwrap();
+        assert_eq!(error.address, address(app));
+        assert_eq!(error.available, MicroAlgos(0));
+        assert_eq!(error.cost, MicroAlgos(500_000 + FEE));
+
+        // the creator funds the app account in the same group
+        let txs = payment_txs(&algod, &[(CREATOR, app, 501_000), (app, INVESTOR, 500_000)]).await;
+        assert!(preflight_check(&algod, &sender_costs(&txs, &[]))
+            .await
+            .is_ok());
+    }
+}
@@ -0,0 +1,43 @@
+use super::number_formats::microalgos_to_algos_str;
+use crate::locale::locale;
+use algonaut::{
+    core::Address,
+    transaction::{transaction::TransactionType, Transaction},
+};
+
+/// Generic description of a tx, for txs the providers don't describe themselves.
+/// Shown by the wallets when signing.
+pub fn default_tx_message(tx: &Transaction) -> String {
+    match &tx.txn_type {
+        TransactionType::Payment(payment) => format!(
+            "Pay {} Algo to {}",
+            microalgos_to_algos_str(payment.amount, &locale()),
+            payment.receiver
+        ),
+        TransactionType::AssetTransferTransaction(xfer) => format!(
+            "Transfer {} units of asset {} to {}",
+            xfer.amount, xfer.xfer, xfer.receiver
+        ),
+        TransactionType::AssetAcceptTransaction(accept) => {
+            format!("Opt in to asset {}", accept.xfer)
+        }
+        TransactionType::ApplicationCallTransaction(call) => match call.app_id {
+            Some(app_id) => format!("Call application {app_id}"),
+            None => "Create application".to_owned(),
+        },
+        TransactionType::AssetConfigurationTransaction(_) => "Create or configure asset".to_owned(),
+        _ => "Sign transaction".to_owned(),
+    }
+}
+
+/// None for tx types we don't handle (not used in the app)
+pub fn tx_sender(tx: &Transaction) -> Option<Address> {
+    match &tx.txn_type {
+        TransactionType::Payment(payment) => Some(payment.sender),
+        TransactionType::AssetTransferTransaction(xfer) => Some(xfer.sender),
+        TransactionType::AssetAcceptTransaction(accept) => Some(accept.sender),
+        TransactionType::ApplicationCallTransaction(call) => Some(call.sender),
+        TransactionType::AssetConfigurationTransaction(config) => Some(config.sender),
+        _ => None,
+    }
+}
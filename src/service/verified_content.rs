@@ -0,0 +1,360 @@
+//! Fetches the DAO's off-chain content (description, image, team, prospectus), checking it against its on-chain hash.
+//!
+//! The prospectus hash is saved in the app state and the image hash in the image NFT's metadata hash.
+//! For the other content, the hash is saved in the url (see [super::content_hash]),
+//! when creating / updating the DAO or setting the team.
+
+use super::content_hash::{
+    check_content_hash, content_status, expected_content_hash, url_with_content_hash,
+    url_without_fragment,
+};
+use algonaut::{algod::v2::Algod, crypto::HashDigest};
+use anyhow::{anyhow, Result};
+use base::{api::fetcher::Fetcher, dependencies::fetcher};
+use data_encoding::BASE64;
+use mbase::models::hashable::hash;
+use serde::Serialize;
+use std::convert::TryInto;
+use tsify::Tsify;
+
+#[derive(Tsify, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ContentStatusJs {
+    /// The content matches the on-chain hash
+    Verified,
+    /// The content doesn't match the on-chain hash (e.g. the host changed it). The content isn't returned.
+    Changed,
+    /// There's no on-chain hash (content saved before hashes were supported)
+    Unverified,
+    /// The content couldn't be fetched
+    Unreachable,
+}
+
+#[derive(Debug, Clone)]
+pub struct VerifiedContent {
+    /// None if changed or unreachable
+    pub bytes: Option<Vec<u8>>,
+    pub status: ContentStatusJs,
+}
+
+impl VerifiedContent {
+    fn without_bytes(status: ContentStatusJs) -> VerifiedContent {
+        VerifiedContent {
+            bytes: None,
+            status,
+        }
+    }
+}
+
+/// `saved_hash`: the on-chain hash, if it's not in the url (prospectus)
+pub async fn fetch_verified(url: &str, saved_hash: Option<&str>) -> VerifiedContent {
+    let fetcher = fetcher();
+    let fetched = fetcher.get(url_without_fragment(url)).await;
+    verified(url, saved_hash, fetched)
+}
+
+fn verified(url: &str, saved_hash: Option<&str>, fetched: Result<Vec<u8>>) -> VerifiedContent {
+    let bytes = match fetched {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::error!("Couldn't fetch content at: {url}: {e:?}");
+            return VerifiedContent::without_bytes(ContentStatusJs::Unreachable);
+        }
+    };
+
+    // saved outside of the url (prospectus, image), or in the url
+    let status = content_status(saved_hash.or_else(|| expected_content_hash(url)), &bytes);
+    match status {
+        ContentStatusJs::Changed => {
+            log::error!("Content at: {url} doesn't match its on-chain hash");
+            VerifiedContent::without_bytes(status)
+        }
+        _ => VerifiedContent {
+            bytes: Some(bytes),
+            status,
+        },
+    }
+}
+
+/// [fetch_verified] for optional content (None if the DAO doesn't have it), with the hash in the url
+pub async fn fetch_verified_opt(url: Option<&str>) -> Option<VerifiedContent> {
+    match url {
+        Some(url) => Some(fetch_verified(url, None).await),
+        None => None,
+    }
+}
+
+/// [fetch_verified_opt] for the image: `nft` is (asset id, url).
+/// The hash is the NFT's metadata hash. Images saved before have it in the url instead.
+pub async fn fetch_verified_image_opt(
+    algod: &Algod,
+    nft: Option<(u64, &str)>,
+) -> Option<VerifiedContent> {
+    match nft {
+        Some((asset_id, url)) => {
+            let saved_hash = nft_metadata_hash(algod, asset_id).await;
+            Some(fetch_verified(url, saved_hash.as_deref()).await)
+        }
+        None => None,
+    }
+}
+
+/// [verify_opt] for the image, see [fetch_verified_image_opt]
+pub async fn verify_image_opt(algod: &Algod, nft: Option<(u64, &str)>) -> Option<ContentStatusJs> {
+    fetch_verified_image_opt(algod, nft)
+        .await
+        .map(|content| content.status)
+}
+
+/// Base64, like [super::content_hash::content_hash]. None if the NFT has no metadata hash or couldn't be fetched.
+async fn nft_metadata_hash(algod: &Algod, asset_id: u64) -> Option<String> {
+    match algod.asset_information(asset_id).await {
+        Ok(asset) => asset
+            .params
+            .metadata_hash
+            .filter(|hash| !hash.is_empty())
+            .map(|hash| BASE64.encode(&hash)),
+        Err(e) => {
+            log::error!("Couldn't fetch image NFT: {asset_id}: {e:?}");
+            None
+        }
+    }
+}
+
+/// Status only, for optional content (None if the DAO doesn't have it)
+pub async fn verify_opt(url: Option<&str>, saved_hash: Option<&str>) -> Option<ContentStatusJs> {
+    match url {
+        Some(url) => Some(fetch_verified(url, saved_hash).await.status),
+        None => None,
+    }
+}
+
+/// The url to save on chain: with the hash of the content currently at the url.
+/// Fails if the url already has a hash and the content changed, so owners don't save changed content unknowingly.
+pub async fn url_with_fetched_content_hash(url: &str) -> Result<String> {
+    let fetcher = fetcher();
+    let bytes = fetcher.get(url_without_fragment(url)).await?;
+    url_with_unchanged_content_hash(url, &bytes)
+}
+
+fn url_with_unchanged_content_hash(url: &str, bytes: &[u8]) -> Result<String> {
+    if check_content_hash(url, bytes) == ContentStatusJs::Changed {
+        return Err(anyhow!(
+            "The content at: {url} changed since it was saved. Please upload it again."
+        ));
+    }
+    Ok(url_with_content_hash(url, bytes))
+}
+
+/// [url_with_fetched_content_hash] for optional urls
+pub async fn url_with_fetched_content_hash_opt(url: Option<String>) -> Result<Option<String>> {
+    Ok(match url {
+        Some(url) => Some(url_with_fetched_content_hash(&url).await?),
+        None => None,
+    })
+}
+
+/// [url_with_fetched_content_hash_opt], if the url isn't the saved one.
+/// Otherwise the saved url is returned as is, with its hash (if it has one): the content isn't fetched again.
+pub async fn url_with_fetched_content_hash_if_changed(
+    url: Option<String>,
+    saved_url: Option<&str>,
+) -> Result<Option<String>> {
+    match (url, saved_url) {
+        (Some(url), Some(saved_url))
+            if url_without_fragment(&url) == url_without_fragment(saved_url)
+                && expected_content_hash(saved_url).is_some() =>
+        {
+            Ok(Some(saved_url.to_owned()))
+        }
+        (url, _) => url_with_fetched_content_hash_opt(url).await,
+    }
+}
+
+/// [fetched_content_hash_opt] for the image, if the url isn't the one of the current image NFT (`nft`: (asset id, url)).
+/// Otherwise it's the NFT's metadata hash: the image isn't fetched again.
+pub async fn image_hash_if_changed(
+    algod: &Algod,
+    url: Option<&str>,
+    nft: Option<(u64, &str)>,
+) -> Result<Option<HashDigest>> {
+    if let (Some(url), Some((asset_id, nft_url))) = (url, nft) {
+        if url == nft_url {
+            if let Some(saved_hash) = nft_metadata_hash_digest(algod, asset_id).await {
+                return Ok(Some(saved_hash));
+            }
+        }
+    }
+    fetched_content_hash_opt(url).await
+}
+
+async fn nft_metadata_hash_digest(algod: &Algod, asset_id: u64) -> Option<HashDigest> {
+    let hash = BASE64
+        .decode(nft_metadata_hash(algod, asset_id).await?.as_bytes())
+        .ok()?;
+    Some(HashDigest(hash.try_into().ok()?))
+}
+
+/// The hash of the content currently at the url, to save as the image NFT's metadata hash
+pub async fn fetched_content_hash_opt(url: Option<&str>) -> Result<Option<HashDigest>> {
+    Ok(match url {
+        Some(url) => {
+            let fetcher = fetcher();
+            let bytes = fetcher.get(url_without_fragment(url)).await?;
+            Some(hash(&bytes))
+        }
+        None => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dependencies::algod,
+        fake_node::{
+            fixtures::{dao_chain, CREATOR},
+            FakeNode,
+        },
+        service::content_hash::content_hash,
+    };
+
+    /// Not reachable: the tests that pass it don't fetch
+    const URL: &str = "https://content.invalid/descr";
+    const IMAGE_ASSET_ID: u64 = 20;
+
+    fn status(content: &VerifiedContent) -> (ContentStatusJs, Option<&[u8]>) {
+        (content.status, content.bytes.as_deref())
+    }
+
+    #[test]
+    fn verified_checks_the_content_against_the_hash_in_the_url() {
+        let url = url_with_content_hash(URL, b"descr");
+
+        assert_eq!(
+            (ContentStatusJs::Verified, Some(&b"descr"[..])),
+            status(&verified(&url, None, Ok(b"descr".to_vec())))
+        );
+        assert_eq!(
+            (ContentStatusJs::Changed, None),
+            status(&verified(&url, None, Ok(b"changed".to_vec())))
+        );
+    }
+
+    #[test]
+    fn verified_checks_the_content_against_the_saved_hash() {
+        let saved_hash = content_hash(b"prospectus");
+
+        assert_eq!(
+            (ContentStatusJs::Verified, Some(&b"prospectus"[..])),
+            status(&verified(
+                URL,
+                Some(&saved_hash),
+                Ok(b"prospectus".to_vec())
+            ))
+        );
+        assert_eq!(
+            (ContentStatusJs::Changed, None),
+            status(&verified(URL, Some(&saved_hash), Ok(b"changed".to_vec())))
+        );
+    }
+
+    #[test]
+    fn verified_returns_content_without_hash_as_unverified() {
+        assert_eq!(
+            (ContentStatusJs::Unverified, Some(&b"legacy"[..])),
+            status(&verified(URL, None, Ok(b"legacy".to_vec())))
+        );
+    }
+
+    #[test]
+    fn verified_returns_unreachable_if_the_content_couldnt_be_fetched() {
+        let url = url_with_content_hash(URL, b"descr");
+
+        assert_eq!(
+            (ContentStatusJs::Unreachable, None),
+            status(&verified(&url, None, Err(anyhow!("Not found"))))
+        );
+    }
+
+    #[test]
+    fn url_with_unchanged_content_hash_refuses_changed_content() {
+        let saved_url = url_with_content_hash(URL, b"descr");
+
+        assert!(url_with_unchanged_content_hash(&saved_url, b"changed").is_err());
+        assert_eq!(
+            saved_url,
+            url_with_unchanged_content_hash(&saved_url, b"descr").unwrap()
+        );
+        // without a hash (legacy or new url), the hash is added
+        assert_eq!(
+            saved_url,
+            url_with_unchanged_content_hash(URL, b"descr").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn url_with_fetched_content_hash_if_changed_keeps_the_saved_url() {
+        let saved_url = url_with_content_hash(URL, b"descr");
+
+        // the url can't be fetched: this would fail if it was fetched
+        assert_eq!(
+            Some(saved_url.clone()),
+            url_with_fetched_content_hash_if_changed(Some(URL.to_owned()), Some(&saved_url))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            None,
+            url_with_fetched_content_hash_if_changed(None, Some(&saved_url))
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn image_is_verified_with_the_nft_metadata_hash() {
+        let mut chain = dao_chain();
+        chain.add_asset(IMAGE_ASSET_ID, CREATOR, "IMG", 0, 1);
+        chain.set_asset_metadata_hash(IMAGE_ASSET_ID, &hash(b"image").0);
+        chain.add_asset(IMAGE_ASSET_ID + 1, CREATOR, "IMG", 0, 1);
+        let _node = FakeNode::start(chain).unwrap();
+        let algod = algod();
+
+        let saved_hash = nft_metadata_hash(&algod, IMAGE_ASSET_ID).await;
+        assert_eq!(Some(content_hash(b"image")), saved_hash);
+        assert_eq!(
+            ContentStatusJs::Verified,
+            verified(URL, saved_hash.as_deref(), Ok(b"image".to_vec())).status
+        );
+        assert_eq!(
+            ContentStatusJs::Changed,
+            verified(URL, saved_hash.as_deref(), Ok(b"changed".to_vec())).status
+        );
+
+        // without metadata hash, or not found: the hash in the url is used, if any
+        assert_eq!(None, nft_metadata_hash(&algod, IMAGE_ASSET_ID + 1).await);
+        assert_eq!(None, nft_metadata_hash(&algod, IMAGE_ASSET_ID + 2).await);
+    }
+
+    #[tokio::test]
+    async fn image_hash_if_changed_keeps_the_nft_metadata_hash() {
+        let mut chain = dao_chain();
+        chain.add_asset(IMAGE_ASSET_ID, CREATOR, "IMG", 0, 1);
+        chain.set_asset_metadata_hash(IMAGE_ASSET_ID, &hash(b"image").0);
+        let _node = FakeNode::start(chain).unwrap();
+
+        // the url can't be fetched: this would fail if it was fetched
+        let hash_digest = image_hash_if_changed(&algod(), Some(URL), Some((IMAGE_ASSET_ID, URL)))
+            .await
+            .unwrap();
+
+        assert_eq!(Some(hash(b"image").0), hash_digest.map(|h| h.0));
+        assert_eq!(
+            None,
+            image_hash_if_changed(&algod(), None, Some((IMAGE_ASSET_ID, URL)))
+                .await
+                .unwrap()
+                .map(|h| h.0)
+        );
+    }
+}
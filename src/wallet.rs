@@ -0,0 +1,80 @@
+use crate::service::storage::{storage_get, storage_remove, storage_set};
+use algonaut::core::Address;
+use anyhow::{Error, Result};
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+/// Local storage key for the connected wallet
+const WALLET_KEY: &str = "WALLET";
+
+#[derive(Tsify, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WalletType {
+    MyAlgo,
+    Pera,
+    Defly,
+    /// Any other wallet connected via WalletConnect
+    WalletConnect,
+    /// Wallets implementing ARC-1 `signTxns` (e.g. AlgoSigner, Exodus)
+    Arc1,
+}
+
+/// Format of the txs to sign expected by a wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignFormat {
+    MyAlgo,
+    WalletConnect,
+    Arc1,
+}
+
+impl WalletType {
+    pub fn sign_format(&self) -> SignFormat {
+        match self {
+            WalletType::MyAlgo => SignFormat::MyAlgo,
+            WalletType::Pera | WalletType::Defly | WalletType::WalletConnect => {
+                SignFormat::WalletConnect
+            }
+            WalletType::Arc1 => SignFormat::Arc1,
+        }
+    }
+}
+
+/// The connected wallet. Set by JS when connecting the wallet, cleared when disconnecting.
+#[derive(Tsify, Debug, Clone, Serialize, Deserialize)]
+#[tsify(from_wasm_abi, into_wasm_abi)]
+pub struct WalletSettings {
+    pub wallet_type: WalletType,
+    /// The connected account
+    pub address: Option<String>,
+    /// Set if the account is rekeyed: the address of the account that signs for it (ARC-1 `authAddr` hint)
+    pub auth_addr: Option<String>,
+    /// Set if the account is a multisig (ARC-1 `msig` hint)
+    pub msig: Option<MultisigHint>,
+}
+
+/// ARC-1 `MultisigMetadata`
+#[derive(Tsify, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigHint {
+    pub version: u8,
+    pub threshold: u8,
+    pub addrs: Vec<String>,
+}
+
+/// None: no wallet was set - txs to sign are returned in all formats
+pub fn wallet_settings() -> Result<Option<WalletSettings>> {
+    storage_get(WALLET_KEY)
+}
+
+/// None clears the wallet (disconnect)
+pub fn set_wallet_settings(settings: Option<&WalletSettings>) -> Result<()> {
+    match settings {
+        Some(settings) => {
+            // validate before saving
+            for address in settings.address.iter().chain(settings.auth_addr.iter()) {
+                address.parse::<Address>().map_err(Error::msg)?;
+            }
+            storage_set(WALLET_KEY, settings)
+        }
+        None => storage_remove(WALLET_KEY),
+    }
+}